@@ -0,0 +1,175 @@
+//! asciicast v2 recordings of agent sessions.
+//!
+//! A recording is a JSON header line followed by `[time, "o", output]` event lines,
+//! playable with `asciinema play` or the replay view.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Gaps between events longer than this are shortened on replay, in seconds
+const IDLE_LIMIT: f64 = 2.0;
+
+/// Recording next to a session log: `<id>.log` is recorded to `<id>.cast`.
+pub fn cast_path(log_file: &Path) -> PathBuf {
+    log_file.with_extension("cast")
+}
+
+/// First line of a recording
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    version: u8,
+    width: u16,
+    height: u16,
+    /// Start of the recording, in seconds since the Unix epoch
+    #[serde(default)]
+    timestamp: u64,
+}
+
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default()
+}
+
+/// Writes output events to a recording.
+pub struct CastWriter {
+    file: File,
+    /// Start of the recording, in seconds since the Unix epoch
+    start: f64,
+    /// Incomplete UTF-8 sequence at the end of the last chunk
+    pending: Vec<u8>,
+}
+
+impl CastWriter {
+    /// Open a recording, continuing an existing one so event times keep increasing.
+    pub fn open(path: &Path, width: u16, height: u16) -> std::io::Result<Self> {
+        let existing = File::open(path).ok().and_then(|file| {
+            let mut line = String::new();
+            BufReader::new(file).read_line(&mut line).ok()?;
+            serde_json::from_str::<Header>(&line).ok()
+        });
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        let start = match existing {
+            Some(header) => header.timestamp as f64,
+            None => {
+                let header = Header {
+                    version: 2,
+                    width,
+                    height,
+                    timestamp: now() as u64,
+                };
+                let line = serde_json::to_string(&header).map_err(std::io::Error::other)?;
+                writeln!(file, "{}", line)?;
+                header.timestamp as f64
+            }
+        };
+        Ok(Self {
+            file,
+            start,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Record a chunk of terminal output.
+    pub fn write(&mut self, output: &[u8]) -> std::io::Result<()> {
+        self.pending.extend_from_slice(output);
+        let complete = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            // A character split across chunks is completed by the next one
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        let rest = self.pending.split_off(complete);
+        let text = String::from_utf8_lossy(&self.pending).into_owned();
+        self.pending = rest;
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        let time = ((now() - self.start) * 1e6).round() / 1e6;
+        let line = serde_json::to_string(&(time, "o", text)).map_err(std::io::Error::other)?;
+        writeln!(self.file, "{}", line)
+    }
+}
+
+/// A recording loaded for replay
+#[derive(Debug, Clone)]
+pub struct Cast {
+    pub width: u16,
+    pub height: u16,
+    /// Output events with their replay time, idle gaps shortened
+    pub events: Vec<(f64, String)>,
+}
+
+impl Cast {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let file = File::open(path)
+            .map_err(|e| format!("No recording at {}: {}", path.display(), e))?;
+        let mut lines = BufReader::new(file).lines();
+        let header: Header = lines
+            .next()
+            .and_then(|l| l.ok())
+            .and_then(|l| serde_json::from_str(&l).ok())
+            .ok_or_else(|| format!("{} is not an asciicast recording", path.display()))?;
+
+        let mut events = Vec::new();
+        let (mut last, mut replay_time) = (0.0, 0.0);
+        for line in lines.map_while(Result::ok) {
+            // A line cut short by a crash is skipped
+            let Ok((time, kind, data)) = serde_json::from_str::<(f64, String, String)>(&line)
+            else {
+                continue;
+            };
+            if kind != "o" {
+                continue;
+            }
+            replay_time += (time - last).clamp(0.0, IDLE_LIMIT);
+            last = time;
+            events.push((replay_time, data));
+        }
+        Ok(Self {
+            width: header.width,
+            height: header.height,
+            events,
+        })
+    }
+
+    /// Replay time of the last event
+    pub fn duration(&self) -> f64 {
+        self.events.last().map_or(0.0, |(time, _)| *time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recording_round_trips_split_characters() {
+        let root = std::env::temp_dir().join(format!("assistant-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let path = cast_path(&root.join("a.log"));
+        assert_eq!(path, root.join("a.cast"));
+
+        let mut writer = CastWriter::open(&path, 80, 24).unwrap();
+        let text = "héllo\r\n".as_bytes();
+        writer.write(&text[..2]).unwrap();
+        writer.write(&text[2..]).unwrap();
+        drop(writer);
+        // Reopening continues the same recording
+        CastWriter::open(&path, 80, 24).unwrap().write(b"\x1b[1mdone").unwrap();
+
+        let cast = Cast::load(&path).unwrap();
+        assert_eq!((cast.width, cast.height), (80, 24));
+        let output: Vec<&str> = cast.events.iter().map(|(_, data)| data.as_str()).collect();
+        assert_eq!(output, vec!["h", "éllo\r\n", "\x1b[1mdone"]);
+        assert!(cast.events.windows(2).all(|w| w[0].0 <= w[1].0));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,170 @@
+//! Best-of-N: several agents attempting the same issue side by side.
+//!
+//! Each attempt runs in its own worktree and branch (`issue-<n>-a`, `issue-<n>-b`…) and
+//! terminal session, possibly with a different agent or model. Once they are done, one
+//! attempt is kept and the others are discarded with their worktrees and branches.
+
+use std::path::Path;
+
+use super::claude::{kill_session, launch_in_worktree, SessionLaunch};
+use super::monitor::{start_queued_sessions, watch_session, SessionEnv};
+use super::{
+    create_attempt_worktree, get_changed_files, remove_worktree, AgentError, AgentSession,
+    SessionManager,
+};
+use crate::config::ResolvedProfile;
+use crate::github::IssueDetail;
+
+/// Label of the attempt at `index`: "a", "b"… "z", then "aa"…
+fn attempt_label(index: usize) -> String {
+    let letter = (b'a' + (index % 26) as u8) as char;
+    letter.to_string().repeat(index / 26 + 1)
+}
+
+/// Labels for `count` new attempts, skipping the ones already taken
+fn next_labels(taken: &[String], count: usize) -> Vec<String> {
+    (0..)
+        .map(attempt_label)
+        .filter(|label| !taken.contains(label))
+        .take(count)
+        .collect()
+}
+
+/// Dispatch one attempt at an issue per profile, each in its own worktree and branch, all
+/// starting on the same `prompt`.
+///
+/// Attempts that could be launched keep running when a later one fails; the error of
+/// the first failure is returned only if none was launched.
+pub async fn dispatch_attempts(
+    issue: &IssueDetail,
+    local_path: &Path,
+    project: &str,
+    profiles: &[ResolvedProfile],
+    base_branch: Option<&str>,
+    prompt: &str,
+) -> Result<Vec<AgentSession>, AgentError> {
+    let env = SessionEnv::system();
+    let taken: Vec<String> = SessionManager::load_from(env.sessions_file.clone())
+        .list()
+        .iter()
+        .filter(|s| s.project == project && s.issue_number == issue.number)
+        .filter_map(|s| s.attempt.clone())
+        .collect();
+
+    let mut sessions = Vec::new();
+    let mut first_error = None;
+    for (profile, label) in profiles.iter().zip(next_labels(&taken, profiles.len())) {
+        let launched =
+            create_attempt_worktree(local_path, project, issue.number, &label, base_branch)
+                .map_err(AgentError::from)
+                .and_then(|(worktree_path, branch_name)| {
+                    launch_in_worktree(
+                        &env,
+                        SessionLaunch {
+                            id: None,
+                            number: issue.number,
+                            attempt: Some(label),
+                            title: issue.title.clone(),
+                            project,
+                            worktree_path: &worktree_path,
+                            branch_name: &branch_name,
+                            agent_type: &profile.agent,
+                            prompt,
+                            args: &profile.cli_args(),
+                            pr_url: None,
+                        },
+                    )
+                });
+        match launched {
+            Ok(session) => {
+                watch_session(&session);
+                sessions.push(session);
+            }
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    match first_error {
+        Some(e) if sessions.is_empty() => Err(e),
+        _ => Ok(sessions),
+    }
+}
+
+/// One attempt, as shown in the comparison view
+#[derive(Debug, Clone)]
+pub struct AttemptSummary {
+    pub session: AgentSession,
+    /// Changed files with lines added and deleted
+    pub files: Vec<(String, usize, usize)>,
+}
+
+/// Attempts at an issue with their changed files, in label order.
+pub fn compare_attempts(project: &str, issue_number: u64) -> Vec<AttemptSummary> {
+    let mut attempts: Vec<AttemptSummary> = SessionManager::load()
+        .list()
+        .iter()
+        .filter(|s| s.project == project && s.issue_number == issue_number && s.attempt.is_some())
+        .map(|session| AttemptSummary {
+            files: get_changed_files(&session.worktree_path),
+            session: session.clone(),
+        })
+        .collect();
+    attempts.sort_by_key(|a| {
+        let label = a.session.attempt.clone().unwrap_or_default();
+        (label.len(), label)
+    });
+    attempts
+}
+
+/// Keep one attempt and discard the other attempts at the same issue: their agents are
+/// stopped and their worktrees, branches and sessions removed. Returns how many were
+/// discarded.
+pub fn keep_attempt(id: &str, local_path: &Path) -> Result<usize, AgentError> {
+    let manager = SessionManager::load();
+    let kept = manager
+        .get(id)
+        .ok_or_else(|| AgentError::SessionError("No such session".to_string()))?;
+    let discarded: Vec<AgentSession> = manager
+        .list()
+        .iter()
+        .filter(|s| {
+            s.project == kept.project
+                && s.issue_number == kept.issue_number
+                && s.attempt.is_some()
+                && s.id != kept.id
+        })
+        .cloned()
+        .collect();
+
+    for session in &discarded {
+        if session.is_running() {
+            let _ = kill_session(&session.session_name());
+        }
+        if session.worktree_path.exists() {
+            remove_worktree(local_path, &session.worktree_path, true)?;
+        }
+    }
+    SessionManager::update(|manager| {
+        for session in &discarded {
+            manager.remove(&session.id);
+        }
+    })?;
+    start_queued_sessions();
+    Ok(discarded.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attempt_labels_skip_taken_ones() {
+        assert_eq!(attempt_label(0), "a");
+        assert_eq!(attempt_label(25), "z");
+        assert_eq!(attempt_label(26), "aa");
+        let taken = vec!["a".to_string(), "c".to_string()];
+        assert_eq!(next_labels(&taken, 3), vec!["b", "d", "e"]);
+    }
+}
@@ -0,0 +1,161 @@
+//! Automatic pull requests for projects with `auto_pr` set.
+//!
+//! When the monitor sees an agent session end, or wait for input longer than the configured
+//! idle time, the worktree's leftover changes are committed with an LLM-written message, the
+//! branch is pushed and a pull request linked to the issue is opened, as a draft by default.
+
+use std::thread;
+use std::time::Duration;
+
+use super::monitor::SessionEnv;
+use super::{
+    commit_all, default_branch, get_diff, get_uncommitted_diff, has_uncommitted_changes,
+    push_branch, AgentError, AgentSession, AgentStatus, SessionManager,
+};
+use crate::config::{AutoPrConfig, NotifyEvent};
+use crate::github::GitHubConfig;
+use crate::notify::{self, Notification};
+use crate::pull_requests;
+
+/// Whether a session's pull request is due: it ended, or it has been idle long enough.
+pub(super) fn due(config: &AutoPrConfig, ended: bool, idle_for: Option<Duration>) -> bool {
+    if ended {
+        return config.on_complete;
+    }
+    match (config.idle_minutes, idle_for) {
+        (Some(minutes), Some(idle_for)) => idle_for >= Duration::from_secs(minutes * 60),
+        _ => false,
+    }
+}
+
+/// Open the pull request of a session in the background, notifying the outcome.
+pub(super) fn spawn(env: SessionEnv, session: AgentSession, config: AutoPrConfig) {
+    thread::spawn(move || {
+        let opened = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(AgentError::from)
+            .and_then(|runtime| runtime.block_on(open_pull_request(&env, &session, &config)));
+
+        let notification = match opened {
+            Ok(Some(url)) => Notification::new(
+                NotifyEvent::PrCreated,
+                "Pull request created",
+                &format!("Opened a PR for issue #{}", session.issue_number),
+            )
+            .with_url(&url),
+            Ok(None) => return,
+            Err(e) => Notification::new(
+                NotifyEvent::Failed,
+                "Automatic PR failed",
+                &format!("Could not open a PR for issue #{}: {}", session.issue_number, e),
+            ),
+        };
+        let notification = notification.for_issue(&session.project, session.issue_number);
+        notify::dispatch(&env.notifiers, notification);
+    });
+}
+
+/// Commit the leftovers, push the branch and open the pull request.
+///
+/// Returns `None` when there is nothing to open: no changes, a PR already exists, the plan
+/// is still under review, the session was killed, or it is one of several attempts waiting
+/// for one to be kept.
+async fn open_pull_request(
+    env: &SessionEnv,
+    session: &AgentSession,
+    config: &AutoPrConfig,
+) -> Result<Option<String>, AgentError> {
+    let skipped = SessionManager::load_from(env.sessions_file.clone())
+        .get(&session.id)
+        .is_none_or(|s| {
+            s.pr_url.is_some()
+                || s.planning
+                || s.attempt.is_some()
+                || matches!(s.status, AgentStatus::Failed { .. })
+        });
+    if skipped {
+        return Ok(None);
+    }
+    // Work made only of new files is not in the diff against the merge-base
+    let worktree_path = &session.worktree_path;
+    if get_diff(worktree_path).trim().is_empty()
+        && get_uncommitted_diff(worktree_path).trim().is_empty()
+    {
+        return Ok(None);
+    }
+
+    let settings = crate::config::load_config()
+        .map_err(|e| AgentError::ProcessError(e.to_string()))?;
+    let project = settings.get_project(&session.project).ok_or_else(|| {
+        AgentError::ProcessError(format!("Project {} is not configured", session.project))
+    })?;
+    let github = GitHubConfig::from_keyring(project.owner.clone(), project.repo.clone())
+        .map_err(|e| AgentError::ProcessError(e.to_string()))?;
+    let issue = github.get_issue(session.issue_number).await.ok();
+    let agent_type = &settings.coding_agent;
+
+    if has_uncommitted_changes(worktree_path) {
+        let diff = get_uncommitted_diff(worktree_path);
+        let message = pull_requests::generate_commit_message(issue.as_ref(), &diff, agent_type)
+            .unwrap_or_else(|_| {
+                pull_requests::fallback_commit_message(issue.as_ref(), &session.branch_name)
+            });
+        commit_all(worktree_path, &message)?;
+    }
+    push_branch(worktree_path, &session.branch_name)?;
+
+    let diff = get_diff(worktree_path);
+    let template = pull_requests::find_template(worktree_path);
+    let content =
+        pull_requests::generate_pr_content(issue.as_ref(), &diff, template.as_deref(), agent_type)
+            .unwrap_or_else(|_| {
+                pull_requests::fallback_content(
+                    issue.as_ref(),
+                    &session.branch_name,
+                    template.as_deref(),
+                )
+            });
+    let base = project
+        .base_branch
+        .clone()
+        .or_else(|| default_branch(worktree_path))
+        .unwrap_or_else(|| "main".to_string());
+
+    let (url, _) = github
+        .create_pull_request(
+            &session.branch_name,
+            &base,
+            content.title.trim(),
+            &content.body,
+            config.draft,
+        )
+        .await
+        .map_err(|e| AgentError::ProcessError(e.to_string()))?;
+    env.update_sessions(|manager| manager.set_pr_url(&session.id, url.clone()))?;
+    Ok(Some(url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due_on_completion_or_after_idle_time() {
+        let config = AutoPrConfig {
+            idle_minutes: Some(10),
+            ..AutoPrConfig::default()
+        };
+        assert!(due(&config, true, None));
+        assert!(!due(&config, false, None));
+        assert!(!due(&config, false, Some(Duration::from_secs(9 * 60))));
+        assert!(due(&config, false, Some(Duration::from_secs(10 * 60))));
+
+        let config = AutoPrConfig {
+            on_complete: false,
+            ..AutoPrConfig::default()
+        };
+        assert!(!due(&config, true, None));
+        assert!(!due(&config, false, Some(Duration::from_secs(3600))));
+    }
+}
@@ -0,0 +1,114 @@
+//! In-memory session backend for tests.
+
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::{Attachment, SessionBackend, SessionKey};
+use crate::agents::AgentError;
+
+/// A session of the fake backend
+#[derive(Debug, Clone, Default)]
+pub struct FakeSession {
+    pub cwd: PathBuf,
+    pub command: Option<String>,
+    /// Text returned by `capture`
+    pub screen: String,
+    /// Messages sent with `send_message`
+    pub messages: Vec<String>,
+    /// Keys pressed with `send_keys`
+    pub keys: Vec<SessionKey>,
+}
+
+/// Backend keeping sessions in memory; tests drive the screen and session end.
+#[derive(Default)]
+pub struct FakeBackend {
+    sessions: Mutex<BTreeMap<String, FakeSession>>,
+}
+
+impl FakeBackend {
+    /// Snapshot of a running session
+    pub fn session(&self, name: &str) -> Option<FakeSession> {
+        self.sessions.lock().unwrap().get(name).cloned()
+    }
+
+    /// Replace what the session's screen shows.
+    pub fn set_screen(&self, name: &str, screen: &str) {
+        if let Some(session) = self.sessions.lock().unwrap().get_mut(name) {
+            session.screen = screen.to_string();
+        }
+    }
+
+    /// End a session, as if its command exited.
+    pub fn end(&self, name: &str) {
+        self.sessions.lock().unwrap().remove(name);
+    }
+}
+
+impl SessionBackend for FakeBackend {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    fn create(&self, name: &str, cwd: &Path, command: Option<&str>) -> Result<(), AgentError> {
+        let mut sessions = self.sessions.lock().unwrap();
+        if sessions.contains_key(name) {
+            return Err(AgentError::SessionError(format!("Session '{}' already exists", name)));
+        }
+        let session = FakeSession {
+            cwd: cwd.to_path_buf(),
+            command: command.map(|c| c.to_string()),
+            ..Default::default()
+        };
+        sessions.insert(name.to_string(), session);
+        Ok(())
+    }
+
+    fn is_running(&self, name: &str) -> bool {
+        self.sessions.lock().unwrap().contains_key(name)
+    }
+
+    fn list(&self) -> Vec<String> {
+        self.sessions.lock().unwrap().keys().cloned().collect()
+    }
+
+    fn kill(&self, name: &str) -> Result<(), AgentError> {
+        match self.sessions.lock().unwrap().remove(name) {
+            Some(_) => Ok(()),
+            None => Err(AgentError::SessionError(format!("No session '{}'", name))),
+        }
+    }
+
+    fn capture(&self, name: &str) -> Option<String> {
+        self.session(name).map(|s| s.screen)
+    }
+
+    fn send_message(&self, name: &str, text: &str) -> Result<(), AgentError> {
+        match self.sessions.lock().unwrap().get_mut(name) {
+            Some(session) => {
+                session.messages.push(text.to_string());
+                Ok(())
+            }
+            None => Err(AgentError::SessionError(format!("No session '{}'", name))),
+        }
+    }
+
+    fn send_keys(&self, name: &str, keys: &[SessionKey]) -> Result<(), AgentError> {
+        match self.sessions.lock().unwrap().get_mut(name) {
+            Some(session) => {
+                session.keys.extend_from_slice(keys);
+                Ok(())
+            }
+            None => Err(AgentError::SessionError(format!("No session '{}'", name))),
+        }
+    }
+
+    fn attach(&self, name: &str, _rows: u16, _cols: u16) -> Result<Attachment, String> {
+        let screen = self.capture(name).ok_or_else(|| format!("No session '{}'", name))?;
+        Ok(Attachment {
+            reader: Box::new(Cursor::new(screen.into_bytes())),
+            writer: Box::new(std::io::sink()),
+        })
+    }
+}
@@ -0,0 +1,162 @@
+//! Session backends: where agent terminals live.
+//!
+//! Agents run in named, detached terminal sessions that outlive the TUI. The tmux and
+//! zellij backends use multiplexer sessions; the native backend runs each agent on a
+//! pseudo-terminal owned by a small supervisor process, reachable through a local socket.
+
+#[cfg(test)]
+mod fake;
+mod native;
+mod tmux;
+mod zellij;
+
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, OnceLock};
+
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+
+use super::AgentError;
+use crate::config::{SessionBackendKind, TranscriptConfig};
+
+#[cfg(test)]
+pub use fake::FakeBackend;
+pub use native::{run_supervisor_from_args, NativeBackend, SUPERVISOR_ARG};
+pub use tmux::TmuxBackend;
+pub use zellij::ZellijBackend;
+
+/// Terminal size of new sessions
+pub const SESSION_ROWS: u16 = 50;
+pub const SESSION_COLS: u16 = 200;
+
+/// Live connection to a session's terminal, as used by the embedded terminal.
+pub struct Attachment {
+    /// Terminal output, starting with a redraw of the current screen
+    pub reader: Box<dyn Read + Send>,
+    /// Keyboard input
+    pub writer: Box<dyn Write + Send>,
+}
+
+/// A key pressed in a session, e.g. to answer a dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionKey {
+    Char(char),
+    Enter,
+    Escape,
+}
+
+impl SessionKey {
+    /// Bytes a terminal sends for the key
+    pub fn bytes(self) -> Vec<u8> {
+        match self {
+            SessionKey::Char(c) => c.to_string().into_bytes(),
+            SessionKey::Enter => b"\r".to_vec(),
+            SessionKey::Escape => b"\x1b".to_vec(),
+        }
+    }
+}
+
+/// Operations on named, detached agent sessions.
+pub trait SessionBackend: Send + Sync {
+    /// Backend name for messages (e.g. "tmux")
+    fn name(&self) -> &'static str;
+
+    /// Start a session in `cwd`, running `command` through bash, or a shell when `None`.
+    fn create(&self, name: &str, cwd: &Path, command: Option<&str>) -> Result<(), AgentError>;
+
+    /// Check if a session exists and is running.
+    fn is_running(&self, name: &str) -> bool;
+
+    /// Names of all running sessions.
+    fn list(&self) -> Vec<String>;
+
+    /// Terminate a session.
+    fn kill(&self, name: &str) -> Result<(), AgentError>;
+
+    /// Text currently on the session's screen, used for idle detection.
+    fn capture(&self, name: &str) -> Option<String>;
+
+    /// Type a message into the session and press Enter.
+    /// Multi-line text is pasted so it arrives as one message.
+    fn send_message(&self, name: &str, text: &str) -> Result<(), AgentError>;
+
+    /// Press keys in the session, without Enter unless given.
+    fn send_keys(&self, name: &str, keys: &[SessionKey]) -> Result<(), AgentError>;
+
+    /// Connect to a session's terminal with the given viewer size.
+    fn attach(&self, name: &str, rows: u16, cols: u16) -> Result<Attachment, String>;
+
+    /// Stream the session's output to a transcript at `log_file`; a session already
+    /// being recorded is left alone. Returns false when the backend cannot stream
+    /// output, leaving the monitor to record screen captures.
+    fn record(
+        &self,
+        _name: &str,
+        _log_file: &Path,
+        _config: &TranscriptConfig,
+    ) -> Result<bool, AgentError> {
+        Ok(false)
+    }
+}
+
+/// Run a multiplexer client attaching to a session on a local pseudo-terminal.
+fn attach_client(cmd: CommandBuilder, rows: u16, cols: u16) -> Result<Attachment, String> {
+    let program = cmd.get_argv()[0].to_string_lossy().to_string();
+    let pair = native_pty_system()
+        .openpty(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map_err(|e| format!("Failed to open PTY: {}", e))?;
+
+    let _child = pair
+        .slave
+        .spawn_command(cmd)
+        .map_err(|e| format!("Failed to spawn {}: {}", program, e))?;
+
+    let reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| format!("Failed to clone reader: {}", e))?;
+    let writer = pair
+        .master
+        .take_writer()
+        .map_err(|e| format!("Failed to take writer: {}", e))?;
+
+    Ok(Attachment { reader, writer })
+}
+
+/// Check whether tmux is installed.
+fn tmux_available() -> bool {
+    Command::new("tmux")
+        .arg("-V")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// The session backend selected in the configuration.
+///
+/// Defaults to tmux when it is installed, and to the native backend otherwise.
+pub fn session_backend() -> Arc<dyn SessionBackend> {
+    static BACKEND: OnceLock<Arc<dyn SessionBackend>> = OnceLock::new();
+    BACKEND
+        .get_or_init(|| {
+            let kind = crate::config::load_config()
+                .ok()
+                .and_then(|c| c.session_backend);
+            match kind {
+                Some(SessionBackendKind::Tmux) => Arc::new(TmuxBackend),
+                Some(SessionBackendKind::Zellij) => Arc::new(ZellijBackend),
+                Some(SessionBackendKind::Native) => Arc::new(NativeBackend::default()),
+                None if tmux_available() => Arc::new(TmuxBackend),
+                None => Arc::new(NativeBackend::default()),
+            }
+        })
+        .clone()
+}
@@ -0,0 +1,618 @@
+//! Native session backend: agents on pseudo-terminals owned by supervisor processes.
+//!
+//! Each session is a detached `assistant __session-supervisor` process running the
+//! command on a PTY and keeping its screen in a vt100 parser. The supervisor listens
+//! on a loopback socket whose port and access token are written to
+//! `<cache>/pty/<session>.session`.
+//!
+//! Clients send one request line, `<token> <REQUEST>`:
+//! - `PING`: `OK` is returned, telling a live supervisor from another process that took
+//!   over its port, e.g. after a reboot
+//! - `CAPTURE`: the screen text is returned
+//! - `MESSAGE <len>` followed by `len` bytes: the text is pasted and Enter pressed
+//! - `KEYS <len>` followed by `len` bytes: the bytes are typed as they are
+//! - `ATTACH <rows> <cols>`: the screen is redrawn then streamed, input is forwarded
+//! - `RECORD <keep_ansi> <asciicast> <max_size_kb> <rotations> <path>`: output is
+//!   recorded to a transcript, starting with the current screen
+//! - `KILL`: the command is terminated
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
+use vt100::Parser;
+
+use super::{Attachment, SessionBackend, SessionKey, SESSION_COLS, SESSION_ROWS};
+use crate::agents::transcript::Transcript;
+use crate::agents::{cache_dir, AgentError};
+use crate::config::TranscriptConfig;
+
+/// Hidden command line argument starting a supervisor
+pub const SUPERVISOR_ARG: &str = "__session-supervisor";
+
+/// Extension of session endpoint files
+const ENDPOINT_EXT: &str = "session";
+
+/// How long to wait for a new supervisor to accept connections
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long to wait for a supervisor to answer `PING`
+const PING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Sessions run by supervisor processes.
+pub struct NativeBackend {
+    /// Directory holding the endpoint files
+    root: PathBuf,
+}
+
+impl Default for NativeBackend {
+    fn default() -> Self {
+        Self {
+            root: cache_dir().join("pty"),
+        }
+    }
+}
+
+/// Address and token of a running supervisor
+struct Endpoint {
+    port: u16,
+    token: String,
+}
+
+impl Endpoint {
+    fn read(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        let (port, token) = content.trim().split_once(' ')?;
+        Some(Self {
+            port: port.parse().ok()?,
+            token: token.to_string(),
+        })
+    }
+
+    /// Write the endpoint file, readable only by the current user.
+    fn write(&self, path: &Path) -> std::io::Result<()> {
+        let tmp = path.with_extension("tmp");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp)?;
+        write!(file, "{} {}", self.port, self.token)?;
+        fs::rename(tmp, path)
+    }
+}
+
+impl NativeBackend {
+    fn endpoint_path(&self, name: &str) -> PathBuf {
+        self.root.join(format!("{}.{}", name, ENDPOINT_EXT))
+    }
+
+    /// Connect to a session's supervisor and send a request line.
+    /// Endpoint files of supervisors that are gone are removed.
+    fn request(&self, name: &str, request: &str) -> std::io::Result<TcpStream> {
+        let path = self.endpoint_path(name);
+        let endpoint = Endpoint::read(&path).ok_or(std::io::ErrorKind::NotFound)?;
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, endpoint.port));
+        let mut stream = match TcpStream::connect_timeout(&addr, Duration::from_secs(1)) {
+            Ok(stream) => stream,
+            Err(e) => {
+                let _ = fs::remove_file(&path);
+                return Err(e);
+            }
+        };
+        writeln!(stream, "{} {}", endpoint.token, request)?;
+        Ok(stream)
+    }
+
+    /// Send a request and read the response until the supervisor closes the connection.
+    fn request_response(&self, name: &str, request: &str, body: &[u8]) -> Result<String, AgentError> {
+        let error = |e: std::io::Error| {
+            AgentError::ProcessError(format!("Session '{}' is not reachable: {}", name, e))
+        };
+        let mut stream = self.request(name, request).map_err(error)?;
+        stream.write_all(body).map_err(error)?;
+        stream.set_read_timeout(Some(Duration::from_secs(5))).map_err(error)?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).map_err(error)?;
+        Ok(response)
+    }
+}
+
+impl SessionBackend for NativeBackend {
+    fn name(&self) -> &'static str {
+        "native"
+    }
+
+    fn create(&self, name: &str, cwd: &Path, command: Option<&str>) -> Result<(), AgentError> {
+        if self.is_running(name) {
+            return Err(AgentError::SessionError(format!("Session '{}' already exists", name)));
+        }
+        fs::create_dir_all(&self.root)?;
+
+        let exe = std::env::current_exe()?;
+        let mut supervisor = Command::new(exe);
+        supervisor
+            .arg(SUPERVISOR_ARG)
+            .arg(self.endpoint_path(name))
+            .arg(cwd)
+            .args(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        // Keep the supervisor alive when the terminal running the TUI closes
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut supervisor, 0);
+        #[cfg(windows)]
+        std::os::windows::process::CommandExt::creation_flags(&mut supervisor, 0x0000_0208);
+        supervisor
+            .spawn()
+            .map_err(|e| AgentError::ProcessError(format!("Failed to start supervisor: {}", e)))?;
+
+        let started = Instant::now();
+        while started.elapsed() < STARTUP_TIMEOUT {
+            if self.is_running(name) {
+                return Ok(());
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        Err(AgentError::ProcessError(format!("Session '{}' did not start", name)))
+    }
+
+    fn is_running(&self, name: &str) -> bool {
+        let Ok(mut stream) = self.request(name, "PING") else {
+            return false;
+        };
+        // Whatever holds the port now, if it is not the supervisor, does not answer
+        let mut reply = [0u8; 3];
+        let alive = stream.set_read_timeout(Some(PING_TIMEOUT)).is_ok()
+            && stream.read_exact(&mut reply).is_ok()
+            && &reply == b"OK\n";
+        if !alive {
+            let _ = fs::remove_file(self.endpoint_path(name));
+        }
+        alive
+    }
+
+    fn list(&self) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return vec![];
+        };
+        let mut names: Vec<String> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == ENDPOINT_EXT))
+            .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .filter(|name| self.is_running(name))
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn kill(&self, name: &str) -> Result<(), AgentError> {
+        let response = self.request_response(name, "KILL", &[])?;
+        if response.trim() != "OK" {
+            return Err(AgentError::ProcessError(format!(
+                "Failed to kill '{}': {}",
+                name,
+                response.trim()
+            )));
+        }
+        // Wait for the supervisor to go away, like tmux kill-session does
+        let started = Instant::now();
+        while self.is_running(name) && started.elapsed() < STARTUP_TIMEOUT {
+            thread::sleep(Duration::from_millis(50));
+        }
+        Ok(())
+    }
+
+    fn capture(&self, name: &str) -> Option<String> {
+        self.request_response(name, "CAPTURE", &[]).ok()
+    }
+
+    fn send_message(&self, name: &str, text: &str) -> Result<(), AgentError> {
+        let request = format!("MESSAGE {}", text.len());
+        match self.request_response(name, &request, text.as_bytes())?.trim() {
+            "OK" => Ok(()),
+            other => Err(AgentError::ProcessError(format!(
+                "Failed to send message to '{}': {}",
+                name, other
+            ))),
+        }
+    }
+
+    fn send_keys(&self, name: &str, keys: &[SessionKey]) -> Result<(), AgentError> {
+        let bytes: Vec<u8> = keys.iter().flat_map(|key| key.bytes()).collect();
+        let request = format!("KEYS {}", bytes.len());
+        match self.request_response(name, &request, &bytes)?.trim() {
+            "OK" => Ok(()),
+            other => Err(AgentError::ProcessError(format!(
+                "Failed to send keys to '{}': {}",
+                name, other
+            ))),
+        }
+    }
+
+    fn record(
+        &self,
+        name: &str,
+        log_file: &Path,
+        config: &TranscriptConfig,
+    ) -> Result<bool, AgentError> {
+        let request = format!(
+            "RECORD {} {} {} {} {}",
+            u8::from(config.keep_ansi),
+            u8::from(config.asciicast),
+            config.max_size_kb,
+            config.rotations,
+            log_file.display()
+        );
+        match self.request_response(name, &request, &[])?.trim() {
+            "OK" => Ok(true),
+            other => Err(AgentError::ProcessError(format!(
+                "Failed to record '{}': {}",
+                name, other
+            ))),
+        }
+    }
+
+    fn attach(&self, name: &str, rows: u16, cols: u16) -> Result<Attachment, String> {
+        let stream = self
+            .request(name, &format!("ATTACH {} {}", rows, cols))
+            .map_err(|e| format!("Failed to attach to '{}': {}", name, e))?;
+        let reader = stream
+            .try_clone()
+            .map_err(|e| format!("Failed to clone connection: {}", e))?;
+        Ok(Attachment {
+            reader: Box::new(reader),
+            writer: Box::new(Viewer(stream)),
+        })
+    }
+}
+
+/// Input side of an attached connection. Dropping it closes the connection,
+/// which ends the output stream as well.
+struct Viewer(TcpStream);
+
+impl Write for Viewer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl Drop for Viewer {
+    fn drop(&mut self) {
+        let _ = self.0.shutdown(std::net::Shutdown::Both);
+    }
+}
+
+/// State shared between the supervisor threads
+struct Supervised {
+    parser: Mutex<Parser>,
+    master: Mutex<Box<dyn MasterPty + Send>>,
+    input: Mutex<Box<dyn Write + Send>>,
+    killer: Mutex<Box<dyn ChildKiller + Send + Sync>>,
+    /// Attached clients receiving the terminal output
+    viewers: Mutex<Vec<TcpStream>>,
+    transcript: Mutex<Option<Transcript>>,
+}
+
+impl Supervised {
+    fn write_input(&self, data: &[u8]) -> std::io::Result<()> {
+        let mut input = self.input.lock().unwrap();
+        input.write_all(data)?;
+        input.flush()
+    }
+
+    fn resize(&self, rows: u16, cols: u16) {
+        let size = PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        };
+        let _ = self.master.lock().unwrap().resize(size);
+        self.parser.lock().unwrap().screen_mut().set_size(rows, cols);
+    }
+
+    /// Type a message: pasted when the program enabled bracketed paste, then Enter.
+    fn send_message(&self, text: &[u8]) -> std::io::Result<()> {
+        let bracketed = self.parser.lock().unwrap().screen().bracketed_paste();
+        if bracketed {
+            self.write_input(&[b"\x1b[200~", text, b"\x1b[201~"].concat())?;
+        } else {
+            self.write_input(text)?;
+        }
+        // Give the program time to take the paste before submitting it
+        thread::sleep(Duration::from_millis(100));
+        self.write_input(b"\r")
+    }
+
+    /// Start recording output, unless already recording.
+    fn record(&self, path: &Path, config: &TranscriptConfig) -> std::io::Result<()> {
+        let parser = self.parser.lock().unwrap();
+        let mut current = self.transcript.lock().unwrap();
+        if current.is_some() {
+            return Ok(());
+        }
+        let mut transcript = Transcript::open(path, config)?;
+        // Output so far is only left on the screen
+        if config.keep_ansi {
+            transcript.write(&parser.screen().contents_formatted())?;
+            transcript.write(b"\r\n")?;
+        } else {
+            let contents = parser.screen().contents();
+            let lines: Vec<&str> = contents.lines().map(str::trim_end).collect();
+            let end = lines.iter().rposition(|l| !l.is_empty()).map_or(0, |i| i + 1);
+            if end > 0 {
+                transcript.write_lines(&lines[..end])?;
+            }
+        }
+        *current = Some(transcript);
+        Ok(())
+    }
+
+    /// Feed PTY output to the parser, the transcript and the attached clients.
+    fn pump_output(&self, mut output: Box<dyn Read + Send>) {
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = match output.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            // Hold the parser while broadcasting so new viewers never miss or repeat output
+            let mut parser = self.parser.lock().unwrap();
+            parser.process(&buf[..n]);
+            if let Some(transcript) = self.transcript.lock().unwrap().as_mut() {
+                let _ = transcript.write(&buf[..n]);
+            }
+            self.viewers
+                .lock()
+                .unwrap()
+                .retain_mut(|viewer| viewer.write_all(&buf[..n]).is_ok());
+        }
+    }
+
+    /// Serve one client connection.
+    fn handle(&self, mut stream: TcpStream, token: &str) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut words = line.split_whitespace();
+        if words.next() != Some(token) {
+            return Ok(());
+        }
+
+        match (words.next(), words.next(), words.next()) {
+            (Some("PING"), _, _) => stream.write_all(b"OK\n"),
+            (Some("CAPTURE"), _, _) => {
+                let contents = self.parser.lock().unwrap().screen().contents();
+                stream.write_all(contents.as_bytes())
+            }
+            (Some("MESSAGE"), Some(len), _) => {
+                let len: usize = len.parse().map_err(|_| std::io::ErrorKind::InvalidInput)?;
+                let mut text = vec![0u8; len];
+                reader.read_exact(&mut text)?;
+                match self.send_message(&text) {
+                    Ok(()) => stream.write_all(b"OK\n"),
+                    Err(e) => stream.write_all(format!("{}\n", e).as_bytes()),
+                }
+            }
+            (Some("KEYS"), Some(len), _) => {
+                let len: usize = len.parse().map_err(|_| std::io::ErrorKind::InvalidInput)?;
+                let mut keys = vec![0u8; len];
+                reader.read_exact(&mut keys)?;
+                match self.write_input(&keys) {
+                    Ok(()) => stream.write_all(b"OK\n"),
+                    Err(e) => stream.write_all(format!("{}\n", e).as_bytes()),
+                }
+            }
+            (Some("ATTACH"), Some(rows), Some(cols)) => {
+                if let (Ok(rows), Ok(cols)) = (rows.parse(), cols.parse()) {
+                    self.resize(rows, cols);
+                }
+                stream.set_write_timeout(Some(Duration::from_secs(2)))?;
+                {
+                    let parser = self.parser.lock().unwrap();
+                    stream.write_all(&parser.screen().contents_formatted())?;
+                    self.viewers.lock().unwrap().push(stream.try_clone()?);
+                }
+                // Forward keyboard input until the client disconnects
+                let mut buf = [0u8; 1024];
+                loop {
+                    match reader.read(&mut buf) {
+                        Ok(0) | Err(_) => return Ok(()),
+                        Ok(n) => self.write_input(&buf[..n])?,
+                    }
+                }
+            }
+            (Some("RECORD"), _, _) => {
+                let fields: Vec<&str> = line.trim_end().splitn(7, ' ').collect();
+                let [_, _, keep_ansi, asciicast, max_size_kb, rotations, path] = fields[..] else {
+                    return stream.write_all(b"Invalid request\n");
+                };
+                let defaults = TranscriptConfig::default();
+                let config = TranscriptConfig {
+                    keep_ansi: keep_ansi == "1",
+                    max_size_kb: max_size_kb.parse().unwrap_or(defaults.max_size_kb),
+                    rotations: rotations.parse().unwrap_or(defaults.rotations),
+                    asciicast: asciicast == "1",
+                };
+                match self.record(Path::new(path), &config) {
+                    Ok(()) => stream.write_all(b"OK\n"),
+                    Err(e) => stream.write_all(format!("{}\n", e).as_bytes()),
+                }
+            }
+            (Some("KILL"), _, _) => match self.killer.lock().unwrap().kill() {
+                Ok(()) => stream.write_all(b"OK\n"),
+                Err(e) => stream.write_all(format!("{}\n", e).as_bytes()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Run a session until its command exits.
+///
+/// Listens on a loopback port advertised through `endpoint_file`, which is removed
+/// on exit. Without a command, the user's shell is started.
+pub fn run_supervisor(endpoint_file: &Path, cwd: &Path, command: Option<&str>) -> std::io::Result<()> {
+    let io_error = |e: Box<dyn std::error::Error + Send + Sync>| std::io::Error::other(e.to_string());
+    let size = PtySize {
+        rows: SESSION_ROWS,
+        cols: SESSION_COLS,
+        pixel_width: 0,
+        pixel_height: 0,
+    };
+    let pair = native_pty_system().openpty(size).map_err(|e| io_error(e.into()))?;
+
+    let mut cmd = match command {
+        Some(command) => {
+            let mut cmd = CommandBuilder::new("bash");
+            cmd.args(["-c", command]);
+            cmd
+        }
+        None => CommandBuilder::new_default_prog(),
+    };
+    cmd.cwd(cwd);
+    cmd.env("TERM", "xterm-256color");
+    let mut child = pair.slave.spawn_command(cmd).map_err(|e| io_error(e.into()))?;
+    drop(pair.slave);
+
+    let output = pair.master.try_clone_reader().map_err(|e| io_error(e.into()))?;
+    let input = pair.master.take_writer().map_err(|e| io_error(e.into()))?;
+    let supervised = Arc::new(Supervised {
+        parser: Mutex::new(Parser::new(SESSION_ROWS, SESSION_COLS, 0)),
+        master: Mutex::new(pair.master),
+        input: Mutex::new(input),
+        killer: Mutex::new(child.clone_killer()),
+        viewers: Mutex::new(Vec::new()),
+        transcript: Mutex::new(None),
+    });
+
+    let pump = Arc::clone(&supervised);
+    thread::spawn(move || pump.pump_output(output));
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let endpoint = Endpoint {
+        port: listener.local_addr()?.port(),
+        token: uuid::Uuid::new_v4().simple().to_string(),
+    };
+    endpoint.write(endpoint_file)?;
+
+    let server = Arc::clone(&supervised);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let server = Arc::clone(&server);
+            let token = endpoint.token.clone();
+            thread::spawn(move || server.handle(stream, &token));
+        }
+    });
+
+    let status = child.wait();
+    let _ = fs::remove_file(endpoint_file);
+    status.map(|_| ())
+}
+
+/// Entry point of the supervisor process: `__session-supervisor <endpoint> <cwd> [command]`.
+pub fn run_supervisor_from_args(args: &[String]) -> ! {
+    let code = match args {
+        [endpoint, cwd, rest @ ..] => {
+            let command = rest.first().map(String::as_str);
+            match run_supervisor(Path::new(endpoint), Path::new(cwd), command) {
+                Ok(()) => 0,
+                Err(_) => 1,
+            }
+        }
+        _ => 2,
+    };
+    std::process::exit(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn supervisor_serves_capture_messages_and_kill() {
+        let root = std::env::temp_dir().join(format!("assistant-pty-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let backend = NativeBackend { root: root.clone() };
+
+        let endpoint = backend.endpoint_path("test-issue-1");
+        let cwd = root.clone();
+        let supervisor = thread::spawn(move || {
+            run_supervisor(&endpoint, &cwd, Some("echo hello; cat"))
+        });
+
+        let started = Instant::now();
+        while !backend.is_running("test-issue-1") {
+            assert!(started.elapsed() < STARTUP_TIMEOUT, "supervisor did not start");
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(backend.list(), vec!["test-issue-1"]);
+
+        let screen_contains = |text: &str| {
+            let started = Instant::now();
+            while started.elapsed() < STARTUP_TIMEOUT {
+                if backend.capture("test-issue-1").is_some_and(|s| s.contains(text)) {
+                    return true;
+                }
+                thread::sleep(Duration::from_millis(20));
+            }
+            false
+        };
+        assert!(screen_contains("hello"));
+
+        let log = root.join("test.log");
+        assert!(backend.record("test-issue-1", &log, &TranscriptConfig::default()).unwrap());
+        backend.send_message("test-issue-1", "ping back").unwrap();
+        assert!(screen_contains("ping back"));
+        let keys = [SessionKey::Char('4'), SessionKey::Char('2'), SessionKey::Enter];
+        backend.send_keys("test-issue-1", &keys).unwrap();
+        assert!(screen_contains("42"));
+
+        backend.kill("test-issue-1").unwrap();
+        supervisor.join().unwrap().unwrap();
+        assert!(!backend.is_running("test-issue-1"));
+        assert!(backend.list().is_empty());
+
+        let transcript = fs::read_to_string(&log).unwrap();
+        assert!(transcript.starts_with("hello\n"));
+        assert!(transcript.contains("ping back"));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn stale_endpoint_on_a_reused_port_is_not_running() {
+        let root = std::env::temp_dir().join(format!("assistant-pty-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let backend = NativeBackend { root: root.clone() };
+
+        // Another process accepting connections on the port, without answering
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let endpoint = Endpoint {
+            port: listener.local_addr().unwrap().port(),
+            token: "token".to_string(),
+        };
+        let path = backend.endpoint_path("test-issue-2");
+        endpoint.write(&path).unwrap();
+        let accepted = thread::spawn(move || listener.accept().map(|(stream, _)| stream));
+
+        assert!(!backend.is_running("test-issue-2"));
+        assert!(!path.exists());
+        drop(accepted.join().unwrap());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
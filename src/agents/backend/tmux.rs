@@ -0,0 +1,141 @@
+//! tmux session backend.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use portable_pty::CommandBuilder;
+
+use super::{
+    attach_client, Attachment, SessionBackend, SessionKey, SESSION_COLS, SESSION_ROWS,
+};
+use crate::agents::traits::{shell_args, shell_quote};
+use crate::agents::transcript::transcript_args;
+use crate::agents::AgentError;
+use crate::config::TranscriptConfig;
+
+/// Sessions managed by tmux.
+pub struct TmuxBackend;
+
+/// Run a tmux command, reporting failures with its stderr.
+fn run_tmux(args: &[&str]) -> Result<(), AgentError> {
+    let output = Command::new("tmux")
+        .args(args)
+        .output()
+        .map_err(|e| AgentError::ProcessError(format!("Failed to run tmux: {}", e)))?;
+    if !output.status.success() {
+        return Err(AgentError::ProcessError(format!(
+            "tmux {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+impl SessionBackend for TmuxBackend {
+    fn name(&self) -> &'static str {
+        "tmux"
+    }
+
+    fn create(&self, name: &str, cwd: &Path, command: Option<&str>) -> Result<(), AgentError> {
+        let rows = SESSION_ROWS.to_string();
+        let cols = SESSION_COLS.to_string();
+        let cwd = cwd.to_str().unwrap_or(".");
+        let mut args = vec!["new-session", "-d", "-s", name, "-c", cwd, "-x", &cols, "-y", &rows];
+        if let Some(command) = command {
+            args.extend(["bash", "-c", command]);
+        }
+        run_tmux(&args)
+    }
+
+    fn is_running(&self, name: &str) -> bool {
+        Command::new("tmux")
+            .args(["has-session", "-t", name])
+            .stderr(Stdio::null())
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false)
+    }
+
+    fn list(&self) -> Vec<String> {
+        let output = Command::new("tmux")
+            .args(["list-sessions", "-F", "#{session_name}"])
+            .stderr(Stdio::null())
+            .output();
+
+        match output {
+            Ok(out) if out.status.success() => String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .map(|s| s.to_string())
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    fn kill(&self, name: &str) -> Result<(), AgentError> {
+        run_tmux(&["kill-session", "-t", name])
+    }
+
+    fn capture(&self, name: &str) -> Option<String> {
+        let output = Command::new("tmux")
+            .args(["capture-pane", "-t", name, "-p", "-S", "-50"])
+            .output()
+            .ok()?;
+
+        if output.status.success() {
+            Some(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            None
+        }
+    }
+
+    fn send_message(&self, name: &str, text: &str) -> Result<(), AgentError> {
+        // A paste buffer keeps multi-line prompts together
+        let buffer = format!("assistant-{}", name);
+        run_tmux(&["set-buffer", "-b", &buffer, "--", text])?;
+        run_tmux(&["paste-buffer", "-d", "-p", "-b", &buffer, "-t", name])?;
+        run_tmux(&["send-keys", "-t", name, "Enter"])
+    }
+
+    fn send_keys(&self, name: &str, keys: &[SessionKey]) -> Result<(), AgentError> {
+        for key in keys {
+            match key {
+                SessionKey::Char(c) => run_tmux(&["send-keys", "-t", name, "-l", &c.to_string()])?,
+                SessionKey::Enter => run_tmux(&["send-keys", "-t", name, "Enter"])?,
+                SessionKey::Escape => run_tmux(&["send-keys", "-t", name, "Escape"])?,
+            }
+        }
+        Ok(())
+    }
+
+    fn attach(&self, name: &str, rows: u16, cols: u16) -> Result<Attachment, String> {
+        let mut cmd = CommandBuilder::new("tmux");
+        cmd.args(["attach", "-t", name]);
+        attach_client(cmd, rows, cols)
+    }
+
+    fn record(
+        &self,
+        name: &str,
+        log_file: &Path,
+        config: &TranscriptConfig,
+    ) -> Result<bool, AgentError> {
+        // pipe-pane replaces an existing pipe, so leave a recorded pane alone
+        let piped = Command::new("tmux")
+            .args(["display-message", "-p", "-t", name, "#{pane_pipe}"])
+            .output()
+            .is_ok_and(|out| String::from_utf8_lossy(&out.stdout).trim() == "1");
+        if piped {
+            return Ok(true);
+        }
+
+        let exe = std::env::current_exe()?;
+        let command = format!(
+            "{}{}",
+            shell_quote(&exe.to_string_lossy()),
+            shell_args(&transcript_args(log_file, config))
+        );
+        run_tmux(&["pipe-pane", "-t", name, &command])?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,159 @@
+//! zellij session backend.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use portable_pty::CommandBuilder;
+
+use super::{attach_client, Attachment, SessionBackend, SessionKey};
+use crate::agents::traits::shell_quote;
+use crate::agents::{cache_dir, new_session_id, AgentError};
+
+/// Sessions managed by zellij.
+pub struct ZellijBackend;
+
+/// Run a zellij command, reporting failures with its stderr.
+fn run_zellij(args: &[&str]) -> Result<(), AgentError> {
+    let output = Command::new("zellij")
+        .args(args)
+        .output()
+        .map_err(|e| AgentError::ProcessError(format!("Failed to run zellij: {}", e)))?;
+    if !output.status.success() {
+        return Err(AgentError::ProcessError(format!(
+            "zellij {} failed: {}",
+            args.iter().take(3).copied().collect::<Vec<_>>().join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Run a `zellij action` in a session.
+fn action(name: &str, args: &[&str]) -> Result<(), AgentError> {
+    let mut full = vec!["--session", name, "action"];
+    full.extend(args);
+    run_zellij(&full)
+}
+
+/// Names of live sessions in `zellij list-sessions` output.
+/// Exited sessions, kept by zellij for resurrection, are skipped.
+fn parse_session_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| !line.contains("EXITED"))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+impl SessionBackend for ZellijBackend {
+    fn name(&self) -> &'static str {
+        "zellij"
+    }
+
+    fn create(&self, name: &str, cwd: &Path, command: Option<&str>) -> Result<(), AgentError> {
+        let cwd_str = cwd.to_str().unwrap_or(".");
+        run_zellij(&[
+            "attach",
+            "--create-background",
+            name,
+            "options",
+            "--default-cwd",
+            cwd_str,
+        ])?;
+
+        // The session starts with a shell; replacing it with the command makes the
+        // session end with the command, as with tmux
+        if let Some(command) = command {
+            let line = format!(
+                "cd {} && exec bash -c {}",
+                shell_quote(cwd_str),
+                shell_quote(command)
+            );
+            action(name, &["write-chars", &line])?;
+            action(name, &["write", "13"])?;
+        }
+        Ok(())
+    }
+
+    fn is_running(&self, name: &str) -> bool {
+        self.list().iter().any(|s| s == name)
+    }
+
+    fn list(&self) -> Vec<String> {
+        let output = Command::new("zellij")
+            .args(["list-sessions", "--no-formatting"])
+            .stderr(Stdio::null())
+            .output();
+
+        match output {
+            Ok(out) if out.status.success() => {
+                parse_session_list(&String::from_utf8_lossy(&out.stdout))
+            }
+            _ => vec![],
+        }
+    }
+
+    fn kill(&self, name: &str) -> Result<(), AgentError> {
+        run_zellij(&["kill-session", name])?;
+        // Drop the exited session so it is not offered for resurrection
+        let _ = run_zellij(&["delete-session", name]);
+        Ok(())
+    }
+
+    fn capture(&self, name: &str) -> Option<String> {
+        let dir = cache_dir().join("zellij");
+        std::fs::create_dir_all(&dir).ok()?;
+        let path = dir.join(format!("{}.txt", new_session_id()));
+        action(name, &["dump-screen", path.to_str()?]).ok()?;
+        let content = std::fs::read_to_string(&path).ok();
+        let _ = std::fs::remove_file(&path);
+        content
+    }
+
+    fn send_message(&self, name: &str, text: &str) -> Result<(), AgentError> {
+        // Bracketed paste keeps multi-line prompts together
+        let multiline = text.contains('\n');
+        if multiline {
+            action(name, &["write", "27", "91", "50", "48", "48", "126"])?;
+        }
+        action(name, &["write-chars", text])?;
+        if multiline {
+            action(name, &["write", "27", "91", "50", "48", "49", "126"])?;
+        }
+        action(name, &["write", "13"])
+    }
+
+    fn send_keys(&self, name: &str, keys: &[SessionKey]) -> Result<(), AgentError> {
+        let bytes: Vec<String> = keys
+            .iter()
+            .flat_map(|key| key.bytes())
+            .map(|b| b.to_string())
+            .collect();
+        let mut args = vec!["write"];
+        args.extend(bytes.iter().map(String::as_str));
+        action(name, &args)
+    }
+
+    fn attach(&self, name: &str, rows: u16, cols: u16) -> Result<Attachment, String> {
+        let mut cmd = CommandBuilder::new("zellij");
+        cmd.args(["attach", name]);
+        attach_client(cmd, rows, cols)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_session_list_skips_exited_sessions() {
+        let output = "\
+proj-issue-1 [Created 2m ago]
+proj-issue-2 [Created 1h ago] (EXITED - attach to resurrect)
+proj-pr-7 [Created 10s ago] (current)
+";
+        assert_eq!(parse_session_list(output), vec!["proj-issue-1", "proj-pr-7"]);
+        assert!(parse_session_list("").is_empty());
+    }
+}
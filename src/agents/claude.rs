@@ -2,91 +2,258 @@
 
 use std::fs;
 use std::path::Path;
-use std::process::{Command, Stdio};
-use std::thread;
-use std::time::Duration;
 
-use super::traits::CodingAgent;
+use super::backend::session_backend;
+use super::monitor::{start_queued_sessions, watch_session, SessionEnv};
+use super::traits::{shell_args, AgentDialog, CodingAgent};
 use super::{
-    agents_log_dir, build_issue_prompt, create_worktree, get_diff_stats, new_session_id,
-    send_notification, AgentError, AgentSession, AgentStats, AgentStatus, SessionManager,
+    build_issue_prompt, create_worktree, new_session_id, AgentError, AgentSession, AgentStatus,
+    SessionManager,
 };
-use crate::config::CodingAgentType;
-use crate::github::IssueDetail;
+use crate::config::{CodingAgentType, ResolvedProfile};
+use crate::github::{IssueDetail, PullRequestDetail};
 
 /// Claude Code agent for processing GitHub issues.
 pub struct ClaudeCodeAgent;
 
 impl CodingAgent for ClaudeCodeAgent {
-    fn name(&self) -> &'static str {
+    fn name(&self) -> &str {
         "Claude Code"
     }
 
-    fn cli_command(&self) -> &'static str {
+    fn cli_command(&self) -> &str {
         "claude"
     }
 
     fn is_idle(&self, pane_content: &str) -> bool {
         is_claude_idle(pane_content)
     }
+
+    fn dialog(&self, pane_content: &str) -> Option<AgentDialog> {
+        claude_dialog(pane_content)
+    }
+
+    fn build_resume_command(&self, worktree_path: &Path, args: &[String]) -> Option<String> {
+        Some(format!(
+            "cd '{}' && claude --continue{}",
+            worktree_path.display(),
+            shell_args(args)
+        ))
+    }
 }
 
 /// Dispatch an issue to a coding agent for processing.
 ///
 /// This creates a git worktree, launches the agent in an interactive
-/// tmux session, and returns immediately with a session handle.
+/// session (tmux or native), and returns immediately with a session handle.
+///
+/// The agent starts on `prompt` (see [`build_issue_prompt`] and
+/// [`super::build_dispatch_prompt`]). Without an explicit `profile`, the agent, model and
+/// flags are resolved from the project settings and label routing, starting from `agent_type`.
 pub async fn dispatch_to_agent(
     issue: &IssueDetail,
     local_path: &Path,
     project: &str,
     agent_type: &CodingAgentType,
     base_branch: Option<&str>,
-    additional_instructions: Option<&str>,
+    prompt: &str,
+    profile: Option<&ResolvedProfile>,
 ) -> Result<AgentSession, AgentError> {
-    use super::traits::get_agent;
+    let profile = resolve_profile(issue, project, agent_type, profile);
+    dispatch_with_prompt(issue, local_path, project, base_branch, &profile, prompt, false)
+}
+
+/// Dispatch an issue to a coding agent that only writes an implementation plan.
+///
+/// `prompt` describes the issue; it is followed by the request for a plan. The session is
+/// marked as planning until the plan is approved and sent back to it as the instruction to
+/// implement (see [`crate::plan`]).
+pub async fn dispatch_plan_first(
+    issue: &IssueDetail,
+    local_path: &Path,
+    project: &str,
+    agent_type: &CodingAgentType,
+    base_branch: Option<&str>,
+    prompt: &str,
+    profile: Option<&ResolvedProfile>,
+) -> Result<AgentSession, AgentError> {
+    let profile = resolve_profile(issue, project, agent_type, profile);
+    let prompt = crate::plan::build_plan_prompt(prompt);
+    dispatch_with_prompt(issue, local_path, project, base_branch, &profile, &prompt, true)
+}
 
-    let agent = get_agent(agent_type);
-    let session_id = new_session_id();
+/// The given profile, or the one resolved from the project settings and label routing
+fn resolve_profile(
+    issue: &IssueDetail,
+    project: &str,
+    agent_type: &CodingAgentType,
+    profile: Option<&ResolvedProfile>,
+) -> ResolvedProfile {
+    match profile {
+        Some(profile) => profile.clone(),
+        None => resolve_agent_profile(project, &issue.labels, agent_type),
+    }
+}
 
+/// Agent settings for work in `project` carrying `labels`, from the project settings and
+/// label routing, starting from `agent_type`.
+pub fn resolve_agent_profile(
+    project: &str,
+    labels: &[String],
+    agent_type: &CodingAgentType,
+) -> ResolvedProfile {
+    crate::config::load_config()
+        .map(|c| c.resolve_agent_profile(project, labels, agent_type))
+        .unwrap_or_else(|_| ResolvedProfile::for_agent(agent_type.clone()))
+}
+
+/// Create the issue worktree and launch the agent on `prompt`.
+fn dispatch_with_prompt(
+    issue: &IssueDetail,
+    local_path: &Path,
+    project: &str,
+    base_branch: Option<&str>,
+    profile: &ResolvedProfile,
+    prompt: &str,
+    planning: bool,
+) -> Result<AgentSession, AgentError> {
     // Create the worktree
     let (worktree_path, branch_name) = create_worktree(local_path, project, issue.number, base_branch)?;
 
-    // Ensure log directory exists
-    let log_dir = agents_log_dir();
-    fs::create_dir_all(&log_dir)?;
-
-    // Create log file (for session metadata)
-    let log_file = log_dir.join(format!("{}.log", session_id));
-
-    // Build the prompt with optional additional instructions
-    let prompt = build_issue_prompt(issue, additional_instructions);
-
-    // Get tmux session name
-    let tmux_name = tmux_session_name(project, issue.number);
-
-    // Launch agent in tmux using trait method
-    launch_agent_tmux(&*agent, &worktree_path, &prompt, &tmux_name)?;
-
-    // Create session with agent type
-    let session = AgentSession::new(
-        session_id.clone(),
-        issue.number,
-        issue.title.clone(),
-        project.to_string(),
-        0, // No direct PID, we use tmux session name
-        log_file.clone(),
-        worktree_path.clone(),
-        branch_name,
-        agent_type.clone(),
-    );
+    let env = SessionEnv::system();
+    let mut session = launch_in_worktree(
+        &env,
+        SessionLaunch {
+            id: None,
+            number: issue.number,
+            attempt: None,
+            title: issue.title.clone(),
+            project,
+            worktree_path: &worktree_path,
+            branch_name: &branch_name,
+            agent_type: &profile.agent,
+            prompt,
+            args: &profile.cli_args(),
+            pr_url: None,
+        },
+    )?;
+    if planning {
+        session.planning = true;
+        env.update_sessions(|manager| {
+            if let Some(saved) = manager.get_mut(&session.id) {
+                saved.planning = true;
+            }
+        })?;
+    }
+    watch_session(&session);
+    Ok(session)
+}
+
+/// Dispatch a coding agent into an existing pull request worktree.
+///
+/// The session uses the PR number (PRs and issues share numbering) and records the
+/// PR URL, so it shows up next to the PR. Without an explicit `profile`, the agent settings
+/// are resolved from the project settings and the PR's labels, as for issues.
+#[allow(clippy::too_many_arguments)]
+pub fn dispatch_to_pr_worktree(
+    pr: &PullRequestDetail,
+    worktree_path: &Path,
+    branch_name: &str,
+    project: &str,
+    agent_type: &CodingAgentType,
+    title: String,
+    prompt: &str,
+    profile: Option<&ResolvedProfile>,
+) -> Result<AgentSession, AgentError> {
+    let profile = profile
+        .cloned()
+        .unwrap_or_else(|| resolve_agent_profile(project, &pr.labels, agent_type));
+    let env = SessionEnv::system();
+    let session = launch_in_worktree(
+        &env,
+        SessionLaunch {
+            id: None,
+            number: pr.number,
+            attempt: None,
+            title,
+            project,
+            worktree_path,
+            branch_name,
+            agent_type: &profile.agent,
+            prompt,
+            args: &profile.cli_args(),
+            pr_url: Some(pr.html_url.clone()),
+        },
+    )?;
+    watch_session(&session);
+    Ok(session)
+}
+
+/// An agent to launch in an existing worktree
+pub(super) struct SessionLaunch<'a> {
+    /// Session to start, for a queued dispatch; a new session otherwise
+    pub id: Option<String>,
+    /// Issue or PR number
+    pub number: u64,
+    /// Label of the attempt, when several agents work on the issue
+    pub attempt: Option<String>,
+    pub title: String,
+    pub project: &'a str,
+    pub worktree_path: &'a Path,
+    pub branch_name: &'a str,
+    pub agent_type: &'a CodingAgentType,
+    pub prompt: &'a str,
+    /// Extra agent CLI arguments from the profile
+    pub args: &'a [String],
+    pub pr_url: Option<String>,
+}
+
+/// Launch an agent in a worktree and record its session.
+pub(super) fn launch_in_worktree(
+    env: &SessionEnv,
+    launch: SessionLaunch,
+) -> Result<AgentSession, AgentError> {
+    use super::traits::get_agent;
 
-    // Save session
-    let mut manager = SessionManager::load();
-    manager.add(session.clone());
-    manager.save()?;
+    let session_name =
+        attempt_session_name(launch.project, launch.number, launch.attempt.as_deref());
+    if env.backend.is_running(&session_name) {
+        return Err(AgentError::SessionError(format!(
+            "An agent is already running for #{}",
+            launch.number
+        )));
+    }
+
+    // Log file for session metadata
+    fs::create_dir_all(&env.log_dir)?;
+    let session_id = launch.id.unwrap_or_else(new_session_id);
+    let log_file = env.log_dir.join(format!("{}.log", session_id));
+
+    // Launch agent in a session using trait method
+    let agent = get_agent(launch.agent_type);
+    let cmd = agent.build_launch_command(launch.worktree_path, launch.prompt, launch.args);
+    env.backend.create(&session_name, launch.worktree_path, Some(&cmd))?;
+
+    let mut session = AgentSession::new(
+        session_id,
+        launch.number,
+        launch.title,
+        launch.project.to_string(),
+        0, // No direct PID, we use the session name
+        log_file,
+        launch.worktree_path.to_path_buf(),
+        launch.branch_name.to_string(),
+        launch.agent_type.clone(),
+    );
+    session.pr_url = launch.pr_url;
+    session.attempt = launch.attempt;
+    session.args = launch.args.to_vec();
 
-    // Start monitoring thread for tmux session with agent type
-    start_tmux_monitoring(session_id, tmux_name, worktree_path, agent_type.clone());
+    // Save session, replacing its queue entry
+    env.update_sessions(|manager| {
+        manager.remove(&session.id);
+        manager.add(session.clone());
+    })?;
 
     Ok(session)
 }
@@ -98,54 +265,27 @@ pub async fn dispatch_to_claude(
     project: &str,
     base_branch: Option<&str>,
 ) -> Result<AgentSession, AgentError> {
-    dispatch_to_agent(issue, local_path, project, &CodingAgentType::Claude, base_branch, None).await
+    dispatch_to_agent(
+        issue,
+        local_path,
+        project,
+        &CodingAgentType::Claude,
+        base_branch,
+        &build_issue_prompt(issue, None),
+        None,
+    )
+    .await
 }
 
-/// Launch a coding agent in an interactive tmux session.
-fn launch_agent_tmux(
-    agent: &dyn CodingAgent,
-    worktree_path: &Path,
-    prompt: &str,
-    session_name: &str,
-) -> Result<(), AgentError> {
-    // Build the command using the agent's trait method
-    let cmd = agent.build_launch_command(worktree_path, prompt);
-
-    // Create tmux session in detached mode
-    let status = Command::new("tmux")
-        .args([
-            "new-session",
-            "-d",
-            "-s",
-            session_name,
-            "-x",
-            "200",
-            "-y",
-            "50",
-            "bash",
-            "-c",
-            &cmd,
-        ])
-        .status()
-        .map_err(|e| AgentError::ProcessError(format!("Failed to launch tmux: {}", e)))?;
-
-    if !status.success() {
-        return Err(AgentError::ProcessError(
-            "Failed to create tmux session".to_string(),
-        ));
-    }
-
-    Ok(())
-}
-
-/// Launch a coding agent interactively in a tmux session.
+/// Launch a coding agent interactively in a session.
 ///
 /// If `initial_prompt` is provided, the agent will be launched with that prompt.
-/// Otherwise, it starts in interactive mode without initial context.
+/// Otherwise, it starts in interactive mode without initial context. Either way it gets
+/// the model and flags of `profile`.
 pub fn launch_agent_interactive(
     worktree_path: &Path,
     session_name: &str,
-    agent_type: &CodingAgentType,
+    profile: &ResolvedProfile,
     initial_prompt: Option<&str>,
 ) -> Result<(), AgentError> {
     use super::traits::get_agent;
@@ -159,396 +299,141 @@ pub fn launch_agent_interactive(
     }
 
     // Check if session already exists
-    if is_tmux_session_running(session_name) {
+    if is_session_running(session_name) {
         return Err(AgentError::ProcessError(format!(
-            "Tmux session '{}' already exists",
+            "Session '{}' already exists",
             session_name
         )));
     }
 
-    let agent = get_agent(agent_type);
+    let agent = get_agent(&profile.agent);
+    let backend = session_backend();
+    let args = profile.cli_args();
 
-    // If we have a prompt, use build_launch_command like launch_agent_tmux
     if let Some(prompt) = initial_prompt {
-        let cmd = agent.build_launch_command(worktree_path, prompt);
-
-        let status = Command::new("tmux")
-            .args([
-                "new-session",
-                "-d",
-                "-s",
-                session_name,
-                "-x",
-                "200",
-                "-y",
-                "50",
-                "bash",
-                "-c",
-                &cmd,
-            ])
-            .status()
-            .map_err(|e| AgentError::ProcessError(format!("Failed to launch tmux: {}", e)))?;
-
-        if !status.success() {
-            return Err(AgentError::ProcessError(
-                "Failed to create tmux session".to_string(),
-            ));
-        }
+        let cmd = agent.build_launch_command(worktree_path, prompt, &args);
+        backend.create(session_name, worktree_path, Some(&cmd))
     } else {
-        // No prompt - start in interactive mode
-        let output = Command::new("tmux")
-            .args([
-                "new-session",
-                "-d",
-                "-s",
-                session_name,
-                "-c",
-                worktree_path.to_str().unwrap_or("."),
-                "-x",
-                "200",
-                "-y",
-                "50",
-            ])
-            .output()
-            .map_err(|e| AgentError::ProcessError(format!("Failed to launch tmux: {}", e)))?;
-
-        if !output.status.success() {
-            return Err(AgentError::ProcessError(format!(
-                "Failed to create tmux session: {}",
-                String::from_utf8_lossy(&output.stderr)
-            )));
-        }
-
-        // Send the agent command to the tmux session
-        let agent_cmd = agent.cli_command();
-        let _ = Command::new("tmux")
-            .args(["send-keys", "-t", session_name, agent_cmd, "Enter"])
-            .output();
+        // No prompt - start a shell and type the agent command into it
+        backend.create(session_name, worktree_path, None)?;
+        let command = format!("{}{}", agent.build_interactive_command(), shell_args(&args));
+        let _ = backend.send_message(session_name, &command);
+        Ok(())
     }
-
-    Ok(())
 }
 
-/// Get the tmux session name for an issue.
-pub fn tmux_session_name(project: &str, issue_number: u64) -> String {
+/// Get the session name for an issue.
+pub fn issue_session_name(project: &str, issue_number: u64) -> String {
     format!("{}-issue-{}", project, issue_number)
 }
 
-/// Check if a tmux session exists and is running.
-pub fn is_tmux_session_running(session_name: &str) -> bool {
-    Command::new("tmux")
-        .args(["has-session", "-t", session_name])
-        .stderr(Stdio::null())
-        .status()
-        .map(|s| s.success())
-        .unwrap_or(false)
-}
-
-/// List all tmux sessions for the assistant (issue-based only).
-pub fn list_tmux_sessions() -> Vec<String> {
-    let output = Command::new("tmux")
-        .args(["list-sessions", "-F", "#{session_name}"])
-        .output();
-
-    match output {
-        Ok(out) if out.status.success() => {
-            String::from_utf8_lossy(&out.stdout)
-                .lines()
-                .filter(|s| s.contains("-issue-"))
-                .map(|s| s.to_string())
-                .collect()
-        }
-        _ => vec![],
+/// Get the session name for an issue, or for one of several attempts at it.
+pub fn attempt_session_name(project: &str, issue_number: u64, attempt: Option<&str>) -> String {
+    match attempt {
+        Some(attempt) => format!("{}-{}", issue_session_name(project, issue_number), attempt),
+        None => issue_session_name(project, issue_number),
     }
 }
 
-/// List all tmux sessions (no filtering).
-pub fn list_all_tmux_sessions() -> Vec<String> {
-    let output = Command::new("tmux")
-        .args(["list-sessions", "-F", "#{session_name}"])
-        .output();
-
-    match output {
-        Ok(out) if out.status.success() => {
-            String::from_utf8_lossy(&out.stdout)
-                .lines()
-                .map(|s| s.to_string())
-                .collect()
-        }
-        _ => vec![],
-    }
+/// Check if a session exists and is running.
+pub fn is_session_running(session_name: &str) -> bool {
+    session_backend().is_running(session_name)
 }
 
-/// Attach to a tmux session (returns the command to run).
-pub fn attach_tmux_command(session_name: &str) -> String {
-    format!("tmux attach -t {}", session_name)
+/// List all sessions for the assistant (issue-based only).
+pub fn list_issue_sessions() -> Vec<String> {
+    list_all_sessions()
+        .into_iter()
+        .filter(|s| s.contains("-issue-"))
+        .collect()
 }
 
-/// Kill a tmux session.
-pub fn kill_tmux_session(session_name: &str) -> Result<(), AgentError> {
-    let status = Command::new("tmux")
-        .args(["kill-session", "-t", session_name])
-        .status()
-        .map_err(|e| AgentError::ProcessError(format!("Failed to kill tmux session: {}", e)))?;
-
-    if !status.success() {
-        return Err(AgentError::ProcessError(
-            "Failed to kill tmux session".to_string(),
-        ));
-    }
-
-    Ok(())
+/// List all sessions (no filtering).
+pub fn list_all_sessions() -> Vec<String> {
+    session_backend().list()
 }
 
-/// Capture the content of a tmux pane.
-fn capture_tmux_pane(session_name: &str) -> Option<String> {
-    let output = Command::new("tmux")
-        .args(["capture-pane", "-t", session_name, "-p", "-S", "-50"])
-        .output()
-        .ok()?;
+/// Send a message to the agent running in a session, as if typed by the user.
+///
+/// Multi-line prompts are pasted so they arrive as one message.
+pub fn send_to_session(session_name: &str, text: &str) -> Result<(), AgentError> {
+    session_backend().send_message(session_name, text)
+}
 
-    if output.status.success() {
-        Some(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        None
-    }
+/// Kill a session.
+pub fn kill_session(session_name: &str) -> Result<(), AgentError> {
+    session_backend().kill(session_name)
 }
 
 /// Check if Claude Code is idle (waiting for input).
 /// Returns true if the last lines indicate Claude is waiting for user input.
 fn is_claude_idle(pane_content: &str) -> bool {
-    let lines: Vec<&str> = pane_content.lines().collect();
-
-    // Skip empty lines from the end to find the last meaningful line
-    let last_lines: Vec<&str> = lines
-        .iter()
-        .rev()
-        .filter(|l| !l.trim().is_empty())
-        .take(5)
-        .copied()
-        .collect();
-
-    for line in &last_lines {
+    // Claude Code shows ">" prompt when waiting for input
+    last_lines(pane_content, 5).iter().any(|line| {
         // Trim only leading whitespace to preserve trailing context
         let trimmed = line.trim_start();
+        trimmed == ">" || trimmed.starts_with("> ")
+    }) || claude_dialog(pane_content).is_some()
+}
 
-        // Claude Code shows ">" prompt when waiting for input
-        if trimmed == ">" || trimmed.starts_with("> ") {
-            return true;
-        }
+/// Detect the dialog Claude Code is showing at the bottom of the pane.
+fn claude_dialog(pane_content: &str) -> Option<AgentDialog> {
+    for line in last_lines(pane_content, 5) {
+        let trimmed = line.trim();
 
         // Claude Code shows selection dialog when asking a question
         // Pattern: "Enter to select · Tab/Arrow keys to navigate · Esc to cancel"
         if trimmed.contains("Enter to select") {
-            return true;
+            return Some(AgentDialog::Selection);
         }
 
         // Claude Code shows authorization prompt
         // Pattern: "Esc to cancel" at the end of permission dialogs
         if trimmed == "Esc to cancel" {
-            return true;
+            return Some(AgentDialog::Permission);
         }
     }
-
-    false
+    None
 }
 
-/// Start a monitoring thread for the tmux session.
-/// If `already_awaiting` is true, skip the first idle notification (used when resuming).
-fn start_tmux_monitoring_with_state(
-    session_id: String,
-    tmux_name: String,
-    worktree_path: std::path::PathBuf,
-    already_awaiting: bool,
-    agent_type: CodingAgentType,
-) {
-    use super::traits::get_agent;
-
-    thread::spawn(move || {
-        let agent = get_agent(&agent_type);
-        let mut was_idle = already_awaiting;
-        let mut idle_notified = already_awaiting;
-
-        loop {
-            thread::sleep(Duration::from_secs(5));
-
-            // Update stats from git diff
-            let (lines_added, lines_deleted, files_changed) = get_diff_stats(&worktree_path);
-
-            let stats = AgentStats {
-                lines_output: 0, // We don't track output lines with tmux
-                lines_added,
-                lines_deleted,
-                files_changed,
-            };
-
-            // Keep a copy for notification message
-            let stats_copy = stats.clone();
-
-            // Update session
-            let mut manager = SessionManager::load();
-            manager.update_stats(&session_id, stats);
-            let _ = manager.save();
-
-            // Check if tmux session is still running
-            if !is_tmux_session_running(&tmux_name) {
-                // Session ended - mark as completed
-                let new_status = AgentStatus::Completed { exit_code: 0 };
-
-                let mut manager = SessionManager::load();
-                manager.update_status(&session_id, new_status);
-                let _ = manager.save();
-
-                // Send notification using agent name
-                if let Some(session) = manager.get(&session_id) {
-                    let title = agent.name();
-                    let message = format!("Session ended for issue #{}", session.issue_number);
-                    send_notification(title, &message);
-                }
-
-                break;
-            }
-
-            // Check if agent is idle (waiting for user input)
-            if let Some(pane_content) = capture_tmux_pane(&tmux_name) {
-                let is_idle = agent.is_idle(&pane_content);
-
-                if is_idle && !was_idle {
-                    // Agent just became idle - update status to Awaiting
-                    let mut manager = SessionManager::load();
-                    manager.update_status(&session_id, AgentStatus::Awaiting);
-                    let _ = manager.save();
-
-                    // Send notification only once
-                    if !idle_notified {
-                        if let Some(session) = manager.get(&session_id) {
-                            let title = agent.name();
-                            let message = format!(
-                                "Awaiting input for issue #{} (+{} -{})",
-                                session.issue_number,
-                                stats_copy.lines_added,
-                                stats_copy.lines_deleted
-                            );
-                            send_notification(title, &message);
-                        }
-                        idle_notified = true;
-                    }
-                } else if !is_idle && was_idle {
-                    // Agent started working again - update status to Running
-                    let mut manager = SessionManager::load();
-                    manager.update_status(&session_id, AgentStatus::Running);
-                    let _ = manager.save();
-                    // Reset notification flag so we can notify again when idle
-                    idle_notified = false;
-                }
-
-                was_idle = is_idle;
-            }
-        }
-    });
-}
-
-/// Start a monitoring thread for a new tmux session.
-fn start_tmux_monitoring(
-    session_id: String,
-    tmux_name: String,
-    worktree_path: std::path::PathBuf,
-    agent_type: CodingAgentType,
-) {
-    start_tmux_monitoring_with_state(session_id, tmux_name, worktree_path, false, agent_type);
-}
-
-/// Resume monitoring threads for all running sessions.
-///
-/// This should be called when the TUI starts to ensure stats are updated
-/// for sessions that were started in a previous process.
-pub fn resume_monitoring_for_running_sessions() {
-    let manager = SessionManager::load();
-
-    for session in manager.running() {
-        let tmux_name = tmux_session_name(&session.project, session.issue_number);
-
-        // Only start monitoring if tmux session is actually running
-        if is_tmux_session_running(&tmux_name) {
-            // Pass the current awaiting state to avoid duplicate notifications
-            let already_awaiting = session.is_awaiting();
-            start_tmux_monitoring_with_state(
-                session.id.clone(),
-                tmux_name,
-                session.worktree_path.clone(),
-                already_awaiting,
-                session.agent_type.clone(),
-            );
-        }
-    }
+/// Last `count` non-empty lines of a pane, bottom first.
+fn last_lines(pane_content: &str, count: usize) -> Vec<&str> {
+    pane_content
+        .lines()
+        .rev()
+        .filter(|l| !l.trim().is_empty())
+        .take(count)
+        .collect()
 }
 
-/// Kill an agent by session ID (kills the tmux session).
+/// Kill an agent by session ID (kills its session), letting the next queued dispatch start.
 pub fn kill_agent(session_id: &str) -> Result<(), AgentError> {
     let manager = SessionManager::load();
 
     if let Some(session) = manager.get(session_id)
         && session.is_running()
     {
-        // Build tmux session name and kill it
-        let tmux_name = tmux_session_name(&session.project, session.issue_number);
-        let _ = kill_tmux_session(&tmux_name);
+        let _ = kill_session(&session.session_name());
 
         // Update status
-        let mut manager = SessionManager::load();
-        manager.update_status(
-            session_id,
-            AgentStatus::Failed {
-                error: "Killed by user".to_string(),
-            },
-        );
-        manager.save()?;
+        SessionManager::update(|manager| {
+            manager.update_status(
+                session_id,
+                AgentStatus::Failed {
+                    error: "Killed by user".to_string(),
+                },
+            )
+        })?;
+        start_queued_sessions();
     }
 
     Ok(())
 }
 
-/// Create a PR from a completed session.
-pub fn create_pr(session: &AgentSession, base_branch: Option<&str>) -> Result<String, AgentError> {
-    // Build base args
-    let mut args = vec![
-        "pr".to_string(),
-        "create".to_string(),
-        "--title".to_string(),
-        format!("Fix #{}: {}", session.issue_number, session.issue_title),
-        "--body".to_string(),
-        format!(
-            "Closes #{}\n\nAutomatically generated by Claude Code.",
-            session.issue_number
-        ),
-    ];
-
-    // Add --base if we have a base branch
-    if let Some(base) = base_branch {
-        args.push("--base".to_string());
-        args.push(base.to_string());
-    }
-
-    let output = Command::new("gh")
-        .current_dir(&session.worktree_path)
-        .args(&args)
-        .output()
-        .map_err(|e| AgentError::ProcessError(format!("Failed to create PR: {}", e)))?;
-
-    if output.status.success() {
-        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        Ok(url)
-    } else {
-        Err(AgentError::ProcessError(format!(
-            "gh pr create failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        )))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agents::monitor::test_support::fake_env;
 
     #[test]
     fn build_issue_prompt_with_body() {
@@ -646,6 +531,57 @@ mod tests {
         assert!(!prompt.contains("Additional instructions:"));
     }
 
+    #[test]
+    fn build_review_feedback_prompt_lists_threads_and_skips_author_comments() {
+        use crate::agents::build_review_feedback_prompt;
+        use crate::github::{CommentInfo, PullRequestDetail, ReviewThread};
+
+        let comment = |author: &str, body: &str| CommentInfo {
+            id: 1,
+            author: author.to_string(),
+            body: body.to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        };
+        let pr = PullRequestDetail {
+            number: 12,
+            title: "Add cache".to_string(),
+            body: None,
+            html_url: "https://github.com/test/test/pull/12".to_string(),
+            labels: vec![],
+            state: "Open".to_string(),
+            assignees: vec![],
+            author: "octocat".to_string(),
+            draft: false,
+            head_ref: "cache".to_string(),
+            head_sha: "abc123".to_string(),
+            base_ref: "main".to_string(),
+            mergeable: None,
+            requested_reviewers: vec![],
+            head_repo: None,
+            head_clone_url: None,
+            maintainer_can_modify: false,
+            comments: vec![],
+        };
+        let threads = vec![ReviewThread {
+            comment_id: 501,
+            path: "src/cache.rs".to_string(),
+            line: Some(42),
+            comments: vec![comment("alice", "Evict the oldest entry")],
+        }];
+        let comments = vec![
+            comment("bob", "Please add a changelog entry"),
+            comment("octocat", "Thanks, will do"),
+        ];
+
+        let prompt = build_review_feedback_prompt(&pr, &threads, &comments, Some("Keep it small"));
+        assert!(prompt.contains("Address the review feedback on PR #12: Add cache"));
+        assert!(prompt.contains("### src/cache.rs:42\n@alice: Evict the oldest entry"));
+        assert!(prompt.contains("@bob: Please add a changelog entry"));
+        assert!(!prompt.contains("Thanks, will do"));
+        assert!(prompt.contains("do not push"));
+        assert!(prompt.contains("Additional instructions:\nKeep it small"));
+    }
+
     #[test]
     fn idle_detection_simple_prompt() {
         // Simple prompt on its own line
@@ -695,4 +631,46 @@ mod tests {
         let content = "Bash command\nuv run python --version\nDo you want to proceed?\n1. Yes\n2. Yes, and don't ask again\nEsc to cancel\n";
         assert!(is_claude_idle(content));
     }
+
+    fn launch<'a>(worktree: &'a Path, args: &'a [String]) -> SessionLaunch<'a> {
+        SessionLaunch {
+            id: None,
+            number: 42,
+            attempt: None,
+            title: "Fix parser".to_string(),
+            project: "proj",
+            worktree_path: worktree,
+            branch_name: "issue-42",
+            agent_type: &CodingAgentType::Claude,
+            prompt: "Implement #42",
+            args,
+            pr_url: None,
+        }
+    }
+
+    #[test]
+    fn launch_in_worktree_starts_and_records_session() {
+        let (env, backend, root) = fake_env();
+        let args = vec!["--model".to_string(), "haiku".to_string()];
+
+        let session = launch_in_worktree(&env, launch(&root, &args)).unwrap();
+
+        let fake = backend.session("proj-issue-42").unwrap();
+        assert_eq!(fake.cwd, root);
+        let command = fake.command.unwrap();
+        assert!(command.contains("claude"));
+        assert!(command.contains("'--model' 'haiku'"));
+        assert!(command.contains("Implement #42"));
+
+        let manager = SessionManager::load_from(env.sessions_file.clone());
+        let saved = manager.get(&session.id).unwrap();
+        assert_eq!(saved.issue_number, 42);
+        assert_eq!(saved.branch_name, "issue-42");
+        assert!(saved.is_running());
+
+        let err = launch_in_worktree(&env, launch(&root, &args)).unwrap_err();
+        assert!(err.to_string().contains("already running for #42"));
+
+        let _ = fs::remove_dir_all(root);
+    }
 }
@@ -0,0 +1,223 @@
+//! Coding agents defined in the configuration file.
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use regex::Regex;
+
+use super::traits::{shell_args, shell_quote, CodingAgent};
+use crate::config::CustomAgentConfig;
+
+/// Agent driven by a `custom_agents` entry of the configuration.
+pub struct CustomAgent {
+    config: CustomAgentConfig,
+    idle_patterns: Vec<Regex>,
+}
+
+impl CustomAgent {
+    /// Build an agent from its definition. Invalid idle patterns are ignored.
+    pub fn new(config: CustomAgentConfig) -> Self {
+        let idle_patterns = config
+            .idle_patterns
+            .iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect();
+        Self {
+            config,
+            idle_patterns,
+        }
+    }
+
+    /// Look up a custom agent in the configuration file.
+    pub fn from_config(name: &str) -> Option<Self> {
+        let config = crate::config::load_config().ok()?;
+        config.get_custom_agent(name).cloned().map(Self::new)
+    }
+
+    /// Stand-in for an agent that is no longer in the configuration:
+    /// runs a command named after it with the prompt as argument.
+    pub fn missing(name: &str) -> Self {
+        Self::new(CustomAgentConfig {
+            name: name.to_string(),
+            launch: format!("{} {{prompt}}", name),
+            ..Default::default()
+        })
+    }
+
+    /// `export` statements for the agent's environment variables.
+    fn env_exports(&self) -> String {
+        let mut vars: Vec<_> = self.config.env.iter().collect();
+        vars.sort();
+        vars.iter()
+            .map(|(key, value)| format!("export {}={}; ", key, shell_quote(value)))
+            .collect()
+    }
+
+    /// Substitute placeholders in a command template.
+    ///
+    /// Profile arguments go to `{args}`, or right after the command when the template has none.
+    fn render(
+        &self,
+        template: &str,
+        worktree_path: Option<&Path>,
+        prompt: &str,
+        args: &[String],
+    ) -> String {
+        let mut command = template.to_string();
+        if command.contains("{args}") {
+            command = command.replace("{args}", shell_args(args).trim_start());
+        } else if !args.is_empty() {
+            let program_end = command.find(char::is_whitespace).unwrap_or(command.len());
+            command.insert_str(program_end, &shell_args(args));
+        }
+        if command.contains("{prompt_file}") {
+            let path = write_prompt_file(prompt)
+                .map(|p| p.display().to_string())
+                .unwrap_or_default();
+            command = command.replace("{prompt_file}", &shell_quote(&path));
+        }
+        if let Some(worktree_path) = worktree_path {
+            let worktree = shell_quote(&worktree_path.display().to_string());
+            command = command.replace("{worktree}", &worktree);
+        }
+        // Last, so placeholders inside the prompt are left alone
+        command.replace("{prompt}", &shell_quote(prompt))
+    }
+
+    /// Run the non-interactive command and return its output.
+    pub fn generate(&self, prompt: &str) -> Result<String, Box<dyn Error>> {
+        let template = self.config.generate.as_deref().ok_or_else(|| {
+            format!("Custom agent '{}' has no generate command", self.config.name)
+        })?;
+
+        let output = Command::new("bash")
+            .args(["-c", &self.render(template, None, prompt, &[])])
+            .envs(&self.config.env)
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("{} failed: {}", self.config.name, stderr).into());
+        }
+
+        let content = String::from_utf8(output.stdout)?;
+        Ok(content.trim().to_string())
+    }
+}
+
+impl CodingAgent for CustomAgent {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn cli_command(&self) -> &str {
+        self.config.launch.split_whitespace().next().unwrap_or_default()
+    }
+
+    fn is_idle(&self, pane_content: &str) -> bool {
+        pane_content
+            .lines()
+            .rev()
+            .filter(|l| !l.trim().is_empty())
+            .take(10)
+            .any(|line| self.idle_patterns.iter().any(|re| re.is_match(line)))
+    }
+
+    fn build_launch_command(&self, worktree_path: &Path, prompt: &str, args: &[String]) -> String {
+        format!(
+            "cd {} && {}{}",
+            shell_quote(&worktree_path.display().to_string()),
+            self.env_exports(),
+            self.render(&self.config.launch, Some(worktree_path), prompt, args)
+        )
+    }
+
+    fn build_interactive_command(&self) -> String {
+        format!("{}{}", self.env_exports(), self.cli_command())
+    }
+}
+
+/// Write a prompt to a file for agents that read it from disk.
+fn write_prompt_file(prompt: &str) -> std::io::Result<PathBuf> {
+    let dir = super::cache_dir().join("prompts");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.md", super::new_session_id()));
+    std::fs::write(&path, prompt)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(launch: &str) -> CustomAgent {
+        CustomAgent::new(CustomAgentConfig {
+            name: "aider".to_string(),
+            launch: launch.to_string(),
+            idle_patterns: vec![r"^> $".to_string(), "(".to_string()],
+            generate: Some("printf '%s|%s' \"$GREETING\" {prompt}".to_string()),
+            env: [("GREETING".to_string(), "it's me".to_string())].into(),
+        })
+    }
+
+    #[test]
+    fn launch_command_substitutes_placeholders() {
+        let agent = agent("aider --yes --message {prompt} --dir {worktree}");
+        let cmd = agent.build_launch_command(Path::new("/tmp/wt"), "Fix 'this' {worktree}", &[]);
+
+        assert_eq!(
+            cmd,
+            "cd '/tmp/wt' && export GREETING='it'\\''s me'; \
+            aider --yes --message 'Fix '\\''this'\\'' {worktree}' --dir '/tmp/wt'"
+        );
+        assert_eq!(agent.cli_command(), "aider");
+        assert_eq!(agent.build_interactive_command(), "export GREETING='it'\\''s me'; aider");
+    }
+
+    #[test]
+    fn launch_command_places_profile_args() {
+        let args = vec!["--model".to_string(), "sonnet".to_string()];
+
+        let inserted = agent("aider --yes {prompt}").build_launch_command(Path::new("/w"), "go", &args);
+        assert!(inserted.ends_with("aider '--model' 'sonnet' --yes 'go'"));
+
+        let placed = agent("aider --yes {prompt} {args}").build_launch_command(Path::new("/w"), "go", &args);
+        assert!(placed.ends_with("aider --yes 'go' '--model' 'sonnet'"));
+    }
+
+    #[test]
+    fn launch_command_writes_prompt_file() {
+        let agent = agent("aider --message-file {prompt_file}");
+        let cmd = agent.build_launch_command(Path::new("/tmp/wt"), "Implement #42", &[]);
+
+        let path = cmd
+            .split("--message-file ")
+            .nth(1)
+            .unwrap()
+            .trim_matches('\'')
+            .to_string();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "Implement #42");
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn idle_detection_uses_configured_patterns() {
+        let agent = agent("aider {prompt}");
+        assert!(agent.is_idle("Applied edit to src/lib.rs\n> \n\n"));
+        assert!(!agent.is_idle("Thinking...\n"));
+
+        let silent = CustomAgent::missing("codex");
+        assert!(!silent.is_idle("> \n"));
+        assert_eq!(silent.cli_command(), "codex");
+    }
+
+    #[test]
+    fn generate_runs_command_with_env() {
+        let agent = agent("aider {prompt}");
+        assert_eq!(agent.generate("hello world").unwrap(), "it's me|hello world");
+
+        let err = CustomAgent::missing("codex").generate("hi").unwrap_err();
+        assert!(err.to_string().contains("no generate command"));
+    }
+}
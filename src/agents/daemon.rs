@@ -0,0 +1,251 @@
+//! Session daemon: monitoring that outlives the TUI.
+//!
+//! `assistant daemon` runs the session monitor and serves it on a Unix socket in the
+//! cache directory. Clients send one JSON request per connection; a `subscribe`
+//! connection then receives [`SessionEvent`]s as JSON lines.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::monitor::Command;
+use super::{cache_dir, AgentSession};
+
+/// Request sent to the daemon
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(not(unix), allow(dead_code))]
+#[serde(tag = "request", rename_all = "snake_case")]
+enum Request {
+    /// Stream session events, starting with the watched sessions
+    Subscribe,
+    Watch { session: Box<AgentSession> },
+    Refresh,
+    Verify { id: String },
+}
+
+/// Path of the daemon socket
+pub fn socket_path() -> PathBuf {
+    cache_dir().join("daemon.sock")
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::sync::mpsc::{self, Sender};
+    use std::thread;
+
+    use super::{socket_path, Request};
+    use crate::agents::monitor::{fall_back_to_local, spawn_monitor, Command, SessionEnv};
+    use crate::agents::{subscribe_setup_output, SessionEvent, SetupOutput};
+
+    /// Check whether a daemon accepts connections on `path`.
+    pub fn is_running_at(path: &Path) -> bool {
+        UnixStream::connect(path).is_ok()
+    }
+
+    /// Send a request to the daemon at `path`.
+    fn request_at(path: &Path, request: &Request) -> std::io::Result<UnixStream> {
+        let mut stream = UnixStream::connect(path)?;
+        let line = serde_json::to_string(request).map_err(std::io::Error::other)?;
+        writeln!(stream, "{}", line)?;
+        Ok(stream)
+    }
+
+    /// Forward the daemon's events to `events`.
+    /// When the daemon goes away, the in-process monitor takes over the subscription.
+    fn subscribe_at(
+        path: &Path,
+        events: Sender<SessionEvent>,
+    ) -> Result<(), Sender<SessionEvent>> {
+        let Ok(stream) = request_at(path, &Request::Subscribe) else {
+            return Err(events);
+        };
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else { break };
+                let Ok(event) = serde_json::from_str::<SessionEvent>(&line) else {
+                    continue;
+                };
+                if events.send(event).is_err() {
+                    return;
+                }
+            }
+            let _ = fall_back_to_local().send(Command::Subscribe(events));
+        });
+        Ok(())
+    }
+
+    /// Pass a monitor command to the daemon at `path`, giving it back on failure.
+    pub fn send_at(path: &Path, command: Command) -> Result<(), Command> {
+        let request = match command {
+            Command::Subscribe(events) => {
+                return subscribe_at(path, events).map_err(Command::Subscribe);
+            }
+            Command::Watch(session) => Request::Watch { session },
+            Command::Refresh => Request::Refresh,
+            Command::Verify(id) => Request::Verify { id },
+        };
+        match request_at(path, &request) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(match request {
+                Request::Watch { session } => Command::Watch(session),
+                Request::Verify { id } => Command::Verify(id),
+                _ => Command::Refresh,
+            }),
+        }
+    }
+
+    /// Serve one client connection.
+    fn handle(stream: UnixStream, monitor: Sender<Command>) -> std::io::Result<()> {
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line)?;
+        let request: Request = serde_json::from_str(&line).map_err(std::io::Error::other)?;
+
+        match request {
+            Request::Watch { session } => {
+                let _ = monitor.send(Command::Watch(session));
+            }
+            Request::Refresh => {
+                let _ = monitor.send(Command::Refresh);
+            }
+            Request::Verify { id } => {
+                let _ = monitor.send(Command::Verify(id));
+            }
+            Request::Subscribe => {
+                let (tx, rx) = mpsc::channel();
+                // Bells rung here go to the client's terminal, the daemon may have none
+                let bells = crate::notify::subscribe_bells();
+                let bell_tx = tx.clone();
+                thread::spawn(move || {
+                    for sequence in bells {
+                        if bell_tx.send(SessionEvent::Bell { sequence }).is_err() {
+                            break;
+                        }
+                    }
+                });
+                // Setup output of the queued dispatches the daemon starts
+                let setup = subscribe_setup_output();
+                let setup_tx = tx.clone();
+                thread::spawn(move || {
+                    for SetupOutput { worktree, line } in setup {
+                        if setup_tx.send(SessionEvent::Setup { worktree, line }).is_err() {
+                            break;
+                        }
+                    }
+                });
+                let _ = monitor.send(Command::Subscribe(tx));
+                let mut stream = stream;
+                for event in rx {
+                    let line = serde_json::to_string(&event).map_err(std::io::Error::other)?;
+                    writeln!(stream, "{}", line)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Accept clients until the listener fails.
+    fn serve(listener: UnixListener, monitor: Sender<Command>) {
+        for stream in listener.incoming().flatten() {
+            let monitor = monitor.clone();
+            thread::spawn(move || handle(stream, monitor));
+        }
+    }
+
+    /// Run the daemon until it is killed.
+    pub fn run() -> std::io::Result<()> {
+        let path = socket_path();
+        if is_running_at(&path) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                format!("A daemon is already running on {}", path.display()),
+            ));
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // Left behind by a daemon that was killed
+        let _ = std::fs::remove_file(&path);
+
+        let listener = UnixListener::bind(&path)?;
+        println!("Monitoring agent sessions, listening on {}", path.display());
+        serve(listener, spawn_monitor(SessionEnv::system()));
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::agents::backend::SessionBackend;
+        use crate::agents::monitor::test_support::{fake_env, session};
+        use std::time::Duration;
+
+        #[test]
+        fn daemon_forwards_watch_and_events() {
+            let (env, backend, root) = fake_env();
+            std::fs::create_dir_all(&root).unwrap();
+            let path = root.join("daemon.sock");
+            let listener = UnixListener::bind(&path).unwrap();
+            let monitor = spawn_monitor(env);
+            thread::spawn(move || serve(listener, monitor));
+            assert!(is_running_at(&path));
+
+            let (tx, events) = mpsc::channel();
+            assert!(send_at(&path, Command::Subscribe(tx)).is_ok());
+
+            let session = session("a", 7, &root);
+            backend.create("proj-issue-7", &root, None).unwrap();
+            assert!(send_at(&path, Command::Watch(Box::new(session))).is_ok());
+
+            let event = events.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert!(matches!(event, SessionEvent::Added { session } if session.issue_number == 7));
+
+            let missing = root.join("missing.sock");
+            assert!(!is_running_at(&missing));
+            assert!(matches!(send_at(&missing, Command::Refresh), Err(Command::Refresh)));
+
+            let _ = std::fs::remove_dir_all(root);
+        }
+    }
+}
+
+/// Check whether the daemon is running.
+pub fn is_running() -> bool {
+    #[cfg(unix)]
+    {
+        unix::is_running_at(&socket_path())
+    }
+    #[cfg(not(unix))]
+    {
+        false
+    }
+}
+
+/// Pass a monitor command to the daemon, giving it back when the daemon is unreachable.
+pub(super) fn send(command: Command) -> Result<(), Command> {
+    #[cfg(unix)]
+    {
+        unix::send_at(&socket_path(), command)
+    }
+    #[cfg(not(unix))]
+    {
+        Err(command)
+    }
+}
+
+/// Run the session daemon: monitor all sessions and serve clients until killed.
+pub fn run_daemon() -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        unix::run()
+    }
+    #[cfg(not(unix))]
+    {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "The session daemon requires Unix sockets",
+        ))
+    }
+}
@@ -1,22 +1,60 @@
 //! Agent management for dispatching issues to Claude Code and other agents.
 
+mod asciicast;
+mod attempts;
+mod auto_pr;
+mod backend;
 mod claude;
+mod custom;
+mod daemon;
+mod monitor;
 mod opencode;
+mod policy;
+mod prompt;
+mod queue;
+mod resume;
 mod session;
+mod setup;
 mod traits;
+mod transcript;
+mod verify;
 mod worktree;
 
+pub use asciicast::{cast_path, Cast};
+pub use attempts::{compare_attempts, dispatch_attempts, keep_attempt, AttemptSummary};
+pub use backend::{
+    run_supervisor_from_args as run_session_supervisor, session_backend, Attachment,
+    NativeBackend, SessionBackend, SessionKey, TmuxBackend, SUPERVISOR_ARG,
+};
 pub use claude::{
-    attach_tmux_command, create_pr, dispatch_to_agent, dispatch_to_claude, is_tmux_session_running,
-    kill_agent, kill_tmux_session, launch_agent_interactive, list_all_tmux_sessions,
-    list_tmux_sessions, resume_monitoring_for_running_sessions, tmux_session_name, ClaudeCodeAgent,
+    dispatch_plan_first, dispatch_to_agent, dispatch_to_claude, dispatch_to_pr_worktree,
+    is_session_running, issue_session_name, kill_agent, kill_session, launch_agent_interactive,
+    list_all_sessions, list_issue_sessions, resolve_agent_profile, send_to_session,
+    ClaudeCodeAgent,
+};
+pub use custom::CustomAgent;
+pub use daemon::{is_running as is_daemon_running, run_daemon};
+pub use monitor::{
+    refresh_session_stats, start_queued_sessions, start_session_monitor,
+    subscribe_session_events, verify_session, watch_session, SessionEvent,
 };
 pub use opencode::OpencodeAgent;
+pub use prompt::{
+    build_dispatch_prompt, gather_prompt_context, is_bot, LinkedIssue, PromptContext,
+};
+pub use queue::{cancel_queued, move_queued, queue_dispatch, QueuedLaunch};
+pub use resume::{build_resume_prompt, resume_session};
 pub use session::{AgentSession, AgentStats, AgentStatus, SessionManager};
-pub use traits::{get_agent, CodingAgent};
+pub use setup::{subscribe_setup_output, SetupOutput};
+pub use traits::{available_agents, get_agent, AgentDialog, CodingAgent, DialogAnswer};
+pub use transcript::{run_transcript_from_args as run_transcript, strip_ansi, TRANSCRIPT_ARG};
+pub use verify::{build_verify_feedback_prompt, run_verification, Verification, VerifyStatus};
 pub use worktree::{
-    create_worktree, create_worktree_with_branch, get_diff_stats, list_orphaned_worktrees,
-    list_worktrees, open_in_ide, prune_worktrees, remove_worktree, WorktreeError, WorktreeInfo,
+    commit_all, create_attempt_worktree, create_worktree, create_worktree_for_pr,
+    create_worktree_with_branch, current_branch, default_branch, get_changed_files, get_diff,
+    get_diff_stats, get_uncommitted_diff, has_uncommitted_changes, head_sha,
+    list_orphaned_worktrees, list_worktrees, open_in_ide, pr_worktree_path, prune_worktrees,
+    push_branch, remove_worktree, PushTarget, WorktreeError, WorktreeInfo,
 };
 
 use std::path::PathBuf;
@@ -83,26 +121,6 @@ pub fn new_session_id() -> String {
     Uuid::new_v4().to_string()
 }
 
-/// Send a macOS notification with sound
-#[cfg(target_os = "macos")]
-pub fn send_notification(title: &str, message: &str) {
-    use std::process::Command;
-    let script = format!(
-        "display notification \"{}\" with title \"{}\" sound name \"Glass\"",
-        message.replace('"', "\\\"").replace('\n', " "),
-        title.replace('"', "\\\"")
-    );
-    // Use output() instead of spawn() to ensure the command completes
-    let _ = Command::new("osascript")
-        .args(["-e", &script])
-        .output();
-}
-
-#[cfg(not(target_os = "macos"))]
-pub fn send_notification(_title: &str, _message: &str) {
-    // No-op on non-macOS platforms
-}
-
 /// Build the prompt for dispatching an issue to a coding agent.
 pub fn build_issue_prompt(
     issue: &crate::github::IssueDetail,
@@ -126,3 +144,53 @@ pub fn build_issue_prompt(
 
     prompt
 }
+
+/// Build the prompt asking an agent to address review feedback on a pull request.
+///
+/// Comments written by the PR author are skipped; they are usually replies, not requests.
+pub fn build_review_feedback_prompt(
+    pr: &crate::github::PullRequestDetail,
+    threads: &[crate::github::ReviewThread],
+    comments: &[crate::github::CommentInfo],
+    additional_instructions: Option<&str>,
+) -> String {
+    let mut prompt = format!(
+        "Address the review feedback on PR #{}: {}\n\n",
+        pr.number, pr.title
+    );
+
+    if !threads.is_empty() {
+        prompt.push_str("Unresolved review threads:\n");
+        for thread in threads {
+            match thread.line {
+                Some(line) => prompt.push_str(&format!("\n### {}:{}\n", thread.path, line)),
+                None => prompt.push_str(&format!("\n### {}\n", thread.path)),
+            }
+            for comment in &thread.comments {
+                prompt.push_str(&format!("@{}: {}\n", comment.author, comment.body));
+            }
+        }
+    }
+
+    let comments: Vec<_> = comments.iter().filter(|c| c.author != pr.author).collect();
+    if !comments.is_empty() {
+        prompt.push_str("\nPR comments:\n");
+        for comment in comments {
+            prompt.push_str(&format!("\n@{}: {}\n", comment.author, comment.body));
+        }
+    }
+
+    prompt.push_str(&format!(
+        "\nCommit your changes when you are done, but do not push. Do not commit `{}`.",
+        crate::pr_review::FEEDBACK_FILE
+    ));
+
+    if let Some(instructions) = additional_instructions
+        && !instructions.trim().is_empty()
+    {
+        prompt.push_str("\n\n---\n\nAdditional instructions:\n");
+        prompt.push_str(instructions);
+    }
+
+    prompt
+}
@@ -0,0 +1,1014 @@
+//! Session monitor: one thread watching every running agent session.
+//!
+//! The monitor refreshes diff stats, detects idle agents and ended sessions, writes
+//! the changes to the sessions file in a single locked update per pass, and pushes
+//! [`SessionEvent`]s to subscribers such as the TUI. It also starts the transcript of
+//! each watched session, recording screen captures when the backend cannot stream,
+//! and launches queued dispatches as running sessions end. Verification commands run
+//! in the background when agents go idle or end, and their outcomes are recorded here.
+//! Sessions breaking their project's policy are stopped, or their waiting agent reminded.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use super::backend::{session_backend, SessionBackend, SessionKey};
+use super::auto_pr;
+use super::daemon;
+use super::policy::{self, Enforcement};
+use super::queue;
+use super::traits::{get_agent, CodingAgent};
+use super::transcript::{new_screen_lines, Transcript};
+use super::verify::{self, build_verify_feedback_prompt, Verification};
+use super::{
+    agents_log_dir, get_diff_stats, sessions_file, AgentError, AgentSession, AgentStats,
+    AgentStatus, SessionManager,
+};
+use crate::config::{
+    AutoPrConfig, NotifierConfig, NotifyEvent, SessionPolicy, TranscriptConfig, VerifyConfig,
+};
+use crate::notify::{self, Notification};
+
+/// Interval between monitoring passes
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Where agent sessions run and are recorded.
+#[derive(Clone)]
+pub(super) struct SessionEnv {
+    pub backend: Arc<dyn SessionBackend>,
+    pub sessions_file: PathBuf,
+    pub log_dir: PathBuf,
+    pub transcript: TranscriptConfig,
+    pub notifiers: Vec<NotifierConfig>,
+    /// Agents running at once before queued dispatches wait
+    pub max_sessions: usize,
+    /// Automatic pull request settings, by project
+    pub auto_pr: HashMap<String, AutoPrConfig>,
+    /// Verification commands, by project
+    pub verify: HashMap<String, VerifyConfig>,
+    /// Time and diff size limits, by project
+    pub policies: HashMap<String, SessionPolicy>,
+}
+
+impl SessionEnv {
+    /// The configured backend and the assistant's cache directory
+    pub fn system() -> Self {
+        let config = crate::config::load_config().ok();
+        Self {
+            backend: session_backend(),
+            sessions_file: sessions_file(),
+            log_dir: agents_log_dir(),
+            transcript: config.as_ref().map(|c| c.transcript.clone()).unwrap_or_default(),
+            notifiers: config.as_ref().map(|c| c.notifiers()).unwrap_or_default(),
+            auto_pr: config.as_ref().map(|c| c.auto_pr()).unwrap_or_default(),
+            verify: config.as_ref().map(|c| c.verify()).unwrap_or_default(),
+            policies: config.as_ref().map(|c| c.policies()).unwrap_or_default(),
+            max_sessions: config.unwrap_or_default().max_concurrent_sessions(),
+        }
+    }
+
+    /// Locked read-modify-write of the sessions file
+    pub fn update_sessions<T>(
+        &self,
+        f: impl FnOnce(&mut SessionManager) -> T,
+    ) -> std::io::Result<T> {
+        SessionManager::update_at(self.sessions_file.clone(), f)
+    }
+}
+
+/// Change to an agent session, as observed by the monitor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// A session is being watched: sent after a dispatch, and for every watched
+    /// session when subscribing
+    Added { session: Box<AgentSession> },
+    /// New diff stats for a session
+    StatsUpdated { id: String, stats: AgentStats },
+    /// A session changed status (awaiting input, running again, ended, failed to start)
+    StatusChanged { id: String, status: AgentStatus },
+    /// Verification commands started or finished for a session
+    Verified { id: String, verification: Verification },
+    /// A bell rung by the daemon, for the TUI to write to its terminal
+    Bell { sequence: String },
+    /// Worktree setup output of a queued dispatch started by the daemon
+    Setup { worktree: String, line: String },
+}
+
+/// Requests to the monitor thread
+pub(super) enum Command {
+    Watch(Box<AgentSession>),
+    Subscribe(Sender<SessionEvent>),
+    /// Run a pass now instead of waiting for the next interval, which also starts
+    /// queued dispatches when slots are free
+    Refresh,
+    /// Run the verification commands of a session now
+    Verify(String),
+}
+
+/// A watched session and what the monitor last saw of it
+struct Watched {
+    session: AgentSession,
+    agent: Box<dyn CodingAgent>,
+    was_idle: bool,
+    idle_notified: bool,
+    /// When the agent started waiting for input
+    idle_since: Option<Instant>,
+    /// A reminder was sent for the current wait for input
+    reminded: bool,
+    /// An automatic pull request was started for the session
+    auto_pr_started: bool,
+    /// Failed verifications sent back to the agent
+    feedback_rounds: u32,
+    /// Last screen capture, when the transcript is recorded from captures
+    captured: Option<String>,
+}
+
+/// Send a notification about a watched session.
+fn notify_session(
+    notifiers: &[NotifierConfig],
+    watched: &Watched,
+    event: NotifyEvent,
+    message: &str,
+) {
+    let session = &watched.session;
+    let notification = Notification::new(event, watched.agent.name(), message)
+        .for_issue(&session.project, session.issue_number);
+    notify::dispatch(notifiers, notification);
+}
+
+/// Owner of the watched sessions' state.
+pub(super) struct Monitor {
+    env: SessionEnv,
+    watched: HashMap<String, Watched>,
+    subscribers: Vec<Sender<SessionEvent>>,
+    /// Sessions whose verification commands are running
+    verifying: HashSet<String>,
+    /// Outcomes of the verifications run in the background, with the session id
+    verified_tx: Sender<(String, Verification)>,
+    verified_rx: Receiver<(String, Verification)>,
+    /// Queued dispatches started in the background, with the claimed session
+    started_tx: Sender<(AgentSession, Result<AgentSession, AgentError>)>,
+    started_rx: Receiver<(AgentSession, Result<AgentSession, AgentError>)>,
+}
+
+impl Monitor {
+    pub fn new(env: SessionEnv) -> Self {
+        let (verified_tx, verified_rx) = mpsc::channel();
+        let (started_tx, started_rx) = mpsc::channel();
+        Self {
+            env,
+            watched: HashMap::new(),
+            subscribers: Vec::new(),
+            verifying: HashSet::new(),
+            verified_tx,
+            verified_rx,
+            started_tx,
+            started_rx,
+        }
+    }
+
+    /// Watch the running sessions of the sessions file, marking the ones whose
+    /// terminal session is gone as completed.
+    pub fn resume(&mut self) {
+        let backend = Arc::clone(&self.env.backend);
+        let running = self
+            .env
+            .update_sessions(|manager| {
+                manager.sync_with(&*backend);
+                manager.running().into_iter().cloned().collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        for session in running {
+            self.watch(session);
+        }
+    }
+
+    /// Start watching a session.
+    /// A session already awaiting input does not notify again (used when resuming).
+    pub fn watch(&mut self, session: AgentSession) {
+        let already_awaiting = session.is_awaiting();
+        let streamed = self
+            .env
+            .backend
+            .record(&session.session_name(), &session.log_file, &self.env.transcript)
+            .unwrap_or(false);
+        self.emit(SessionEvent::Added {
+            session: Box::new(session.clone()),
+        });
+        self.watched.insert(
+            session.id.clone(),
+            Watched {
+                agent: get_agent(&session.agent_type),
+                session,
+                was_idle: already_awaiting,
+                idle_notified: already_awaiting,
+                idle_since: already_awaiting.then(Instant::now),
+                reminded: false,
+                auto_pr_started: false,
+                feedback_rounds: 0,
+                captured: (!streamed).then(String::new),
+            },
+        );
+    }
+
+    /// Add a subscriber, first sending it the sessions currently watched.
+    pub fn subscribe(&mut self, subscriber: Sender<SessionEvent>) {
+        for watched in self.watched.values() {
+            let event = SessionEvent::Added {
+                session: Box::new(watched.session.clone()),
+            };
+            if subscriber.send(event).is_err() {
+                return;
+            }
+        }
+        self.subscribers.push(subscriber);
+    }
+
+    /// Send an event to subscribers, dropping the ones that went away.
+    fn emit(&mut self, event: SessionEvent) {
+        self.subscribers.retain(|s| s.send(event.clone()).is_ok());
+    }
+
+    /// One monitoring pass over all watched sessions, then start queued dispatches.
+    pub fn poll(&mut self) {
+        self.finish_verifications();
+        self.finish_starts();
+        if !self.watched.is_empty() {
+            self.poll_watched();
+        }
+        self.start_queued();
+    }
+
+    /// Run the verification commands of a session in the background, unless they are
+    /// already running or its project has none.
+    pub fn verify(&mut self, session: &AgentSession) {
+        let Some(config) = self.env.verify.get(&session.project) else {
+            return;
+        };
+        if !self.verifying.insert(session.id.clone()) {
+            return;
+        }
+        verify::spawn(session, config.clone(), self.verified_tx.clone());
+        self.emit(SessionEvent::Verified {
+            id: session.id.clone(),
+            verification: Verification::running(),
+        });
+    }
+
+    /// Verify a session by id, watched or not.
+    fn verify_by_id(&mut self, id: &str) {
+        let session = match self.watched.get(id) {
+            Some(watched) => Some(watched.session.clone()),
+            None => SessionManager::load_from(self.env.sessions_file.clone()).get(id).cloned(),
+        };
+        if let Some(session) = session {
+            self.verify(&session);
+        }
+    }
+
+    /// Record finished verifications, sending failures back to idle agents that have
+    /// feedback rounds left.
+    fn finish_verifications(&mut self) {
+        let finished: Vec<_> = self.verified_rx.try_iter().collect();
+        for (id, verification) in finished {
+            self.verifying.remove(&id);
+            let _ = self
+                .env
+                .update_sessions(|m| m.set_verification(&id, verification.clone()));
+
+            if verification.failed()
+                && let Some(watched) = self.watched.get_mut(&id)
+                && let Some(config) = self.env.verify.get(&watched.session.project)
+                && watched.was_idle
+                && watched.feedback_rounds < config.feedback_rounds
+            {
+                let prompt = build_verify_feedback_prompt(&verification);
+                let name = watched.session.session_name();
+                if self.env.backend.send_message(&name, &prompt).is_ok() {
+                    watched.feedback_rounds += 1;
+                }
+            }
+            self.emit(SessionEvent::Verified { id, verification });
+        }
+    }
+
+    /// Launch queued dispatches in the background, in queue order, while slots are free.
+    fn start_queued(&mut self) {
+        while let Some(session) = queue::claim_next(&self.env) {
+            queue::spawn(self.env.clone(), session, self.started_tx.clone());
+        }
+    }
+
+    /// Watch the queued dispatches that started, and mark the ones that failed to.
+    fn finish_starts(&mut self) {
+        let finished: Vec<_> = self.started_rx.try_iter().collect();
+        for (session, started) in finished {
+            match started {
+                Ok(started) => self.watch(started),
+                Err(e) => {
+                    let status = AgentStatus::Failed {
+                        error: format!("Failed to start: {}", e),
+                    };
+                    let id = session.id.clone();
+                    let _ = self.env.update_sessions(|m| m.update_status(&id, status.clone()));
+                    let agent = get_agent(&session.agent_type);
+                    let message =
+                        format!("Failed to start agent for issue #{}: {}", session.issue_number, e);
+                    let notification =
+                        Notification::new(NotifyEvent::Failed, agent.name(), &message)
+                            .for_issue(&session.project, session.issue_number);
+                    notify::dispatch(&self.env.notifiers, notification);
+                    self.emit(SessionEvent::StatusChanged { id, status });
+                }
+            }
+        }
+    }
+
+    fn poll_watched(&mut self) {
+        // Diff stats of all worktrees, computed in parallel
+        let stats: HashMap<String, AgentStats> = thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .watched
+                .values()
+                .map(|w| {
+                    let id = w.session.id.clone();
+                    let path = &w.session.worktree_path;
+                    scope.spawn(move || {
+                        let (lines_added, lines_deleted, files_changed) = get_diff_stats(path);
+                        let stats = AgentStats {
+                            lines_output: 0, // We don't track output lines from sessions
+                            lines_added,
+                            lines_deleted,
+                            files_changed,
+                        };
+                        (id, stats)
+                    })
+                })
+                .collect();
+            handles.into_iter().filter_map(|h| h.join().ok()).collect()
+        });
+
+        let mut events = Vec::new();
+        let mut ended = Vec::new();
+        let mut pull_requests = Vec::new();
+        let mut verifications = Vec::new();
+        for (id, watched) in &mut self.watched {
+            if let Some(stats) = stats.get(id)
+                && *stats != watched.session.stats
+            {
+                watched.session.stats = stats.clone();
+                events.push(SessionEvent::StatsUpdated {
+                    id: id.clone(),
+                    stats: stats.clone(),
+                });
+            }
+
+            let name = watched.session.session_name();
+            if !self.env.backend.is_running(&name) {
+                ended.push(id.clone());
+                // A session killed meanwhile, by the user or a policy, did not complete
+                let stopped = SessionManager::load_from(self.env.sessions_file.clone())
+                    .get(id)
+                    .is_none_or(|s| !s.is_running());
+                if stopped {
+                    continue;
+                }
+                // Session ended - mark as completed
+                let message = format!("Session ended for issue #{}", watched.session.issue_number);
+                notify_session(&self.env.notifiers, watched, NotifyEvent::Completed, &message);
+                events.push(SessionEvent::StatusChanged {
+                    id: id.clone(),
+                    status: AgentStatus::Completed { exit_code: 0 },
+                });
+                if let Some(config) = self.env.auto_pr.get(&watched.session.project)
+                    && !watched.auto_pr_started
+                    && auto_pr::due(config, true, None)
+                {
+                    pull_requests.push((watched.session.clone(), config.clone()));
+                }
+                if self.env.verify.get(&watched.session.project).is_some_and(|c| c.on_complete) {
+                    verifications.push(watched.session.clone());
+                }
+                continue;
+            }
+
+            // Check if agent is idle (waiting for user input)
+            let Some(pane_content) = self.env.backend.capture(&name) else {
+                continue;
+            };
+            if let Some(captured) = &mut watched.captured {
+                let lines = new_screen_lines(captured, &pane_content);
+                if !lines.is_empty() {
+                    let _ = Transcript::open(&watched.session.log_file, &self.env.transcript)
+                        .and_then(|mut t| t.write_lines(&lines));
+                }
+                *captured = pane_content.clone();
+            }
+            let is_idle = watched.agent.is_idle(&pane_content);
+            if is_idle && !watched.was_idle {
+                events.push(SessionEvent::StatusChanged {
+                    id: id.clone(),
+                    status: AgentStatus::Awaiting,
+                });
+                // The plan may have been approved since the session was watched
+                let planning = SessionManager::load_from(self.env.sessions_file.clone())
+                    .get(id)
+                    .is_some_and(|s| s.planning);
+                if !planning
+                    && self.env.verify.get(&watched.session.project).is_some_and(|c| c.on_idle)
+                {
+                    verifications.push(watched.session.clone());
+                }
+                // Send notification only once
+                if !watched.idle_notified {
+                    let message = if planning {
+                        format!("Plan ready for review for issue #{}", watched.session.issue_number)
+                    } else {
+                        format!(
+                            "Awaiting input for issue #{} (+{} -{})",
+                            watched.session.issue_number,
+                            watched.session.stats.lines_added,
+                            watched.session.stats.lines_deleted
+                        )
+                    };
+                    let notifiers = &self.env.notifiers;
+                    notify_session(notifiers, watched, NotifyEvent::Awaiting, &message);
+                    watched.idle_notified = true;
+                }
+            } else if !is_idle && watched.was_idle {
+                events.push(SessionEvent::StatusChanged {
+                    id: id.clone(),
+                    status: AgentStatus::Running,
+                });
+                // Reset notification flag so we can notify again when idle
+                watched.idle_notified = false;
+            }
+            watched.was_idle = is_idle;
+
+            if !is_idle {
+                watched.idle_since = None;
+                watched.reminded = false;
+            } else if watched.idle_since.is_none() {
+                watched.idle_since = Some(Instant::now());
+            }
+            let idle_for = watched.idle_since.map(|since| since.elapsed());
+
+            // Stop the session, keeping its worktree, pause it or remind about it per its policy
+            let enforcement = self
+                .env
+                .policies
+                .get(&watched.session.project)
+                .and_then(|p| policy::check(p, &watched.session, idle_for, Utc::now()));
+            match enforcement {
+                Some(Enforcement::Stop(reason)) => {
+                    let _ = self.env.backend.kill(&name);
+                    let message =
+                        format!("{} (issue #{})", reason, watched.session.issue_number);
+                    notify_session(&self.env.notifiers, watched, NotifyEvent::Failed, &message);
+                    events.push(SessionEvent::StatusChanged {
+                        id: id.clone(),
+                        status: AgentStatus::Failed { error: reason },
+                    });
+                    ended.push(id.clone());
+                    continue;
+                }
+                Some(Enforcement::Pause(reason)) => {
+                    let _ = self.env.backend.send_keys(&name, &[SessionKey::Escape]);
+                    let _ = self.env.update_sessions(|m| {
+                        if let Some(saved) = m.get_mut(id) {
+                            saved.paused = Some(reason.clone());
+                        }
+                    });
+                    let message =
+                        format!("{} (issue #{})", reason, watched.session.issue_number);
+                    notify_session(&self.env.notifiers, watched, NotifyEvent::Awaiting, &message);
+                    watched.session.paused = Some(reason);
+                    // Waiting for the user now, without a second notification when it shows
+                    if !watched.was_idle {
+                        events.push(SessionEvent::StatusChanged {
+                            id: id.clone(),
+                            status: AgentStatus::Awaiting,
+                        });
+                        watched.was_idle = true;
+                        watched.idle_since = Some(Instant::now());
+                    }
+                    watched.idle_notified = true;
+                }
+                Some(Enforcement::Remind(message)) if !watched.reminded => {
+                    notify_session(&self.env.notifiers, watched, NotifyEvent::Awaiting, &message);
+                    watched.reminded = true;
+                }
+                _ => {}
+            }
+
+            if let Some(config) = self.env.auto_pr.get(&watched.session.project)
+                && !watched.auto_pr_started
+                && auto_pr::due(config, false, idle_for)
+            {
+                watched.auto_pr_started = true;
+                pull_requests.push((watched.session.clone(), config.clone()));
+            }
+        }
+
+        for id in &ended {
+            self.watched.remove(id);
+        }
+        for (session, config) in pull_requests {
+            auto_pr::spawn(self.env.clone(), session, config);
+        }
+        for session in verifications {
+            self.verify(&session);
+        }
+
+        // Record all changes in one update of the sessions file. Status changes only
+        // apply to sessions still running there: a kill recorded meanwhile wins.
+        let applied = self
+            .env
+            .update_sessions(|manager| {
+                events
+                    .into_iter()
+                    .filter(|event| match event {
+                        SessionEvent::StatsUpdated { id, stats } => {
+                            manager.update_stats(id, stats.clone())
+                        }
+                        SessionEvent::StatusChanged { id, status } => {
+                            manager.get(id).is_some_and(|s| s.is_running())
+                                && manager.update_status(id, status.clone())
+                        }
+                        SessionEvent::Added { .. }
+                        | SessionEvent::Verified { .. }
+                        | SessionEvent::Bell { .. }
+                        | SessionEvent::Setup { .. } => true,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        for event in applied {
+            self.emit(event);
+        }
+    }
+
+    /// Serve commands and poll at a fixed interval until every handle is dropped.
+    fn run(mut self, commands: Receiver<Command>) {
+        let mut next_poll = Instant::now() + POLL_INTERVAL;
+        loop {
+            let timeout = next_poll.saturating_duration_since(Instant::now());
+            match commands.recv_timeout(timeout) {
+                Ok(Command::Watch(session)) => self.watch(*session),
+                Ok(Command::Subscribe(subscriber)) => self.subscribe(subscriber),
+                Ok(Command::Verify(id)) => self.verify_by_id(&id),
+                Ok(Command::Refresh) => {
+                    self.poll();
+                    next_poll = Instant::now() + POLL_INTERVAL;
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    self.poll();
+                    next_poll = Instant::now() + POLL_INTERVAL;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    }
+}
+
+/// Start a monitor thread watching the running sessions, returning its command channel.
+pub(super) fn spawn_monitor(env: SessionEnv) -> Sender<Command> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut monitor = Monitor::new(env);
+        monitor.resume();
+        monitor.run(rx);
+    });
+    tx
+}
+
+/// In-process monitor, started on first use.
+fn local_monitor() -> &'static Sender<Command> {
+    static MONITOR: OnceLock<Sender<Command>> = OnceLock::new();
+    MONITOR.get_or_init(|| spawn_monitor(SessionEnv::system()))
+}
+
+/// Whether this process relies on the daemon for monitoring
+static USE_DAEMON: AtomicBool = AtomicBool::new(false);
+
+/// Stop relying on the daemon and monitor in-process instead.
+pub(super) fn fall_back_to_local() -> &'static Sender<Command> {
+    USE_DAEMON.store(false, Ordering::SeqCst);
+    local_monitor()
+}
+
+/// Send a command to the daemon when connected to it, or to the in-process monitor.
+fn send(command: Command) {
+    if USE_DAEMON.load(Ordering::SeqCst) {
+        if let Err(command) = daemon::send(command) {
+            let _ = fall_back_to_local().send(command);
+        }
+    } else {
+        let _ = local_monitor().send(command);
+    }
+}
+
+/// Start monitoring sessions, including those from a previous process.
+///
+/// Uses the daemon when it is running, and a monitor thread in this process otherwise.
+/// Returns true when the daemon is used.
+pub fn start_session_monitor() -> bool {
+    if daemon::is_running() {
+        USE_DAEMON.store(true, Ordering::SeqCst);
+        true
+    } else {
+        local_monitor();
+        false
+    }
+}
+
+/// Have the monitor watch a newly launched session.
+pub fn watch_session(session: &AgentSession) {
+    send(Command::Watch(Box::new(session.clone())));
+}
+
+/// Receive session events from the monitor.
+pub fn subscribe_session_events() -> Receiver<SessionEvent> {
+    let (tx, rx) = mpsc::channel();
+    send(Command::Subscribe(tx));
+    rx
+}
+
+/// Ask the monitor for an immediate pass, e.g. to get fresh diff stats.
+pub fn refresh_session_stats() {
+    send(Command::Refresh);
+}
+
+/// Have the monitor run the verification commands of a session.
+pub fn verify_session(id: &str) {
+    send(Command::Verify(id.to_string()));
+}
+
+/// Ask the monitor to start queued dispatches, e.g. after a session was killed.
+pub fn start_queued_sessions() {
+    send(Command::Refresh);
+}
+
+/// Fixtures shared by the tests of the session modules
+#[cfg(test)]
+pub(super) mod test_support {
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use super::SessionEnv;
+    use crate::agents::backend::FakeBackend;
+    use crate::agents::{new_session_id, AgentSession};
+    use crate::config::CodingAgentType;
+
+    /// Session environment on a fake backend, with files in a temporary directory
+    pub fn fake_env() -> (SessionEnv, Arc<FakeBackend>, PathBuf) {
+        let root = std::env::temp_dir().join(format!("assistant-test-{}", new_session_id()));
+        let backend = Arc::new(FakeBackend::default());
+        let env = SessionEnv {
+            backend: backend.clone(),
+            sessions_file: root.join("sessions.json"),
+            log_dir: root.join("agents"),
+            transcript: Default::default(),
+            notifiers: Vec::new(),
+            max_sessions: 3,
+            auto_pr: Default::default(),
+            verify: Default::default(),
+            policies: Default::default(),
+        };
+        (env, backend, root)
+    }
+
+    /// Claude Code session on issue `number` of project `proj`, working in `root`
+    pub fn session(id: &str, number: u64, root: &Path) -> AgentSession {
+        AgentSession::new(
+            id.to_string(),
+            number,
+            "Fix parser".to_string(),
+            "proj".to_string(),
+            0,
+            root.join(format!("{}.log", id)),
+            root.to_path_buf(),
+            format!("issue-{}", number),
+            CodingAgentType::Claude,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::{fake_env, session};
+    use super::*;
+    use crate::agents::VerifyStatus;
+
+    #[test]
+    fn monitor_tracks_idle_and_completion() {
+        let (env, backend, root) = fake_env();
+        env.update_sessions(|m| {
+            m.add(session("a", 1, &root));
+            m.add(session("b", 2, &root));
+        })
+        .unwrap();
+        backend.create("proj-issue-1", &root, Some("claude")).unwrap();
+
+        let mut monitor = Monitor::new(env.clone());
+        let (tx, events) = mpsc::channel();
+        monitor.subscribe(tx);
+        monitor.resume();
+
+        // Session b has no terminal session: completed on resume
+        let sessions = || SessionManager::load_from(env.sessions_file.clone());
+        let status = |id: &str| sessions().get(id).unwrap().status.clone();
+        assert_eq!(status("b"), AgentStatus::Completed { exit_code: 0 });
+        assert!(matches!(events.try_recv(), Ok(SessionEvent::Added { session }) if session.id == "a"));
+        assert!(events.try_recv().is_err());
+
+        backend.set_screen("proj-issue-1", "Reading files...\n");
+        monitor.poll();
+        assert_eq!(status("a"), AgentStatus::Running);
+        assert!(events.try_recv().is_err());
+
+        backend.set_screen("proj-issue-1", "Done.\n> \n\n");
+        monitor.poll();
+        assert_eq!(status("a"), AgentStatus::Awaiting);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(SessionEvent::StatusChanged { id, status: AgentStatus::Awaiting }) if id == "a"
+        ));
+
+        backend.set_screen("proj-issue-1", "Editing src/lib.rs\n");
+        monitor.poll();
+        assert_eq!(status("a"), AgentStatus::Running);
+
+        backend.end("proj-issue-1");
+        monitor.poll();
+        assert_eq!(status("a"), AgentStatus::Completed { exit_code: 0 });
+        let last = events.try_iter().last();
+        assert!(matches!(
+            last,
+            Some(SessionEvent::StatusChanged { status: AgentStatus::Completed { .. }, .. })
+        ));
+        assert!(monitor.watched.is_empty());
+
+        // The fake backend cannot stream output: the transcript is built from captures
+        // and kept after the session ended
+        let transcript = std::fs::read_to_string(root.join("a.log")).unwrap();
+        assert_eq!(transcript, "Reading files...\nDone.\n>\nEditing src/lib.rs\n");
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn monitor_verifies_idle_sessions_and_feeds_back_failures() {
+        let (env, backend, root) = fake_env();
+        let verify = VerifyConfig {
+            commands: vec!["echo '1 test failed'; exit 1".to_string()],
+            feedback_rounds: 1,
+            ..VerifyConfig::default()
+        };
+        let env = SessionEnv {
+            verify: HashMap::from([("proj".to_string(), verify)]),
+            ..env
+        };
+        env.update_sessions(|m| m.add(session("a", 1, &root))).unwrap();
+        backend.create("proj-issue-1", &root, Some("claude")).unwrap();
+
+        let mut monitor = Monitor::new(env.clone());
+        let (tx, events) = mpsc::channel();
+        monitor.subscribe(tx);
+        monitor.resume();
+
+        // Poll until the verification started by going idle has finished
+        let verify_once = |monitor: &mut Monitor| {
+            backend.set_screen("proj-issue-1", "Working...\n");
+            monitor.poll();
+            backend.set_screen("proj-issue-1", "Done.\n> \n");
+            monitor.poll();
+            let mut outcomes = Vec::new();
+            for _ in 0..100 {
+                monitor.finish_verifications();
+                outcomes.extend(events.try_iter().filter_map(|event| match event {
+                    SessionEvent::Verified { verification, .. } => Some(verification.status),
+                    _ => None,
+                }));
+                if outcomes.len() == 2 {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            outcomes
+        };
+
+        assert_eq!(verify_once(&mut monitor), vec![VerifyStatus::Running, VerifyStatus::Failed]);
+        let sessions = SessionManager::load_from(env.sessions_file.clone());
+        let verification = sessions.get("a").unwrap().verification.clone().unwrap();
+        assert_eq!(verification.output_tail, "$ echo '1 test failed'; exit 1\n1 test failed");
+        let messages = backend.session("proj-issue-1").unwrap().messages;
+        assert_eq!(messages, vec![build_verify_feedback_prompt(&verification)]);
+
+        // Feedback rounds are used up: the next failure is only recorded
+        assert_eq!(verify_once(&mut monitor), vec![VerifyStatus::Running, VerifyStatus::Failed]);
+        assert_eq!(backend.session("proj-issue-1").unwrap().messages.len(), 1);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn monitor_leaves_killed_sessions_alone() {
+        let (env, backend, root) = fake_env();
+        let verify = VerifyConfig {
+            commands: vec!["true".to_string()],
+            ..VerifyConfig::default()
+        };
+        let env = SessionEnv {
+            verify: HashMap::from([("proj".to_string(), verify)]),
+            ..env
+        };
+        env.update_sessions(|m| m.add(session("a", 1, &root))).unwrap();
+        backend.create("proj-issue-1", &root, Some("claude")).unwrap();
+
+        let mut monitor = Monitor::new(env.clone());
+        let (tx, events) = mpsc::channel();
+        monitor.subscribe(tx);
+        monitor.resume();
+        let _ = events.try_iter().count();
+
+        // Killed by the user: no completion, verification or pull request follows
+        let killed = AgentStatus::Failed {
+            error: "Killed by user".to_string(),
+        };
+        env.update_sessions(|m| m.update_status("a", killed.clone())).unwrap();
+        backend.end("proj-issue-1");
+        monitor.poll();
+
+        assert!(monitor.watched.is_empty());
+        assert!(events.try_iter().next().is_none());
+        let sessions = SessionManager::load_from(env.sessions_file.clone());
+        assert_eq!(sessions.get("a").unwrap().status, killed);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn monitor_stops_sessions_over_their_policy_limits() {
+        let (env, backend, root) = fake_env();
+        let policy = SessionPolicy {
+            max_duration_minutes: Some(0),
+            ..SessionPolicy::default()
+        };
+        let env = SessionEnv {
+            policies: HashMap::from([("proj".to_string(), policy)]),
+            ..env
+        };
+        env.update_sessions(|m| m.add(session("a", 1, &root))).unwrap();
+        backend.create("proj-issue-1", &root, Some("claude")).unwrap();
+
+        let mut monitor = Monitor::new(env.clone());
+        monitor.resume();
+        backend.set_screen("proj-issue-1", "Reading files...\n");
+        monitor.poll();
+
+        let sessions = SessionManager::load_from(env.sessions_file.clone());
+        assert!(matches!(
+            &sessions.get("a").unwrap().status,
+            AgentStatus::Failed { error } if error.contains("running for more than 0 minutes")
+        ));
+        assert!(!backend.is_running("proj-issue-1"));
+        assert!(monitor.watched.is_empty());
+        // The worktree is kept to resume the work
+        assert!(root.exists());
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn monitor_pauses_sessions_over_their_diff_limit() {
+        let (env, backend, root) = fake_env();
+        std::fs::create_dir_all(&root).unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(&root)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        std::fs::write(root.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(&["add", "lib.rs"]);
+        git(&["commit", "-qm", "init"]);
+        std::fs::write(root.join("lib.rs"), "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n")
+            .unwrap();
+
+        let policy = SessionPolicy {
+            max_diff_lines: Some(2),
+            ..SessionPolicy::default()
+        };
+        let env = SessionEnv {
+            policies: HashMap::from([("proj".to_string(), policy)]),
+            ..env
+        };
+        env.update_sessions(|m| m.add(session("a", 1, &root))).unwrap();
+        backend.create("proj-issue-1", &root, Some("claude")).unwrap();
+
+        let mut monitor = Monitor::new(env.clone());
+        let (tx, events) = mpsc::channel();
+        monitor.subscribe(tx);
+        monitor.resume();
+        backend.set_screen("proj-issue-1", "Editing src/lib.rs\n");
+        monitor.poll();
+
+        // Interrupted and waiting for the user, the session still running
+        assert!(backend.is_running("proj-issue-1"));
+        assert_eq!(backend.session("proj-issue-1").unwrap().keys, vec![SessionKey::Escape]);
+        let sessions = || SessionManager::load_from(env.sessions_file.clone());
+        let paused = sessions().get("a").unwrap().clone();
+        assert_eq!(paused.status, AgentStatus::Awaiting);
+        assert!(paused.paused.unwrap().contains("diff of 3 lines, over the 2 line limit"));
+        assert!(events.try_iter().any(|e| matches!(
+            e,
+            SessionEvent::StatusChanged { status: AgentStatus::Awaiting, .. }
+        )));
+
+        // Paused once: the agent may go on when told to
+        backend.set_screen("proj-issue-1", "Done.\n> \n");
+        monitor.poll();
+        backend.set_screen("proj-issue-1", "Editing src/main.rs\n");
+        monitor.poll();
+        assert_eq!(backend.session("proj-issue-1").unwrap().keys.len(), 1);
+        assert_eq!(sessions().get("a").unwrap().status, AgentStatus::Running);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn monitor_starts_queued_dispatches_when_slots_free_up() {
+        let (env, backend, root) = fake_env();
+        let env = SessionEnv {
+            max_sessions: 1,
+            ..env
+        };
+        let queued = |id: &str, number: u64| {
+            let mut session = session(id, number, &root);
+            session.status = AgentStatus::Queued;
+            session.queued = Some(queue::QueuedLaunch {
+                // Not a repository: starting fails when the worktree is created
+                local_path: root.join("missing"),
+                base_branch: None,
+                prompt: "Implement it".to_string(),
+                args: Vec::new(),
+            });
+            session
+        };
+        env.update_sessions(|m| {
+            m.add(session("a", 1, &root));
+            m.add(queued("q1", 2));
+            m.add(queued("q2", 3));
+        })
+        .unwrap();
+        backend.create("proj-issue-1", &root, Some("claude")).unwrap();
+
+        let mut monitor = Monitor::new(env.clone());
+        let (tx, events) = mpsc::channel();
+        monitor.subscribe(tx);
+        monitor.resume();
+        monitor.poll();
+
+        // The only slot is taken by session a
+        let sessions = || SessionManager::load_from(env.sessions_file.clone());
+        assert_eq!(sessions().queued().len(), 2);
+
+        // Each start runs in the background and fails, freeing the slot for the next one
+        backend.end("proj-issue-1");
+        let mut failed: Vec<String> = Vec::new();
+        for _ in 0..100 {
+            monitor.poll();
+            failed.extend(events.try_iter().filter_map(|event| match event {
+                SessionEvent::StatusChanged { id, status: AgentStatus::Failed { error } } => {
+                    assert!(error.starts_with("Failed to start"));
+                    Some(id)
+                }
+                _ => None,
+            }));
+            if failed.len() == 2 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        assert_eq!(failed, vec!["q1", "q2"]);
+        assert!(sessions().queued().is_empty());
+        assert!(matches!(sessions().get("q1").unwrap().status, AgentStatus::Failed { .. }));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -2,17 +2,18 @@
 
 use std::path::Path;
 
-use super::traits::CodingAgent;
+use super::backend::SessionKey;
+use super::traits::{shell_args, AgentDialog, CodingAgent, DialogAnswer};
 
 /// Opencode agent for processing GitHub issues.
 pub struct OpencodeAgent;
 
 impl CodingAgent for OpencodeAgent {
-    fn name(&self) -> &'static str {
+    fn name(&self) -> &str {
         "Opencode"
     }
 
-    fn cli_command(&self) -> &'static str {
+    fn cli_command(&self) -> &str {
         "opencode"
     }
 
@@ -20,12 +21,40 @@ impl CodingAgent for OpencodeAgent {
         is_opencode_idle(pane_content)
     }
 
-    fn build_launch_command(&self, worktree_path: &Path, prompt: &str) -> String {
+    fn dialog(&self, pane_content: &str) -> Option<AgentDialog> {
+        let permission = pane_content
+            .lines()
+            .rev()
+            .filter(|l| !l.trim().is_empty())
+            .take(10)
+            .any(is_permission_line);
+        permission.then_some(AgentDialog::Permission)
+    }
+
+    fn build_resume_command(&self, worktree_path: &Path, args: &[String]) -> Option<String> {
+        Some(format!(
+            "cd '{}' && opencode --continue{}",
+            worktree_path.display(),
+            shell_args(args)
+        ))
+    }
+
+    fn answer_keys(&self, answer: DialogAnswer) -> Vec<SessionKey> {
+        match answer {
+            DialogAnswer::Approve => vec![SessionKey::Enter],
+            // Opencode permission dialogs deny with "d"
+            DialogAnswer::Deny => vec![SessionKey::Char('d')],
+            DialogAnswer::Option(n) => vec![SessionKey::Char(char::from(b'0' + n.min(9)))],
+        }
+    }
+
+    fn build_launch_command(&self, worktree_path: &Path, prompt: &str, args: &[String]) -> String {
         // Opencode uses --prompt flag
         let escaped_prompt = prompt.replace('\'', "'\\''");
         format!(
-            "cd '{}' && opencode --prompt '{}'",
+            "cd '{}' && opencode{} --prompt '{}'",
             worktree_path.display(),
+            shell_args(args),
             escaped_prompt
         )
     }
@@ -53,13 +82,7 @@ fn is_opencode_idle(pane_content: &str) -> bool {
             return true;
         }
 
-        // Opencode permission prompt
-        if trimmed.contains("Permission required to run this tool:") {
-            return true;
-        }
-
-        // Opencode permission dialog footer
-        if trimmed.contains("enter accept") && trimmed.contains("a accept always") {
+        if is_permission_line(trimmed) {
             return true;
         }
     }
@@ -67,6 +90,14 @@ fn is_opencode_idle(pane_content: &str) -> bool {
     false
 }
 
+/// Whether a line belongs to an Opencode permission dialog.
+fn is_permission_line(line: &str) -> bool {
+    // Opencode permission prompt
+    line.contains("Permission required to run this tool:")
+        // Opencode permission dialog footer
+        || (line.contains("enter accept") && line.contains("a accept always"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,6 +127,15 @@ mod tests {
         assert!(is_opencode_idle(content));
     }
 
+    #[test]
+    fn permission_dialog_denies_with_d() {
+        let agent = OpencodeAgent;
+        let content = "$ docker ps\nenter accept  a accept always  d deny\n";
+        assert_eq!(agent.dialog(content), Some(AgentDialog::Permission));
+        assert_eq!(agent.dialog("ctrl+p command\n"), None);
+        assert_eq!(agent.answer_keys(DialogAnswer::Deny), vec![SessionKey::Char('d')]);
+    }
+
     #[test]
     fn not_idle_when_working() {
         let content = "Processing files...\nAnalyzing code...\n";
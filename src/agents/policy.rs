@@ -0,0 +1,133 @@
+//! Session policies for projects with `policy` set.
+//!
+//! On each pass the monitor checks how long a session has run, how long its agent has been
+//! waiting for input and the size of its diff against the project's limits. A session over
+//! its running time is stopped and marked failed with the reason, keeping its worktree so the
+//! work can be resumed. A diff over its size limit pauses the agent instead, for the user to
+//! review the work, and a long wait for input sends a reminder or stops the session.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use super::AgentSession;
+use crate::config::{AwaitingAction, SessionPolicy};
+
+/// What the monitor must do about a session breaking its policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Enforcement {
+    /// Stop the session, recording the reason as its error
+    Stop(String),
+    /// Interrupt the agent, keeping its session, until the user tells it to go on
+    Pause(String),
+    /// Notify that the agent is still waiting for input
+    Remind(String),
+}
+
+/// Check a running session against its project's policy.
+/// `idle_for` is how long the agent has been waiting for input, if it is.
+pub(super) fn check(
+    policy: &SessionPolicy,
+    session: &AgentSession,
+    idle_for: Option<Duration>,
+    now: DateTime<Utc>,
+) -> Option<Enforcement> {
+    // A resumed session gets its full running time again
+    let started_at = session.resumed_at.unwrap_or(session.started_at);
+    if let Some(minutes) = policy.max_duration_minutes
+        && now - started_at >= chrono::Duration::minutes(minutes as i64)
+    {
+        return Some(Enforcement::Stop(format!(
+            "Stopped by policy: running for more than {} minutes",
+            minutes
+        )));
+    }
+
+    // Paused once: the user may let the agent go on past the limit
+    let diff_lines = session.stats.lines_added + session.stats.lines_deleted;
+    if let Some(max) = policy.max_diff_lines
+        && diff_lines > max
+        && session.paused.is_none()
+    {
+        return Some(Enforcement::Pause(format!(
+            "Paused by policy: diff of {} lines, over the {} line limit",
+            diff_lines, max
+        )));
+    }
+
+    if let (Some(minutes), Some(idle_for)) = (policy.max_awaiting_minutes, idle_for)
+        && idle_for >= Duration::from_secs(minutes * 60)
+    {
+        return Some(match policy.on_awaiting {
+            AwaitingAction::Kill => Enforcement::Stop(format!(
+                "Stopped by policy: awaiting input for more than {} minutes",
+                minutes
+            )),
+            AwaitingAction::Remind => Enforcement::Remind(format!(
+                "Still awaiting input for issue #{} after {} minutes",
+                session.issue_number, minutes
+            )),
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::AgentStats;
+    use crate::config::CodingAgentType;
+
+    #[test]
+    fn limits_stop_or_remind() {
+        let mut session = AgentSession::new(
+            "a".to_string(),
+            7,
+            "Fix parser".to_string(),
+            "proj".to_string(),
+            0,
+            "a.log".into(),
+            "wt".into(),
+            "issue-7".to_string(),
+            CodingAgentType::Claude,
+        );
+        let now = session.started_at + chrono::Duration::minutes(30);
+        let hour = Duration::from_secs(3600);
+        assert_eq!(check(&SessionPolicy::default(), &session, Some(hour * 24), now), None);
+
+        let policy = SessionPolicy {
+            max_duration_minutes: Some(60),
+            max_awaiting_minutes: Some(20),
+            max_diff_lines: Some(500),
+            ..SessionPolicy::default()
+        };
+        assert_eq!(check(&policy, &session, None, now), None);
+        assert!(matches!(
+            check(&policy, &session, Some(hour), now),
+            Some(Enforcement::Remind(message)) if message.contains("issue #7 after 20 minutes")
+        ));
+        let kill = SessionPolicy {
+            on_awaiting: AwaitingAction::Kill,
+            ..policy.clone()
+        };
+        assert!(matches!(check(&kill, &session, Some(hour), now), Some(Enforcement::Stop(_))));
+
+        session.stats = AgentStats {
+            lines_added: 450,
+            lines_deleted: 100,
+            ..AgentStats::default()
+        };
+        assert!(matches!(
+            check(&policy, &session, None, now),
+            Some(Enforcement::Pause(reason)) if reason.contains("550 lines")
+        ));
+        session.paused = Some("Paused by policy".to_string());
+        assert_eq!(check(&policy, &session, None, now), None);
+
+        let later = session.started_at + chrono::Duration::minutes(61);
+        assert!(matches!(
+            check(&policy, &session, None, later),
+            Some(Enforcement::Stop(reason)) if reason.contains("more than 60 minutes")
+        ));
+    }
+}
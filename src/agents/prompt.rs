@@ -0,0 +1,395 @@
+//! Dispatch prompts built from an issue and its context: the comment thread, labels,
+//! linked issues, images and the repository's guidance files, fitted to a size budget.
+
+use std::path::Path;
+
+use regex::Regex;
+
+use super::build_issue_prompt;
+use crate::config::PromptConfig;
+use crate::github::{GitHubConfig, IssueDetail};
+
+/// Most linked issues fetched for a prompt
+const MAX_LINKED_ISSUES: usize = 5;
+
+/// Sections shorter than this once truncated are dropped instead
+const MIN_SECTION_CHARS: usize = 200;
+
+const TRUNCATED: &str = "[… truncated to fit the prompt size budget]";
+
+/// An issue related to the dispatched one
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkedIssue {
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+    pub state: String,
+    /// The dispatched issue is a sub-issue of this one
+    pub parent: bool,
+}
+
+/// Context of a dispatch prompt beyond the issue itself
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptContext {
+    /// The parent issue first, then the referenced issues
+    pub linked: Vec<LinkedIssue>,
+    /// Guidance files found in the repository, with their content
+    pub guidance: Vec<(String, String)>,
+}
+
+/// Fetch the linked issues and read the guidance files of a dispatch prompt.
+///
+/// Issues that cannot be fetched are left out.
+pub async fn gather_prompt_context(
+    github: &GitHubConfig,
+    issue: &IssueDetail,
+    local_path: Option<&Path>,
+    config: &PromptConfig,
+) -> PromptContext {
+    let mut context = PromptContext::default();
+    if config.linked_issues {
+        let parent = github.get_parent_issue(issue.number).await.ok().flatten();
+        let referenced = referenced_issues(issue, config)
+            .into_iter()
+            .filter(|&number| Some(number) != parent);
+        let numbers = parent
+            .map(|number| (number, true))
+            .into_iter()
+            .chain(referenced.map(|number| (number, false)));
+        for (number, parent) in numbers.take(MAX_LINKED_ISSUES) {
+            if let Ok(linked) = github.get_issue(number).await {
+                context.linked.push(LinkedIssue {
+                    number,
+                    title: linked.title,
+                    body: linked.body,
+                    state: linked.state,
+                    parent,
+                });
+            }
+        }
+    }
+    if let Some(local_path) = local_path {
+        context.guidance = read_guidance(local_path, &config.guidance_files);
+    }
+    context
+}
+
+/// Whether a comment author is a bot whose comments are left out of prompts
+pub fn is_bot(author: &str, config: &PromptConfig) -> bool {
+    author.ends_with("[bot]") || config.bot_authors.iter().any(|b| b.eq_ignore_ascii_case(author))
+}
+
+/// Issues referenced as `#<number>` in the issue and its comments, in order of appearance.
+pub fn referenced_issues(issue: &IssueDetail, config: &PromptConfig) -> Vec<u64> {
+    // Not preceded by a word character, `/` (URL fragments) or `&` (HTML entities)
+    let reference = Regex::new(r"(?:^|[^\w/&])#(\d+)\b").unwrap();
+    let texts = issue.body.iter().map(String::as_str).chain(
+        issue
+            .comments
+            .iter()
+            .filter(|c| !is_bot(&c.author, config))
+            .map(|c| c.body.as_str()),
+    );
+
+    let mut numbers = Vec::new();
+    for text in texts {
+        for cap in reference.captures_iter(text) {
+            if let Ok(number) = cap[1].parse::<u64>()
+                && number != issue.number
+                && !numbers.contains(&number)
+            {
+                numbers.push(number);
+            }
+        }
+    }
+    numbers
+}
+
+/// Read the guidance files present at the root of the repository.
+pub fn read_guidance(local_path: &Path, files: &[String]) -> Vec<(String, String)> {
+    files
+        .iter()
+        .filter_map(|name| {
+            let content = std::fs::read_to_string(local_path.join(name)).ok()?;
+            let content = content.trim();
+            (!content.is_empty()).then(|| (name.clone(), content.to_string()))
+        })
+        .collect()
+}
+
+/// A part of the prompt
+struct Section {
+    text: String,
+    /// Sections are trimmed from the lowest rank up; `None` is never trimmed
+    trim_rank: Option<u8>,
+    /// Trim the start instead of the end, keeping the latest content
+    keep_end: bool,
+}
+
+impl Section {
+    fn fixed(text: String) -> Self {
+        Self {
+            text,
+            trim_rank: None,
+            keep_end: false,
+        }
+    }
+
+    fn trimmable(text: String, trim_rank: u8, keep_end: bool) -> Self {
+        Self {
+            text,
+            trim_rank: Some(trim_rank),
+            keep_end,
+        }
+    }
+}
+
+/// Build the prompt of a dispatched issue with the context enabled in `config`.
+///
+/// When the prompt exceeds `config.max_chars`, the guidance files are trimmed first, then
+/// the linked issues, the oldest comments and the image list. The issue itself and the
+/// additional instructions are always kept whole.
+pub fn build_dispatch_prompt(
+    issue: &IssueDetail,
+    config: &PromptConfig,
+    context: &PromptContext,
+    additional_instructions: Option<&str>,
+) -> String {
+    let mut sections = vec![Section::fixed(build_issue_prompt(issue, None).trim_end().to_string())];
+
+    if config.labels && !issue.labels.is_empty() {
+        sections.push(Section::fixed(format!("Labels: {}", issue.labels.join(", "))));
+    }
+
+    let comments: Vec<_> = issue
+        .comments
+        .iter()
+        .filter(|c| !is_bot(&c.author, config) && !c.body.trim().is_empty())
+        .collect();
+    if config.comments && !comments.is_empty() {
+        let mut text = String::from("## Discussion");
+        for comment in &comments {
+            text.push_str(&format!(
+                "\n\n**@{}** ({}):\n{}",
+                comment.author,
+                comment.created_at,
+                comment.body.trim()
+            ));
+        }
+        sections.push(Section::trimmable(text, 2, true));
+    }
+
+    if !context.linked.is_empty() {
+        let mut text = String::new();
+        for linked in &context.linked {
+            let kind = if linked.parent { "Parent issue" } else { "Related issue" };
+            text.push_str(&format!(
+                "## {} #{}: {} ({})\n\n",
+                kind, linked.number, linked.title, linked.state
+            ));
+            if let Some(body) = &linked.body {
+                text.push_str(body.trim());
+                text.push_str("\n\n");
+            }
+        }
+        sections.push(Section::trimmable(text.trim_end().to_string(), 1, false));
+    }
+
+    if config.images {
+        let mut urls: Vec<String> = Vec::new();
+        let texts = issue.body.iter().chain(comments.iter().map(|c| &c.body));
+        for url in texts.flat_map(|text| crate::images::extract_image_urls(text)) {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        if !urls.is_empty() {
+            let list: Vec<String> = urls.iter().map(|url| format!("- {}", url)).collect();
+            let text = format!("## Images\n\n{}", list.join("\n"));
+            sections.push(Section::trimmable(text, 3, false));
+        }
+    }
+
+    for (name, content) in &context.guidance {
+        let text = format!("## Repository guidelines ({})\n\n{}", name, content);
+        sections.push(Section::trimmable(text, 0, false));
+    }
+
+    if let Some(instructions) = additional_instructions
+        && !instructions.trim().is_empty()
+    {
+        sections.push(Section::fixed(format!(
+            "---\n\nAdditional instructions:\n{}",
+            instructions
+        )));
+    }
+
+    fit_budget(&mut sections, config.max_chars);
+    join_sections(&sections)
+}
+
+fn join_sections(sections: &[Section]) -> String {
+    let parts: Vec<&str> = sections
+        .iter()
+        .map(|s| s.text.as_str())
+        .filter(|text| !text.is_empty())
+        .collect();
+    parts.join("\n\n")
+}
+
+/// Trim sections, lowest rank first, until the prompt fits in `max_chars`.
+fn fit_budget(sections: &mut [Section], max_chars: usize) {
+    let mut ranked: Vec<usize> = (0..sections.len())
+        .filter(|&i| sections[i].trim_rank.is_some())
+        .collect();
+    ranked.sort_by_key(|&i| sections[i].trim_rank);
+
+    for i in ranked {
+        let total = join_sections(sections).chars().count();
+        if total <= max_chars {
+            return;
+        }
+        let section = &mut sections[i];
+        let len = section.text.chars().count();
+        let keep = len.saturating_sub(total - max_chars + TRUNCATED.chars().count() + 1);
+        section.text = if keep < MIN_SECTION_CHARS {
+            String::new()
+        } else if section.keep_end {
+            let tail: String = section.text.chars().skip(len - keep).collect();
+            format!("{}\n{}", TRUNCATED, tail)
+        } else {
+            let head: String = section.text.chars().take(keep).collect();
+            format!("{}\n{}", head, TRUNCATED)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::CommentInfo;
+
+    fn comment(author: &str, body: &str) -> CommentInfo {
+        CommentInfo {
+            id: 1,
+            author: author.to_string(),
+            body: body.to_string(),
+            created_at: "2024-05-01".to_string(),
+        }
+    }
+
+    fn issue() -> IssueDetail {
+        IssueDetail {
+            number: 42,
+            title: "Retry failed uploads".to_string(),
+            body: Some(
+                "Uploads fail on flaky networks, see #12.\n\n![error](https://img.example/1.png)"
+                    .to_string(),
+            ),
+            html_url: "https://github.com/owner/repo/issues/42".to_string(),
+            labels: vec!["bug".to_string(), "backend".to_string()],
+            state: "Open".to_string(),
+            assignees: vec![],
+            comments: vec![
+                comment("alice", "Only retry 5xx, not 4xx. Same as #7 and #12."),
+                comment("dependabot[bot]", "Bumps reqwest, closes #99"),
+                comment("ci-helper", "Build passed"),
+                comment("bob", "Agreed, with a cap of 3 retries"),
+            ],
+        }
+    }
+
+    fn config() -> PromptConfig {
+        PromptConfig {
+            bot_authors: vec!["CI-Helper".to_string()],
+            ..PromptConfig::default()
+        }
+    }
+
+    #[test]
+    fn referenced_issues_skip_bots_self_urls_and_entities() {
+        let mut issue = issue();
+        issue.comments.push(comment(
+            "carol",
+            "See https://github.com/owner/repo/pull/5#3 and it&#39;s #42 itself, then #8",
+        ));
+        assert_eq!(referenced_issues(&issue, &config()), vec![12, 7, 8]);
+    }
+
+    #[test]
+    fn dispatch_prompt_includes_context() {
+        let context = PromptContext {
+            linked: vec![LinkedIssue {
+                number: 3,
+                title: "Reliable uploads".to_string(),
+                body: Some("Epic for upload reliability".to_string()),
+                state: "Open".to_string(),
+                parent: true,
+            }],
+            guidance: vec![("AGENTS.md".to_string(), "Run cargo test".to_string())],
+        };
+        let prompt = build_dispatch_prompt(&issue(), &config(), &context, Some("Keep it small"));
+
+        assert!(prompt.starts_with("Read and implement GitHub issue #42: Retry failed uploads"));
+        assert!(prompt.contains("Labels: bug, backend"));
+        assert!(prompt.contains("**@alice** (2024-05-01):\nOnly retry 5xx"));
+        assert!(prompt.contains("**@bob**"));
+        assert!(!prompt.contains("dependabot"));
+        assert!(!prompt.contains("Build passed"));
+        assert!(prompt.contains("## Parent issue #3: Reliable uploads (Open)"));
+        assert!(prompt.contains("## Images\n\n- https://img.example/1.png"));
+        assert!(prompt.contains("## Repository guidelines (AGENTS.md)\n\nRun cargo test"));
+        assert!(prompt.ends_with("Additional instructions:\nKeep it small"));
+    }
+
+    #[test]
+    fn dispatch_prompt_without_optional_sections() {
+        let config = PromptConfig {
+            comments: false,
+            labels: false,
+            images: false,
+            ..PromptConfig::default()
+        };
+        let prompt = build_dispatch_prompt(&issue(), &config, &PromptContext::default(), None);
+        assert_eq!(prompt, build_issue_prompt(&issue(), None).trim_end());
+    }
+
+    #[test]
+    fn dispatch_prompt_fits_budget() {
+        let mut issue = issue();
+        issue.comments = (0..50)
+            .map(|i| comment("alice", &format!("comment {} {}", i, "x".repeat(100))))
+            .collect();
+        let context = PromptContext {
+            linked: vec![],
+            guidance: vec![("CONTRIBUTING.md".to_string(), "g".repeat(5000))],
+        };
+        let config = PromptConfig {
+            max_chars: 3000,
+            ..PromptConfig::default()
+        };
+
+        let prompt = build_dispatch_prompt(&issue, &config, &context, Some("Keep it small"));
+        assert!(prompt.chars().count() <= 3000);
+        // Guidance goes first, then the oldest comments
+        assert!(!prompt.contains("CONTRIBUTING.md"));
+        assert!(prompt.contains("comment 49"));
+        assert!(!prompt.contains("comment 0 "));
+        assert!(prompt.contains(TRUNCATED));
+        assert!(prompt.contains("Retry failed uploads"));
+        assert!(prompt.ends_with("Keep it small"));
+    }
+
+    #[test]
+    fn read_guidance_skips_missing_and_empty_files() {
+        let dir = std::env::temp_dir().join(format!("assistant-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("AGENTS.md"), "Use tabs\n").unwrap();
+        std::fs::write(dir.join("CONTRIBUTING.md"), "  \n").unwrap();
+
+        let files = PromptConfig::default().guidance_files;
+        let guidance = read_guidance(&dir, &files);
+        assert_eq!(guidance, vec![("AGENTS.md".to_string(), "Use tabs".to_string())]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,180 @@
+//! Dispatch queue: issues waiting for a free agent slot.
+//!
+//! Batch dispatches are recorded in the sessions file as `Queued` sessions, in queue
+//! order, together with what is needed to launch them. The session monitor starts them
+//! one by one while fewer than `max_concurrent_sessions` agents run, so the queue
+//! survives restarts and moves on whenever a session ends or is killed. Each start runs on
+//! its own thread, so worktree setup commands do not hold up the monitor.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use super::claude::{launch_in_worktree, SessionLaunch};
+use super::monitor::{start_queued_sessions, SessionEnv};
+use super::{
+    create_worktree, new_session_id, AgentError, AgentSession, AgentStatus, SessionManager,
+};
+use crate::config::{CodingAgentType, ResolvedProfile};
+use crate::github::IssueDetail;
+
+/// What a queued dispatch needs to start
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedLaunch {
+    /// Repository the worktree is created from
+    pub local_path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+    pub prompt: String,
+    /// Extra agent CLI arguments from the profile
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+}
+
+/// Add an issue to the dispatch queue. It starts as soon as a slot is free.
+///
+/// The agent profile is resolved now; the worktree is created on start.
+pub fn queue_dispatch(
+    issue: &IssueDetail,
+    local_path: &Path,
+    project: &str,
+    agent_type: &CodingAgentType,
+    base_branch: Option<&str>,
+    prompt: &str,
+) -> Result<AgentSession, AgentError> {
+    let profile = crate::config::load_config()
+        .map(|c| c.resolve_agent_profile(project, &issue.labels, agent_type))
+        .unwrap_or_else(|_| ResolvedProfile::for_agent(agent_type.clone()));
+    let env = SessionEnv::system();
+    let session = enqueue(
+        &env,
+        issue,
+        project,
+        &profile,
+        QueuedLaunch {
+            local_path: local_path.to_path_buf(),
+            base_branch: base_branch.map(String::from),
+            prompt: prompt.to_string(),
+            args: profile.cli_args(),
+        },
+    )?;
+    start_queued_sessions();
+    Ok(session)
+}
+
+/// Record a queued session at the back of the queue.
+fn enqueue(
+    env: &SessionEnv,
+    issue: &IssueDetail,
+    project: &str,
+    profile: &ResolvedProfile,
+    launch: QueuedLaunch,
+) -> Result<AgentSession, AgentError> {
+    let id = new_session_id();
+    let mut session = AgentSession::new(
+        id.clone(),
+        issue.number,
+        issue.title.clone(),
+        project.to_string(),
+        0,
+        env.log_dir.join(format!("{}.log", id)),
+        PathBuf::new(),
+        String::new(),
+        profile.agent.clone(),
+    );
+    session.status = AgentStatus::Queued;
+    session.queued = Some(launch);
+
+    env.update_sessions(|manager| {
+        let busy = manager.list().iter().any(|s| {
+            s.project == project
+                && s.issue_number == issue.number
+                && (s.is_queued() || s.is_running())
+        });
+        if busy {
+            return Err(AgentError::SessionError(format!(
+                "#{} is already queued or running",
+                issue.number
+            )));
+        }
+        manager.add(session.clone());
+        Ok(session)
+    })?
+}
+
+/// Remove a session from the dispatch queue. Returns false if it is no longer queued.
+pub fn cancel_queued(id: &str) -> Result<bool, AgentError> {
+    let cancelled = SessionManager::update(|manager| {
+        let queued = manager.get(id).is_some_and(|s| s.is_queued());
+        if queued {
+            manager.remove(id);
+        }
+        queued
+    })?;
+    Ok(cancelled)
+}
+
+/// Move a queued session one place towards the front (`up`) or the back of the queue.
+pub fn move_queued(id: &str, up: bool) -> Result<bool, AgentError> {
+    Ok(SessionManager::update(|manager| manager.move_queued(id, up))?)
+}
+
+/// Take the next queued session if fewer than the maximum of agents run, marking it
+/// running so no other monitor starts it too.
+pub(super) fn claim_next(env: &SessionEnv) -> Option<AgentSession> {
+    env.update_sessions(|manager| {
+        if manager.running().len() >= env.max_sessions {
+            return None;
+        }
+        let id = manager.queued().first()?.id.clone();
+        manager.update_status(&id, AgentStatus::Running);
+        manager.get(&id).cloned()
+    })
+    .ok()
+    .flatten()
+}
+
+/// Start a claimed queued session on its own thread, as creating its worktree can run long
+/// setup commands, sending the outcome with the claimed session.
+pub(super) fn spawn(
+    env: SessionEnv,
+    session: AgentSession,
+    results: Sender<(AgentSession, Result<AgentSession, AgentError>)>,
+) {
+    thread::spawn(move || {
+        let started = start(&env, &session);
+        let _ = results.send((session, started));
+    });
+}
+
+/// Create the worktree of a claimed queued session and launch its agent.
+fn start(env: &SessionEnv, session: &AgentSession) -> Result<AgentSession, AgentError> {
+    let launch = session
+        .queued
+        .as_ref()
+        .ok_or_else(|| AgentError::SessionError("Not a queued dispatch".to_string()))?;
+    let (worktree_path, branch_name) = create_worktree(
+        &launch.local_path,
+        &session.project,
+        session.issue_number,
+        launch.base_branch.as_deref(),
+    )?;
+    launch_in_worktree(
+        env,
+        SessionLaunch {
+            id: Some(session.id.clone()),
+            number: session.issue_number,
+            attempt: None,
+            title: session.issue_title.clone(),
+            project: &session.project,
+            worktree_path: &worktree_path,
+            branch_name: &branch_name,
+            agent_type: &session.agent_type,
+            prompt: &launch.prompt,
+            args: &launch.args,
+            pr_url: None,
+        },
+    )
+}
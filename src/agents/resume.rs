@@ -0,0 +1,158 @@
+//! Resuming agent sessions whose terminal session is gone, e.g. after a reboot or crash.
+//!
+//! The agent is relaunched in the session's worktree under the same session id, so its
+//! transcript, stats and verification carry on. Agents that can continue their last
+//! conversation do so (`claude --continue`); others get a prompt describing the issue and
+//! the work already in the worktree.
+
+use chrono::Utc;
+
+use super::monitor::{watch_session, SessionEnv};
+use super::traits::get_agent;
+use super::{get_diff, AgentError, AgentSession, AgentStatus, SessionManager};
+use crate::pull_requests::truncate_diff;
+
+/// Diff bytes included in a continuation prompt
+const MAX_DIFF_BYTES: usize = 20_000;
+
+/// Build the prompt relaunching an agent that cannot continue its last conversation.
+pub fn build_resume_prompt(session: &AgentSession, diff: &str) -> String {
+    let mut prompt = format!(
+        "Continue working on GitHub issue #{}: {}\n\n\
+        Your previous session was interrupted. The work done so far is in this worktree, on \
+        branch `{}`.",
+        session.issue_number, session.issue_title, session.branch_name
+    );
+    if diff.trim().is_empty() {
+        prompt.push_str(" No changes were made yet.");
+    } else {
+        prompt.push_str(&format!(
+            " The current diff is:\n\n```diff\n{}```",
+            truncate_diff(diff, MAX_DIFF_BYTES)
+        ));
+    }
+    prompt.push_str("\n\nReview where the work stands, then finish implementing the issue.");
+    prompt
+}
+
+/// Relaunch the agent of an ended session in its worktree, keeping the session id.
+pub fn resume_session(id: &str) -> Result<AgentSession, AgentError> {
+    let session = resume(&SessionEnv::system(), id)?;
+    watch_session(&session);
+    Ok(session)
+}
+
+pub(super) fn resume(env: &SessionEnv, id: &str) -> Result<AgentSession, AgentError> {
+    let session = SessionManager::load_from(env.sessions_file.clone())
+        .get(id)
+        .cloned()
+        .ok_or_else(|| AgentError::SessionError(format!("No session {}", id)))?;
+    let name = session.session_name();
+    if session.is_queued() {
+        return Err(AgentError::SessionError(format!(
+            "#{} is still in the dispatch queue",
+            session.issue_number
+        )));
+    }
+    if env.backend.is_running(&name) {
+        return Err(AgentError::SessionError(format!(
+            "An agent is already running for #{}",
+            session.issue_number
+        )));
+    }
+    let worktree_path = &session.worktree_path;
+    if !worktree_path.exists() {
+        return Err(AgentError::ProcessError(format!(
+            "Worktree path does not exist: {}",
+            worktree_path.display()
+        )));
+    }
+
+    // Take an agent slot the way queued dispatches do, so the queue cannot fill it meanwhile
+    let claimed = env.update_sessions(|manager| {
+        let others = manager.running().iter().filter(|s| s.id != id).count();
+        others < env.max_sessions && manager.update_status(id, AgentStatus::Running)
+    })?;
+    if !claimed {
+        return Err(AgentError::SessionError(format!(
+            "All {} agent slots are busy, resume #{} once a session ends",
+            env.max_sessions, session.issue_number
+        )));
+    }
+
+    let agent = get_agent(&session.agent_type);
+    let command = match agent.build_resume_command(worktree_path, &session.args) {
+        Some(command) => command,
+        None => {
+            let prompt = build_resume_prompt(&session, &get_diff(worktree_path));
+            agent.build_launch_command(worktree_path, &prompt, &session.args)
+        }
+    };
+    if let Err(e) = env.backend.create(&name, worktree_path, Some(&command)) {
+        let _ = env.update_sessions(|m| m.update_status(id, session.status.clone()));
+        return Err(e);
+    }
+
+    let resumed_at = Utc::now();
+    let resumed = env.update_sessions(|manager| {
+        let saved = manager.get_mut(id)?;
+        saved.resumed_at = Some(resumed_at);
+        Some(saved.clone())
+    })?;
+    resumed.ok_or_else(|| AgentError::SessionError(format!("No session {}", id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::monitor::test_support::{fake_env, session};
+    use crate::config::CodingAgentType;
+    use std::path::Path;
+
+    #[test]
+    fn resume_relaunches_agent_under_same_session() {
+        let (env, backend, root) = fake_env();
+        std::fs::create_dir_all(&root).unwrap();
+        let mut ended = session("a", 3, &root);
+        ended.agent_type = CodingAgentType::Opencode;
+        ended.status = AgentStatus::Completed { exit_code: 0 };
+        ended.args = vec!["--model".to_string(), "sonnet".to_string()];
+        env.update_sessions(|m| m.add(ended)).unwrap();
+
+        let resumed = resume(&env, "a").unwrap();
+        assert_eq!(resumed.id, "a");
+        assert_eq!(resumed.status, AgentStatus::Running);
+        assert!(resumed.resumed_at.is_some());
+        let command = backend.session("proj-issue-3").unwrap().command.unwrap();
+        assert!(command.ends_with("opencode --continue '--model' 'sonnet'"));
+
+        let error = resume(&env, "a").unwrap_err().to_string();
+        assert!(error.contains("already running for #3"));
+        assert!(resume(&env, "missing").is_err());
+
+        // No slot left: the session stays ended
+        let mut other = session("b", 4, &root);
+        other.status = AgentStatus::Completed { exit_code: 0 };
+        env.update_sessions(|m| m.add(other)).unwrap();
+        let full = SessionEnv {
+            max_sessions: 1,
+            ..env.clone()
+        };
+        let error = resume(&full, "b").unwrap_err().to_string();
+        assert!(error.contains("All 1 agent slots are busy, resume #4"));
+        assert!(backend.session("proj-issue-4").is_none());
+        let stored = SessionManager::load_from(env.sessions_file.clone());
+        assert_eq!(stored.get("b").unwrap().status, AgentStatus::Completed { exit_code: 0 });
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn resume_prompt_includes_current_diff() {
+        let session = session("a", 3, Path::new("wt"));
+        let prompt = build_resume_prompt(&session, "+fn parse() {}\n");
+        assert!(prompt.starts_with("Continue working on GitHub issue #3: Fix parser"));
+        assert!(prompt.contains("```diff\n+fn parse() {}\n```"));
+        assert!(build_resume_prompt(&session, "").contains("No changes were made yet."));
+    }
+}
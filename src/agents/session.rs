@@ -4,14 +4,18 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use std::process::{Command, Stdio};
 
-use super::{cache_dir, sessions_file};
+use super::claude::attempt_session_name;
+use super::queue::QueuedLaunch;
+use super::sessions_file;
+use super::verify::Verification;
 use crate::config::CodingAgentType;
 
 /// Status of an agent session
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum AgentStatus {
+    /// Waiting in the dispatch queue for a free slot
+    Queued,
     Running,
     Awaiting,
     Completed { exit_code: i32 },
@@ -19,7 +23,7 @@ pub enum AgentStatus {
 }
 
 /// Statistics about an agent's work
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct AgentStats {
     /// Lines of output from the agent
     pub lines_output: usize,
@@ -61,6 +65,27 @@ pub struct AgentSession {
     /// The coding agent type used for this session
     #[serde(default)]
     pub agent_type: CodingAgentType,
+    /// Label of this attempt ("a", "b"…) when several agents work on the issue
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempt: Option<String>,
+    /// The agent was asked for an implementation plan, not yet approved
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub planning: bool,
+    /// How to launch the agent, while the session waits in the dispatch queue
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queued: Option<QueuedLaunch>,
+    /// Outcome of the last run of the project's verification commands
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<Verification>,
+    /// Extra agent CLI arguments the agent was launched with, reused to resume it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// When the agent was last relaunched in its worktree, after a reboot or crash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resumed_at: Option<DateTime<Utc>>,
+    /// Why the agent was interrupted by its project's policy, e.g. its diff size
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paused: Option<String>,
 }
 
 impl AgentSession {
@@ -91,11 +116,18 @@ impl AgentSession {
             stats: AgentStats::default(),
             pr_url: None,
             agent_type,
+            attempt: None,
+            planning: false,
+            queued: None,
+            verification: None,
+            args: Vec::new(),
+            paused: None,
+            resumed_at: None,
         }
     }
 
     /// Check if the session is still running (based on stored status only)
-    /// Note: Awaiting is also considered "running" since tmux session is active
+    /// Note: Awaiting is also considered "running" since the agent session is active
     pub fn is_running(&self) -> bool {
         matches!(self.status, AgentStatus::Running | AgentStatus::Awaiting)
     }
@@ -105,9 +137,14 @@ impl AgentSession {
         matches!(self.status, AgentStatus::Awaiting)
     }
 
-    /// Get the tmux session name for this session
-    pub fn tmux_session_name(&self) -> String {
-        format!("{}-issue-{}", self.project, self.issue_number)
+    /// Check if the session waits in the dispatch queue
+    pub fn is_queued(&self) -> bool {
+        matches!(self.status, AgentStatus::Queued)
+    }
+
+    /// Get the terminal session name for this session
+    pub fn session_name(&self) -> String {
+        attempt_session_name(&self.project, self.issue_number, self.attempt.as_deref())
     }
 
     /// Get duration since start
@@ -129,15 +166,52 @@ impl AgentSession {
     }
 }
 
+/// Take the exclusive lock guarding a sessions file; released when the file is dropped.
+fn lock_file(path: &std::path::Path) -> std::io::Result<fs::File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("json.lock"))?;
+    file.lock()?;
+    Ok(file)
+}
+
 /// Manages agent sessions stored in a JSON file
 pub struct SessionManager {
     sessions: Vec<AgentSession>,
+    path: PathBuf,
 }
 
 impl SessionManager {
     /// Load sessions from file or create empty manager
     pub fn load() -> Self {
-        let path = sessions_file();
+        Self::load_from(sessions_file())
+    }
+
+    /// Load, modify and save the sessions file while holding its lock,
+    /// so concurrent updates from other threads or processes are not lost.
+    pub fn update<T>(f: impl FnOnce(&mut SessionManager) -> T) -> std::io::Result<T> {
+        Self::update_at(sessions_file(), f)
+    }
+
+    /// Locked update of a specific sessions file
+    pub fn update_at<T>(
+        path: PathBuf,
+        f: impl FnOnce(&mut SessionManager) -> T,
+    ) -> std::io::Result<T> {
+        let _lock = lock_file(&path)?;
+        let mut manager = Self::load_from(path);
+        let result = f(&mut manager);
+        manager.save()?;
+        Ok(result)
+    }
+
+    /// Load sessions from a specific file
+    pub fn load_from(path: PathBuf) -> Self {
         let sessions = if path.exists() {
             fs::read_to_string(&path)
                 .ok()
@@ -146,41 +220,36 @@ impl SessionManager {
         } else {
             Vec::new()
         };
-        Self { sessions }
+        Self { sessions, path }
     }
 
-    /// Sync session statuses with actual tmux state.
-    /// Marks sessions as completed if their tmux session no longer exists.
-    pub fn sync_with_tmux(&mut self) -> bool {
+    /// Sync session statuses with a session backend.
+    /// Marks sessions as completed if their terminal session no longer exists.
+    pub fn sync_with(&mut self, backend: &dyn super::SessionBackend) -> bool {
         let mut changed = false;
         for session in &mut self.sessions {
-            if session.is_running() {
-                let tmux_name = session.tmux_session_name();
-                let tmux_exists = Command::new("tmux")
-                    .args(["has-session", "-t", &tmux_name])
-                    .stderr(Stdio::null())
-                    .status()
-                    .map(|s| s.success())
-                    .unwrap_or(false);
-
-                if !tmux_exists {
-                    session.status = AgentStatus::Completed { exit_code: 0 };
-                    changed = true;
-                }
+            if session.is_running() && !backend.is_running(&session.session_name()) {
+                session.status = AgentStatus::Completed { exit_code: 0 };
+                changed = true;
             }
         }
         changed
     }
 
-    /// Save sessions to file
+    /// Save sessions to file.
+    ///
+    /// The file is replaced atomically, so readers never see a partial write.
+    /// Use [`SessionManager::update`] to modify sessions without losing concurrent changes.
     pub fn save(&self) -> Result<(), std::io::Error> {
-        let dir = cache_dir();
-        fs::create_dir_all(&dir)?;
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
 
-        let path = sessions_file();
         let content = serde_json::to_string_pretty(&self.sessions)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-        fs::write(path, content)
+        let tmp = self.path.with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&tmp, content)?;
+        fs::rename(tmp, &self.path)
     }
 
     /// Get all sessions
@@ -193,6 +262,33 @@ impl SessionManager {
         self.sessions.iter().filter(|s| s.is_running()).collect()
     }
 
+    /// Get queued sessions, next to start first
+    pub fn queued(&self) -> Vec<&AgentSession> {
+        self.sessions.iter().filter(|s| s.is_queued()).collect()
+    }
+
+    /// Move a queued session one place towards the front (`up`) or the back of the queue.
+    pub fn move_queued(&mut self, id: &str, up: bool) -> bool {
+        let queued: Vec<usize> = (0..self.sessions.len())
+            .filter(|&i| self.sessions[i].is_queued())
+            .collect();
+        let Some(pos) = queued.iter().position(|&i| self.sessions[i].id == id) else {
+            return false;
+        };
+        let other = if up {
+            pos.checked_sub(1)
+        } else {
+            Some(pos + 1).filter(|&p| p < queued.len())
+        };
+        match other {
+            Some(other) => {
+                self.sessions.swap(queued[pos], queued[other]);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Get a session by ID
     pub fn get(&self, id: &str) -> Option<&AgentSession> {
         self.sessions.iter().find(|s| s.id == id)
@@ -235,12 +331,42 @@ impl SessionManager {
         }
     }
 
+    /// Mark a planning session as implementing its approved plan
+    pub fn approve_plan(&mut self, id: &str) -> bool {
+        if let Some(session) = self.get_mut(id) {
+            session.planning = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Record the pull request opened for a session
+    pub fn set_pr_url(&mut self, id: &str, pr_url: String) -> bool {
+        if let Some(session) = self.get_mut(id) {
+            session.pr_url = Some(pr_url);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Record the outcome of a verification run
+    pub fn set_verification(&mut self, id: &str, verification: Verification) -> bool {
+        if let Some(session) = self.get_mut(id) {
+            session.verification = Some(verification);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Remove old sessions (older than `days`)
     pub fn cleanup_old_sessions(&mut self, days: u32) {
         let cutoff = Utc::now() - chrono::Duration::days(i64::from(days));
         self.sessions.retain(|s| {
-            // Keep running sessions
-            if s.is_running() {
+            // Keep running and queued sessions
+            if s.is_running() || s.is_queued() {
                 return true;
             }
             // Keep recent sessions
@@ -299,7 +425,7 @@ mod tests {
 
     #[test]
     fn session_manager_add_and_get() {
-        let mut manager = SessionManager { sessions: Vec::new() };
+        let mut manager = SessionManager::load_from(PathBuf::new());
 
         let session = AgentSession::new(
             "test-id".to_string(),
@@ -321,7 +447,7 @@ mod tests {
 
     #[test]
     fn session_manager_update_status() {
-        let mut manager = SessionManager { sessions: Vec::new() };
+        let mut manager = SessionManager::load_from(PathBuf::new());
 
         let session = AgentSession::new(
             "test-id".to_string(),
@@ -341,6 +467,30 @@ mod tests {
         assert!(!manager.get("test-id").unwrap().is_running());
     }
 
+    #[test]
+    fn session_manager_set_pr_url() {
+        let mut manager = SessionManager::load_from(PathBuf::new());
+
+        let session = AgentSession::new(
+            "test-id".to_string(),
+            123,
+            "Test issue".to_string(),
+            "test-project".to_string(),
+            1234,
+            PathBuf::from("/tmp/test.log"),
+            PathBuf::from("/tmp/worktree"),
+            "issue-123".to_string(),
+            CodingAgentType::Claude,
+        );
+
+        manager.add(session);
+
+        let url = "https://github.com/test/test/pull/1".to_string();
+        assert!(manager.set_pr_url("test-id", url.clone()));
+        assert_eq!(manager.get("test-id").unwrap().pr_url, Some(url.clone()));
+        assert!(!manager.set_pr_url("non-existent", url));
+    }
+
     #[test]
     fn duration_formatting() {
         let session = AgentSession::new(
@@ -386,7 +536,7 @@ mod tests {
 
     #[test]
     fn session_manager_running_filter() {
-        let mut manager = SessionManager { sessions: Vec::new() };
+        let mut manager = SessionManager::load_from(PathBuf::new());
 
         let running = AgentSession::new(
             "running".to_string(),
@@ -423,7 +573,7 @@ mod tests {
 
     #[test]
     fn session_manager_update_stats() {
-        let mut manager = SessionManager { sessions: Vec::new() };
+        let mut manager = SessionManager::load_from(PathBuf::new());
 
         let session = AgentSession::new(
             "test-id".to_string(),
@@ -455,7 +605,7 @@ mod tests {
 
     #[test]
     fn session_manager_remove() {
-        let mut manager = SessionManager { sessions: Vec::new() };
+        let mut manager = SessionManager::load_from(PathBuf::new());
 
         let session = AgentSession::new(
             "test-id".to_string(),
@@ -480,7 +630,7 @@ mod tests {
 
     #[test]
     fn cleanup_old_sessions() {
-        let mut manager = SessionManager { sessions: Vec::new() };
+        let mut manager = SessionManager::load_from(PathBuf::new());
 
         // Create a running session (should not be removed)
         let running = AgentSession::new(
@@ -517,4 +667,75 @@ mod tests {
 
         assert_eq!(manager.list().len(), 2);
     }
+
+    #[test]
+    fn session_manager_reorders_queue() {
+        let mut manager = SessionManager::load_from(PathBuf::new());
+        for (id, status) in [
+            ("a", AgentStatus::Queued),
+            ("running", AgentStatus::Running),
+            ("b", AgentStatus::Queued),
+            ("c", AgentStatus::Queued),
+        ] {
+            let mut session = AgentSession::new(
+                id.to_string(),
+                1,
+                "Issue".to_string(),
+                "project".to_string(),
+                0,
+                PathBuf::from("/tmp/1.log"),
+                PathBuf::from("/tmp/1"),
+                "issue-1".to_string(),
+                CodingAgentType::Claude,
+            );
+            session.status = status;
+            manager.add(session);
+        }
+        let order =
+            |m: &SessionManager| m.queued().iter().map(|s| s.id.clone()).collect::<Vec<_>>();
+        assert_eq!(order(&manager), vec!["a", "b", "c"]);
+
+        assert!(manager.move_queued("c", true));
+        assert_eq!(order(&manager), vec!["a", "c", "b"]);
+        assert!(manager.move_queued("a", false));
+        assert_eq!(order(&manager), vec!["c", "a", "b"]);
+        assert!(!manager.move_queued("c", true));
+        assert!(!manager.move_queued("b", false));
+        assert!(!manager.move_queued("running", true));
+        assert_eq!(manager.list()[1].id, "running");
+    }
+
+    #[test]
+    fn concurrent_updates_are_not_lost() {
+        let dir = std::env::temp_dir().join(format!("assistant-test-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("sessions.json");
+
+        let handles: Vec<_> = (0..8u64)
+            .map(|n| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    SessionManager::update_at(path, |m| {
+                        m.add(AgentSession::new(
+                            format!("id-{}", n),
+                            n,
+                            "Issue".to_string(),
+                            "proj".to_string(),
+                            0,
+                            PathBuf::from("/tmp/test.log"),
+                            PathBuf::from("/tmp/worktree"),
+                            format!("issue-{}", n),
+                            CodingAgentType::Claude,
+                        ))
+                    })
+                    .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(SessionManager::load_from(path).list().len(), 8);
+        let _ = fs::remove_dir_all(dir);
+    }
 }
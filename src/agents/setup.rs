@@ -0,0 +1,333 @@
+//! Worktree setup hooks for projects with `worktree_setup` set.
+//!
+//! A fresh worktree only has tracked files: untracked `.env` files, installed dependencies
+//! and build caches are missing. Right after `git worktree add`, the configured files are
+//! copied or symlinked from the main checkout and the setup commands are run, their output
+//! going to [`subscribe_setup_output`] subscribers such as the TUI status bar, or the
+//! daemon's clients. A command still running after `timeout_secs` is killed.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::verify::{kill_shell, spawn_shell};
+use super::WorktreeError;
+use crate::config::WorktreeSetupConfig;
+
+/// Output lines kept to report a failed setup command
+const FAILURE_TAIL_LINES: usize = 20;
+
+/// A line of worktree setup progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetupOutput {
+    /// Worktree directory name
+    pub worktree: String,
+    pub line: String,
+}
+
+static SUBSCRIBERS: Mutex<Vec<Sender<SetupOutput>>> = Mutex::new(Vec::new());
+
+/// Receive the progress of worktree setups run by this process.
+pub fn subscribe_setup_output() -> Receiver<SetupOutput> {
+    let (tx, rx) = mpsc::channel();
+    SUBSCRIBERS.lock().unwrap().push(tx);
+    rx
+}
+
+/// Send a progress line to subscribers, dropping the ones that went away.
+fn emit(worktree: &str, line: &str) {
+    let output = SetupOutput {
+        worktree: worktree.to_string(),
+        line: line.to_string(),
+    };
+    SUBSCRIBERS.lock().unwrap().retain(|s| s.send(output.clone()).is_ok());
+}
+
+/// Setup settings of a configured project
+pub(super) fn project_setup(project: &str) -> Option<WorktreeSetupConfig> {
+    crate::config::load_config().ok()?.get_project(project)?.worktree_setup.clone()
+}
+
+/// Prepare a fresh worktree: copy and symlink files from `local_path`, then run the setup
+/// commands in order, stopping at the first failure.
+///
+/// Paths already in the worktree, e.g. tracked files, are left as they are.
+pub fn setup_worktree(
+    local_path: &Path,
+    worktree_path: &Path,
+    config: &WorktreeSetupConfig,
+) -> Result<(), WorktreeError> {
+    let name = worktree_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let copies = config.copy.iter().map(|pattern| (pattern, false));
+    let links = copies.chain(config.symlink.iter().map(|pattern| (pattern, true)));
+    for (pattern, symlink) in links {
+        for relative in expand_pattern(local_path, pattern) {
+            let target = worktree_path.join(&relative);
+            if target.symlink_metadata().is_ok() {
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let source = local_path.join(&relative);
+            if symlink {
+                link(&source, &target)?;
+                emit(&name, &format!("Linked {}", relative.display()));
+            } else {
+                copy_recursive(&source, &target)?;
+                emit(&name, &format!("Copied {}", relative.display()));
+            }
+        }
+    }
+
+    let timeout = Duration::from_secs(config.timeout_secs);
+    for command in &config.commands {
+        emit(&name, &format!("$ {}", command));
+        run_command(&name, worktree_path, command, timeout)?;
+    }
+    Ok(())
+}
+
+/// Run a setup command, streaming its output, with the end of it in the error on failure.
+/// A command still running after `timeout` is killed, with everything it started, and fails.
+fn run_command(
+    name: &str,
+    worktree_path: &Path,
+    command: &str,
+    timeout: Duration,
+) -> Result<(), WorktreeError> {
+    let log = std::env::temp_dir().join(format!("assistant-setup-{}.log", uuid::Uuid::new_v4()));
+    let mut child = spawn_shell(worktree_path, command, File::create(&log)?)?;
+    let mut output = BufReader::new(File::open(&log)?);
+
+    let mut tail = VecDeque::with_capacity(FAILURE_TAIL_LINES);
+    let mut line = Vec::new();
+    let started = Instant::now();
+    let status = loop {
+        let status = child.try_wait()?;
+        // Stream the lines written so far, keeping a partial last line for the next round
+        while output.read_until(b'\n', &mut line)? > 0 {
+            if !line.ends_with(b"\n") && status.is_none() {
+                break;
+            }
+            let text = super::strip_ansi(String::from_utf8_lossy(&line).trim_end());
+            line.clear();
+            emit(name, &text);
+            if tail.len() == FAILURE_TAIL_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(text);
+        }
+        if status.is_some() {
+            break status;
+        }
+        if started.elapsed() >= timeout {
+            kill_shell(&mut child);
+            break None;
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+    let _ = std::fs::remove_file(&log);
+
+    let output: Vec<String> = tail.into();
+    match status {
+        Some(status) if status.success() => Ok(()),
+        Some(status) => Err(WorktreeError::SetupFailed(format!(
+            "`{}` failed ({}):\n{}",
+            command,
+            status,
+            output.join("\n")
+        ))),
+        None => Err(WorktreeError::SetupFailed(format!(
+            "`{}` timed out after {} seconds:\n{}",
+            command,
+            timeout.as_secs(),
+            output.join("\n")
+        ))),
+    }
+}
+
+/// Paths under `root` matching `pattern`, relative to `root`.
+///
+/// `*` and `?` match within one path component; other components are taken literally.
+/// Wildcards only match hidden entries when the component starts with a dot.
+fn expand_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut matches = vec![PathBuf::new()];
+    for component in pattern.split('/').filter(|c| !c.is_empty() && *c != ".") {
+        let wildcard = component.contains(['*', '?']);
+        matches = matches
+            .into_iter()
+            .flat_map(|base| {
+                if !wildcard {
+                    return vec![base.join(component)];
+                }
+                let Ok(entries) = std::fs::read_dir(root.join(&base)) else {
+                    return Vec::new();
+                };
+                let mut names: Vec<String> = entries
+                    .flatten()
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .filter(|n| !n.starts_with('.') || component.starts_with('.'))
+                    .filter(|n| wildcard_match(component, n))
+                    .collect();
+                names.sort();
+                names.into_iter().map(|n| base.join(n)).collect()
+            })
+            .collect();
+    }
+    matches
+        .into_iter()
+        .filter(|m| !m.as_os_str().is_empty() && root.join(m).symlink_metadata().is_ok())
+        .collect()
+}
+
+/// Whether `name` matches `pattern`, where `*` is any run of characters and `?` any one.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` and the name position it was tried at
+    let mut star = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, n));
+            p += 1;
+        } else if let Some((after, tried)) = star {
+            p = after;
+            n = tried + 1;
+            star = Some((after, n));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn copy_recursive(source: &Path, target: &Path) -> std::io::Result<()> {
+    if source.is_dir() {
+        std::fs::create_dir_all(target)?;
+        for entry in std::fs::read_dir(source)?.flatten() {
+            copy_recursive(&entry.path(), &target.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        std::fs::copy(source, target).map(|_| ())
+    }
+}
+
+#[cfg(unix)]
+fn link(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(source, target)
+}
+
+#[cfg(not(unix))]
+fn link(source: &Path, target: &Path) -> std::io::Result<()> {
+    copy_recursive(source, target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards_match_within_a_component() {
+        assert!(wildcard_match(".env*", ".env"));
+        assert!(wildcard_match(".env*", ".env.local"));
+        assert!(wildcard_match("*.local.json", "app.local.json"));
+        assert!(wildcard_match("a?c", "abc"));
+        assert!(wildcard_match("*b*b", "abbab"));
+        assert!(!wildcard_match("*.json", "app.jsonc"));
+        assert!(!wildcard_match("a?c", "ac"));
+    }
+
+    #[test]
+    fn setup_copies_links_and_runs_commands() {
+        let root = std::env::temp_dir().join(format!("assistant-test-{}", uuid::Uuid::new_v4()));
+        let local = root.join("repo");
+        let worktree = root.join("repo-12");
+        std::fs::create_dir_all(local.join("config")).unwrap();
+        std::fs::create_dir_all(local.join("node_modules/left-pad")).unwrap();
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::write(local.join(".env"), "TOKEN=1").unwrap();
+        std::fs::write(local.join(".env.local"), "DEBUG=1").unwrap();
+        std::fs::write(local.join("config/app.local.json"), "{}").unwrap();
+        std::fs::write(local.join("config/app.json"), "{}").unwrap();
+        std::fs::write(worktree.join(".env"), "tracked").unwrap();
+
+        let output = subscribe_setup_output();
+        let config = WorktreeSetupConfig {
+            copy: vec![".env*".to_string(), "config/*.local.json".to_string()],
+            symlink: vec!["node_modules".to_string(), "missing".to_string()],
+            commands: vec!["echo installed > marker".to_string()],
+            ..WorktreeSetupConfig::default()
+        };
+        setup_worktree(&local, &worktree, &config).unwrap();
+
+        // Paths already in the worktree are kept
+        assert_eq!(std::fs::read_to_string(worktree.join(".env")).unwrap(), "tracked");
+        assert_eq!(std::fs::read_to_string(worktree.join(".env.local")).unwrap(), "DEBUG=1");
+        assert!(worktree.join("config/app.local.json").exists());
+        assert!(!worktree.join("config/app.json").exists());
+        assert!(worktree.join("node_modules").symlink_metadata().unwrap().is_symlink());
+        assert!(!worktree.join("missing").exists());
+        assert_eq!(std::fs::read_to_string(worktree.join("marker")).unwrap(), "installed\n");
+        let lines: Vec<String> = output
+            .try_iter()
+            .filter(|o| o.worktree == "repo-12")
+            .map(|o| o.line)
+            .collect();
+        assert!(lines.contains(&"Copied .env.local".to_string()));
+        assert!(lines.contains(&"$ echo installed > marker".to_string()));
+
+        let config = WorktreeSetupConfig {
+            commands: vec![
+                "echo 'npm ERR! missing script'; exit 1".to_string(),
+                "touch never".to_string(),
+            ],
+            ..WorktreeSetupConfig::default()
+        };
+        let error = setup_worktree(&local, &worktree, &config).unwrap_err().to_string();
+        assert!(error.contains("npm ERR! missing script"));
+        assert!(!worktree.join("never").exists());
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn setup_times_out_on_commands_left_running() {
+        let dir = std::env::temp_dir();
+        let output = subscribe_setup_output();
+        let started = Instant::now();
+        let config = WorktreeSetupConfig {
+            commands: vec!["echo starting; sleep 30".to_string()],
+            timeout_secs: 1,
+            ..WorktreeSetupConfig::default()
+        };
+        let error = setup_worktree(&dir, &dir, &config).unwrap_err().to_string();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(error.contains("timed out after 1 seconds:\nstarting"));
+        assert!(output.try_iter().any(|o| o.line == "starting"));
+
+        // A command leaving a background child running still finishes
+        let config = WorktreeSetupConfig {
+            commands: vec!["sleep 30 &".to_string()],
+            timeout_secs: 5,
+            ..WorktreeSetupConfig::default()
+        };
+        let started = Instant::now();
+        setup_worktree(&dir, &dir, &config).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -4,30 +4,87 @@ use std::path::Path;
 
 use crate::config::CodingAgentType;
 
+use super::backend::SessionKey;
 use super::claude::ClaudeCodeAgent;
+use super::custom::CustomAgent;
 use super::opencode::OpencodeAgent;
 
+/// A dialog an agent shows while waiting for an answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentDialog {
+    /// Asking permission to run a tool
+    Permission,
+    /// Asking to pick one of numbered options
+    Selection,
+}
+
+/// A quick answer to an agent dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogAnswer {
+    Approve,
+    Deny,
+    /// Pick the numbered option (1-9)
+    Option(u8),
+}
+
 /// Trait for coding agents that can process GitHub issues.
 pub trait CodingAgent: Send + Sync {
     /// Returns the display name of this agent (e.g., "Claude Code", "Opencode")
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
 
     /// Returns the CLI command to invoke this agent (e.g., "claude", "opencode")
-    fn cli_command(&self) -> &'static str;
+    fn cli_command(&self) -> &str;
 
     /// Check if the agent is idle (waiting for user input) based on tmux pane content.
     fn is_idle(&self, pane_content: &str) -> bool;
 
+    /// The dialog the agent is showing, if the pane ends with one.
+    fn dialog(&self, _pane_content: &str) -> Option<AgentDialog> {
+        None
+    }
+
+    /// Keys answering a dialog: Enter takes the highlighted choice, Esc dismisses it.
+    fn answer_keys(&self, answer: DialogAnswer) -> Vec<SessionKey> {
+        match answer {
+            DialogAnswer::Approve => vec![SessionKey::Enter],
+            DialogAnswer::Deny => vec![SessionKey::Escape],
+            DialogAnswer::Option(n) => vec![SessionKey::Char(char::from(b'0' + n.min(9)))],
+        }
+    }
+
     /// Build the shell command to launch the agent with a prompt.
-    fn build_launch_command(&self, worktree_path: &Path, prompt: &str) -> String {
+    /// `args` are extra CLI arguments (model, flags) from the agent profile.
+    fn build_launch_command(&self, worktree_path: &Path, prompt: &str, args: &[String]) -> String {
         let escaped_prompt = prompt.replace('\'', "'\\''");
         format!(
-            "cd '{}' && {} '{}'",
+            "cd '{}' && {}{} '{}'",
             worktree_path.display(),
             self.cli_command(),
+            shell_args(args),
             escaped_prompt
         )
     }
+
+    /// Build the shell command relaunching the agent on its last conversation in a worktree,
+    /// e.g. `claude --continue`, or `None` when the agent cannot continue one.
+    fn build_resume_command(&self, _worktree_path: &Path, _args: &[String]) -> Option<String> {
+        None
+    }
+
+    /// Build the shell command starting the agent without a prompt.
+    fn build_interactive_command(&self) -> String {
+        self.cli_command().to_string()
+    }
+}
+
+/// Quote a value for a POSIX shell.
+pub(super) fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Quote CLI arguments, each preceded by a space.
+pub(super) fn shell_args(args: &[String]) -> String {
+    args.iter().map(|a| format!(" {}", shell_quote(a))).collect()
 }
 
 /// Factory function to get the appropriate agent based on configuration.
@@ -35,5 +92,22 @@ pub fn get_agent(agent_type: &CodingAgentType) -> Box<dyn CodingAgent> {
     match agent_type {
         CodingAgentType::Claude => Box::new(ClaudeCodeAgent),
         CodingAgentType::Opencode => Box::new(OpencodeAgent),
+        CodingAgentType::Custom(name) => Box::new(
+            CustomAgent::from_config(name).unwrap_or_else(|| CustomAgent::missing(name)),
+        ),
+    }
+}
+
+/// Agents that can be selected for dispatch: the built-in ones, then those from the config.
+pub fn available_agents() -> Vec<CodingAgentType> {
+    let mut agents = vec![CodingAgentType::Claude, CodingAgentType::Opencode];
+    if let Ok(config) = crate::config::load_config() {
+        agents.extend(
+            config
+                .custom_agents
+                .into_iter()
+                .map(|a| CodingAgentType::Custom(a.name)),
+        );
     }
+    agents
 }
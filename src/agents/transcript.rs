@@ -0,0 +1,281 @@
+//! Agent transcripts: terminal output recorded to the session's log file.
+//!
+//! Backends that can stream a session's output feed it to a [`Transcript`]: tmux pipes
+//! the pane into an `assistant __transcript` process, the native supervisor writes it
+//! directly. For other backends the monitor appends the screen lines that are new at
+//! each pass. Logs are rotated by size and kept after the session ends; the raw output
+//! can also be recorded as an asciicast for replay.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::asciicast::{cast_path, CastWriter};
+use super::backend::{SESSION_COLS, SESSION_ROWS};
+use crate::config::TranscriptConfig;
+
+/// Hidden command line argument recording stdin to a transcript
+pub const TRANSCRIPT_ARG: &str = "__transcript";
+
+/// Position of an [`AnsiStripper`] within an escape sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum AnsiState {
+    #[default]
+    Text,
+    /// After ESC
+    Escape,
+    /// Control sequence (`ESC [`), ended by a final byte
+    Csi,
+    /// String sequence (`ESC ]`, `ESC P`...), ended by BEL or `ESC \`
+    String,
+    /// ESC inside a string sequence
+    StringEscape,
+}
+
+/// Removes escape sequences and control characters from terminal output.
+/// Keeps state between chunks, so sequences split across reads are handled.
+#[derive(Debug, Default)]
+pub struct AnsiStripper {
+    state: AnsiState,
+}
+
+impl AnsiStripper {
+    /// Plain text of a chunk of output.
+    pub fn strip(&mut self, input: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(input.len());
+        for &byte in input {
+            self.state = match (self.state, byte) {
+                (AnsiState::Text, 0x1b) => AnsiState::Escape,
+                (AnsiState::Text, b'\n' | b'\t') => {
+                    out.push(byte);
+                    AnsiState::Text
+                }
+                // Other control characters, including carriage returns
+                (AnsiState::Text, 0x00..=0x1f | 0x7f) => AnsiState::Text,
+                (AnsiState::Text, _) => {
+                    out.push(byte);
+                    AnsiState::Text
+                }
+                (AnsiState::Escape, b'[') => AnsiState::Csi,
+                (AnsiState::Escape, b']' | b'P' | b'X' | b'^' | b'_') => AnsiState::String,
+                // Intermediate bytes of a two-character sequence such as `ESC ( B`
+                (AnsiState::Escape, 0x20..=0x2f) => AnsiState::Escape,
+                (AnsiState::Escape, _) => AnsiState::Text,
+                (AnsiState::Csi, 0x40..=0x7e) => AnsiState::Text,
+                (AnsiState::Csi, _) => AnsiState::Csi,
+                (AnsiState::String, 0x07) => AnsiState::Text,
+                (AnsiState::String, 0x1b) => AnsiState::StringEscape,
+                (AnsiState::String, _) => AnsiState::String,
+                (AnsiState::StringEscape, b'\\') => AnsiState::Text,
+                (AnsiState::StringEscape, _) => AnsiState::String,
+            };
+        }
+        out
+    }
+}
+
+/// Plain text of recorded output, for transcripts that keep escape sequences.
+pub fn strip_ansi(text: &str) -> String {
+    let stripped = AnsiStripper::default().strip(text.as_bytes());
+    String::from_utf8_lossy(&stripped).into_owned()
+}
+
+/// Append-only log file with size-based rotation.
+pub struct Transcript {
+    path: PathBuf,
+    config: TranscriptConfig,
+    file: File,
+    size: u64,
+    stripper: Option<AnsiStripper>,
+    cast: Option<CastWriter>,
+}
+
+impl Transcript {
+    /// Open a transcript, appending to an existing log.
+    pub fn open(path: &Path, config: &TranscriptConfig) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        let cast = if config.asciicast {
+            Some(CastWriter::open(&cast_path(path), SESSION_COLS, SESSION_ROWS)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            config: config.clone(),
+            file,
+            size,
+            stripper: (!config.keep_ansi).then(AnsiStripper::default),
+            cast,
+        })
+    }
+
+    /// Record a chunk of terminal output.
+    pub fn write(&mut self, output: &[u8]) -> std::io::Result<()> {
+        if let Some(cast) = &mut self.cast {
+            cast.write(output)?;
+        }
+        let stripped;
+        let data = match &mut self.stripper {
+            Some(stripper) => {
+                stripped = stripper.strip(output);
+                &stripped[..]
+            }
+            None => output,
+        };
+        if data.is_empty() {
+            return Ok(());
+        }
+        if self.size > 0 && self.size + data.len() as u64 > self.config.max_size_kb * 1024 {
+            self.rotate()?;
+        }
+        self.file.write_all(data)?;
+        self.size += data.len() as u64;
+        Ok(())
+    }
+
+    /// Record text lines, e.g. from screen captures.
+    pub fn write_lines(&mut self, lines: &[&str]) -> std::io::Result<()> {
+        // Carriage returns are for the recording; the log drops them
+        let mut text = lines.join("\r\n");
+        text.push_str("\r\n");
+        self.write(text.as_bytes())
+    }
+
+    /// Shift `log`, `log.1`... up by one, dropping the oldest, and start a new log.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", self.path.display(), n));
+        if self.config.rotations == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let _ = fs::remove_file(rotated(self.config.rotations));
+            for n in (1..self.config.rotations).rev() {
+                let _ = fs::rename(rotated(n), rotated(n + 1));
+            }
+            fs::rename(&self.path, rotated(1))?;
+        }
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// Command line arguments of a transcript process writing to `path`.
+pub fn transcript_args(path: &Path, config: &TranscriptConfig) -> Vec<String> {
+    let mut args = vec![
+        TRANSCRIPT_ARG.to_string(),
+        path.display().to_string(),
+        config.max_size_kb.to_string(),
+        config.rotations.to_string(),
+    ];
+    if config.keep_ansi {
+        args.push("--keep-ansi".to_string());
+    }
+    if config.asciicast {
+        args.push("--asciicast".to_string());
+    }
+    args
+}
+
+/// Entry point of the transcript process:
+/// `__transcript <log> <max_size_kb> <rotations> [--keep-ansi] [--asciicast]`, recording stdin.
+pub fn run_transcript_from_args(args: &[String]) -> ! {
+    let code = match args {
+        [path, max_size_kb, rotations, rest @ ..] => {
+            let defaults = TranscriptConfig::default();
+            let config = TranscriptConfig {
+                keep_ansi: rest.iter().any(|a| a == "--keep-ansi"),
+                max_size_kb: max_size_kb.parse().unwrap_or(defaults.max_size_kb),
+                rotations: rotations.parse().unwrap_or(defaults.rotations),
+                asciicast: rest.iter().any(|a| a == "--asciicast"),
+            };
+            match record(std::io::stdin().lock(), Path::new(path), &config) {
+                Ok(()) => 0,
+                Err(_) => 1,
+            }
+        }
+        _ => 2,
+    };
+    std::process::exit(code)
+}
+
+/// Record everything read from `input` until it ends.
+fn record(mut input: impl Read, path: &Path, config: &TranscriptConfig) -> std::io::Result<()> {
+    let mut transcript = Transcript::open(path, config)?;
+    let mut buf = [0u8; 8192];
+    loop {
+        match input.read(&mut buf)? {
+            0 => return Ok(()),
+            n => transcript.write(&buf[..n])?,
+        }
+    }
+}
+
+/// Lines of `current` that were not on the `previous` screen capture.
+///
+/// Output scrolls up, so the longest tail of `previous` that starts `current` is
+/// taken as already recorded. Without overlap the whole screen is new.
+pub fn new_screen_lines<'a>(previous: &str, current: &'a str) -> Vec<&'a str> {
+    let previous: Vec<&str> = previous.lines().map(str::trim_end).collect();
+    let current: Vec<&str> = current.lines().map(str::trim_end).collect();
+    // Blank lines below the output are not part of it yet
+    let end = current.iter().rposition(|l| !l.is_empty()).map_or(0, |i| i + 1);
+    let current = &current[..end];
+    let prev_end = previous.iter().rposition(|l| !l.is_empty()).map_or(0, |i| i + 1);
+    let previous = &previous[..prev_end];
+
+    let overlap = (1..=previous.len().min(current.len()))
+        .rev()
+        .find(|&n| previous[previous.len() - n..] == current[..n])
+        .unwrap_or(0);
+    current[overlap..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stripper_removes_sequences_split_across_chunks() {
+        let mut stripper = AnsiStripper::default();
+        let mut out = stripper.strip(b"\x1b[1;3");
+        out.extend(stripper.strip(b"2mred\x1b[0m text\r\n\x1b]0;title\x07done\x1b(B\n"));
+        assert_eq!(String::from_utf8(out).unwrap(), "red text\ndone\n");
+    }
+
+    #[test]
+    fn transcript_rotates_by_size() {
+        let root = std::env::temp_dir().join(format!("assistant-test-{}", uuid::Uuid::new_v4()));
+        let path = root.join("a.log");
+        let config = TranscriptConfig {
+            max_size_kb: 1,
+            rotations: 2,
+            ..Default::default()
+        };
+        let mut transcript = Transcript::open(&path, &config).unwrap();
+        let chunk = vec![b'x'; 700];
+        for _ in 0..4 {
+            transcript.write(&chunk).unwrap();
+        }
+
+        let size = |p: &str| fs::metadata(root.join(p)).map(|m| m.len()).ok();
+        assert_eq!(size("a.log"), Some(700));
+        assert_eq!(size("a.log.1"), Some(700));
+        assert_eq!(size("a.log.2"), Some(700));
+        assert_eq!(size("a.log.3"), None);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn new_screen_lines_skips_lines_already_seen() {
+        let previous = "one\ntwo\nthree\n\n";
+        assert_eq!(new_screen_lines(previous, "two\nthree\nfour\n\n"), vec!["four"]);
+        assert!(new_screen_lines(previous, "one\ntwo\nthree\n").is_empty());
+        assert_eq!(new_screen_lines(previous, "five\nsix"), vec!["five", "six"]);
+        assert_eq!(new_screen_lines("", "a\n\n"), vec!["a"]);
+    }
+}
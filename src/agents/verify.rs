@@ -0,0 +1,242 @@
+//! Verification hooks: a project's test and lint commands, run in an agent's worktree.
+//!
+//! The monitor runs them when the agent starts waiting for input or its session ends, and
+//! the worktree list runs them on demand. The outcome is recorded on the session, and a
+//! failure can be sent back to the waiting agent as a follow-up prompt.
+
+use std::fs::File;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{strip_ansi, AgentSession};
+use crate::config::VerifyConfig;
+
+/// State of a verification run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyStatus {
+    Running,
+    Passed,
+    Failed,
+}
+
+/// Outcome of a session's verification commands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Verification {
+    pub status: VerifyStatus,
+    /// The command that failed, or the last one run
+    pub command: String,
+    /// Last lines of the commands' output
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub output_tail: String,
+    /// When the run started or finished
+    pub at: DateTime<Utc>,
+}
+
+impl Verification {
+    /// A run that just started
+    pub fn running() -> Self {
+        Self {
+            status: VerifyStatus::Running,
+            command: String::new(),
+            output_tail: String::new(),
+            at: Utc::now(),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status == VerifyStatus::Running
+    }
+
+    pub fn failed(&self) -> bool {
+        self.status == VerifyStatus::Failed
+    }
+}
+
+/// Run the verification commands in `worktree_path`, stopping at the first failure.
+pub fn run_verification(worktree_path: &Path, config: &VerifyConfig) -> Verification {
+    let timeout = Duration::from_secs(config.timeout_secs);
+    let mut output = String::new();
+    let mut last = String::new();
+    for command in &config.commands {
+        last = command.clone();
+        output.push_str(&format!("$ {}\n", command));
+        let passed = match run_command(worktree_path, command, timeout) {
+            Ok((command_output, passed)) => {
+                output.push_str(&command_output);
+                passed
+            }
+            Err(e) => {
+                output.push_str(&format!("Failed to run: {}\n", e));
+                false
+            }
+        };
+        if !passed {
+            return Verification {
+                status: VerifyStatus::Failed,
+                command: command.clone(),
+                output_tail: tail(&output, config.tail_lines),
+                at: Utc::now(),
+            };
+        }
+    }
+    Verification {
+        status: VerifyStatus::Passed,
+        command: last,
+        output_tail: tail(&output, config.tail_lines),
+        at: Utc::now(),
+    }
+}
+
+/// Run one command without input, returning its output and whether it passed.
+/// A command still running after `timeout` is killed, with everything it started, and fails.
+fn run_command(
+    worktree_path: &Path,
+    command: &str,
+    timeout: Duration,
+) -> std::io::Result<(String, bool)> {
+    let log = std::env::temp_dir().join(format!("assistant-verify-{}.log", uuid::Uuid::new_v4()));
+    let mut child = spawn_shell(worktree_path, command, File::create(&log)?)?;
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if started.elapsed() >= timeout {
+            kill_shell(&mut child);
+            break None;
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+
+    let mut output = String::from_utf8_lossy(&std::fs::read(&log)?).to_string();
+    let _ = std::fs::remove_file(&log);
+    if status.is_none() {
+        output.push_str(&format!("Timed out after {} seconds\n", timeout.as_secs()));
+    }
+    Ok((output, status.is_some_and(|s| s.success())))
+}
+
+/// Start a shell command in `cwd` without input, in its own process group, with stderr
+/// interleaved with stdout as in a terminal.
+///
+/// Output goes to a file: a pipe could fill up, or be held open forever by a child left
+/// running in the background.
+pub(super) fn spawn_shell(cwd: &Path, command: &str, output: File) -> std::io::Result<Child> {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(format!("exec 2>&1\n{}", command))
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .stdout(output);
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+    cmd.spawn()
+}
+
+/// Kill a command started by [`spawn_shell`] and everything it started.
+pub(super) fn kill_shell(child: &mut Child) {
+    #[cfg(unix)]
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", child.id())])
+        .status();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Last `lines` lines of a command output, without ANSI codes.
+fn tail(output: &str, lines: usize) -> String {
+    let output = strip_ansi(output);
+    let all: Vec<&str> = output.trim_end().lines().collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+/// Build the follow-up prompt sent to an agent whose work failed verification.
+pub fn build_verify_feedback_prompt(verification: &Verification) -> String {
+    format!(
+        "Verification failed: `{}` did not pass. The end of its output:\n\n```\n{}\n```\n\n\
+        Fix the failures, then run the command again to check.",
+        verification.command, verification.output_tail
+    )
+}
+
+/// Verify a session's worktree in the background, sending the outcome with its id.
+pub(super) fn spawn(
+    session: &AgentSession,
+    config: VerifyConfig,
+    results: Sender<(String, Verification)>,
+) {
+    let id = session.id.clone();
+    let worktree_path = session.worktree_path.clone();
+    thread::spawn(move || {
+        let verification = run_verification(&worktree_path, &config);
+        let _ = results.send((id, verification));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(commands: &[&str]) -> VerifyConfig {
+        VerifyConfig {
+            commands: commands.iter().map(|c| c.to_string()).collect(),
+            tail_lines: 3,
+            ..VerifyConfig::default()
+        }
+    }
+
+    #[test]
+    fn verification_stops_at_first_failure() {
+        let dir = std::env::temp_dir().join(format!("assistant-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("marker"), "").unwrap();
+
+        let passed = run_verification(&dir, &config(&["test -f marker", "echo ok"]));
+        assert_eq!(passed.status, VerifyStatus::Passed);
+        assert_eq!(passed.command, "echo ok");
+        assert!(passed.output_tail.ends_with("$ echo ok\nok"));
+
+        let failed = run_verification(
+            &dir,
+            &config(&["printf 'a\\nb\\nc\\n'; echo broken >&2; exit 1", "echo never"]),
+        );
+        assert!(failed.failed());
+        assert!(failed.command.starts_with("printf"));
+        assert_eq!(failed.output_tail, "b\nc\nbroken");
+
+        let prompt = build_verify_feedback_prompt(&failed);
+        assert!(prompt.contains("```\nb\nc\nbroken\n```"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn verification_times_out_without_input() {
+        let dir = std::env::temp_dir();
+        let started = Instant::now();
+        let timed_out = run_verification(
+            &dir,
+            &VerifyConfig {
+                timeout_secs: 1,
+                ..config(&["echo waiting; sleep 30"])
+            },
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(timed_out.failed());
+        assert_eq!(
+            timed_out.output_tail,
+            "$ echo waiting; sleep 30\nwaiting\nTimed out after 1 seconds"
+        );
+
+        // Reading stdin gets end of file instead of waiting for a terminal
+        let read = run_verification(&dir, &config(&["read line"]));
+        assert!(read.failed());
+    }
+}
@@ -3,6 +3,7 @@
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use super::setup::{project_setup, setup_worktree};
 use super::worktrees_dir;
 
 /// Error types for worktree operations
@@ -10,6 +11,8 @@ use super::worktrees_dir;
 pub enum WorktreeError {
     GitError(String),
     IoError(std::io::Error),
+    /// A `worktree_setup` step failed; the worktree was removed
+    SetupFailed(String),
 }
 
 impl std::fmt::Display for WorktreeError {
@@ -17,6 +20,7 @@ impl std::fmt::Display for WorktreeError {
         match self {
             WorktreeError::GitError(msg) => write!(f, "Git error: {}", msg),
             WorktreeError::IoError(e) => write!(f, "IO error: {}", e),
+            WorktreeError::SetupFailed(msg) => write!(f, "Worktree setup failed: {}", msg),
         }
     }
 }
@@ -152,7 +156,23 @@ pub fn create_worktree(
 ) -> Result<(PathBuf, String), WorktreeError> {
     let branch_name = format!("issue-{}", issue_number);
     let worktree_name = format!("{}-{}", project, issue_number);
-    create_worktree_impl(local_path, &branch_name, &worktree_name, base_branch)
+    create_worktree_impl(local_path, project, &branch_name, &worktree_name, base_branch)
+}
+
+/// Create a git worktree for one of several attempts at an issue.
+///
+/// Each attempt gets its own branch and worktree, suffixed with its label
+/// (`issue-42-a` in `project-42-a`).
+pub fn create_attempt_worktree(
+    local_path: &Path,
+    project: &str,
+    issue_number: u64,
+    attempt: &str,
+    base_branch: Option<&str>,
+) -> Result<(PathBuf, String), WorktreeError> {
+    let branch_name = format!("issue-{}-{}", issue_number, attempt);
+    let worktree_name = format!("{}-{}-{}", project, issue_number, attempt);
+    create_worktree_impl(local_path, project, &branch_name, &worktree_name, base_branch)
 }
 
 /// Create a git worktree with a custom branch name.
@@ -176,12 +196,308 @@ pub fn create_worktree_with_branch(
     // Sanitize branch name for directory (replace / with -)
     let sanitized_name = branch_name.replace('/', "-");
     let worktree_name = format!("{}-{}", project, sanitized_name);
-    create_worktree_impl(local_path, branch_name, &worktree_name, base_branch)
+    create_worktree_impl(local_path, project, branch_name, &worktree_name, base_branch)
+}
+
+/// Branch of a pull request's head repository that a PR worktree pushes to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushTarget {
+    /// Remote name: "origin" for same-repo PRs, the fork owner otherwise
+    pub remote: String,
+    /// Clone URL, used to add the remote if it does not exist yet
+    pub url: String,
+    /// Branch name in the head repository
+    pub branch: String,
+}
+
+impl PushTarget {
+    /// Work out where commits on a PR worktree should be pushed.
+    ///
+    /// Same-repo PRs push to origin. Fork PRs push to the fork only when the
+    /// author allows maintainers to modify the branch; otherwise `None`.
+    pub fn for_pull_request(
+        base_repo: &str,
+        head_repo: Option<&str>,
+        head_clone_url: Option<&str>,
+        head_ref: &str,
+        maintainer_can_modify: bool,
+    ) -> Option<Self> {
+        let head_repo = head_repo?;
+        let url = head_clone_url?.to_string();
+        if head_ref.is_empty() {
+            return None;
+        }
+
+        let remote = if head_repo.eq_ignore_ascii_case(base_repo) {
+            "origin".to_string()
+        } else if maintainer_can_modify {
+            head_repo.split('/').next().unwrap_or(head_repo).to_string()
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            remote,
+            url,
+            branch: head_ref.to_string(),
+        })
+    }
+}
+
+/// Path of the worktree created for a pull request.
+pub fn pr_worktree_path(project: &str, pr_number: u64) -> PathBuf {
+    worktrees_dir().join(format!("{}-pr-{}", project, pr_number))
+}
+
+/// Create a git worktree for a pull request by number.
+///
+/// Fetches `pull/<n>/head` from origin into `refs/remotes/origin/pr/<n>`, which works
+/// for fork PRs too, and creates a local `pr-<n>` branch from it. An existing branch
+/// or worktree is only fast-forwarded, so local commits are never overwritten; if it
+/// has diverged from the PR head an error is returned instead. A worktree with
+/// uncommitted changes is left as it is. When a push target is given, the branch is
+/// configured to push back to the PR's head branch.
+///
+/// # Returns
+/// * The path to the created worktree and the branch name
+pub fn create_worktree_for_pr(
+    local_path: &Path,
+    project: &str,
+    pr_number: u64,
+    push_target: Option<&PushTarget>,
+) -> Result<(PathBuf, String), WorktreeError> {
+    validate_git_repo(local_path)?;
+
+    let branch_name = format!("pr-{}", pr_number);
+    let worktree_path = pr_worktree_path(project, pr_number);
+
+    std::fs::create_dir_all(worktrees_dir())?;
+
+    let fetched = fetch_pr_head(local_path, pr_number);
+    if worktree_path.exists() {
+        // Without the latest head, the worktree is reopened as it is
+        if let Ok(head) = fetched {
+            fast_forward_pr_worktree(&worktree_path, pr_number, &head)?;
+        }
+    } else {
+        let head = fetched?;
+        ensure_pr_branch(local_path, &branch_name, pr_number, &head)?;
+
+        let output = Command::new("git")
+            .current_dir(local_path)
+            .args([
+                "worktree",
+                "add",
+                worktree_path.to_str().unwrap(),
+                &branch_name,
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(WorktreeError::GitError(format!(
+                "Failed to create worktree: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        set_up(local_path, project, &worktree_path)?;
+    }
+
+    if let Some(target) = push_target {
+        set_push_target(local_path, &branch_name, target)?;
+    }
+
+    Ok((worktree_path, branch_name))
+}
+
+/// Fetch a PR's head into `refs/remotes/origin/pr/<n>`, returning that ref.
+fn fetch_pr_head(local_path: &Path, pr_number: u64) -> Result<String, WorktreeError> {
+    let head = format!("refs/remotes/origin/pr/{}", pr_number);
+    let output = Command::new("git")
+        .current_dir(local_path)
+        .args([
+            "fetch",
+            "origin",
+            &format!("+pull/{}/head:{}", pr_number, head),
+        ])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(WorktreeError::GitError(format!(
+            "Failed to fetch PR #{}: {}",
+            pr_number,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(head)
+}
+
+/// Whether `ancestor` is reachable from `descendant`.
+fn is_ancestor(repo_path: &Path, ancestor: &str, descendant: &str) -> bool {
+    Command::new("git")
+        .current_dir(repo_path)
+        .args(["merge-base", "--is-ancestor", ancestor, descendant])
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+/// Error for a local PR branch that can't be fast-forwarded to the PR head.
+fn diverged(pr_number: u64, branch_name: &str) -> WorktreeError {
+    WorktreeError::GitError(format!(
+        "{} has diverged from the head of PR #{}; merge or rebase it by hand",
+        branch_name, pr_number
+    ))
+}
+
+/// Create the local branch for a PR from its fetched head, or fast-forward it.
+fn ensure_pr_branch(
+    local_path: &Path,
+    branch_name: &str,
+    pr_number: u64,
+    head: &str,
+) -> Result<(), WorktreeError> {
+    let branch_ref = format!("refs/heads/{}", branch_name);
+    let branch_exists = Command::new("git")
+        .current_dir(local_path)
+        .args(["rev-parse", "--verify", "--quiet", &branch_ref])
+        .output()
+        .is_ok_and(|o| o.status.success());
+
+    if branch_exists && is_ancestor(local_path, head, &branch_ref) {
+        // Up to date, or ahead with commits not pushed yet
+        return Ok(());
+    }
+    if branch_exists && !is_ancestor(local_path, &branch_ref, head) {
+        return Err(diverged(pr_number, branch_name));
+    }
+
+    let mut args = vec!["branch"];
+    if branch_exists {
+        args.push("--force");
+    }
+    let output = Command::new("git")
+        .current_dir(local_path)
+        .args(args)
+        .args([branch_name, head])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(WorktreeError::GitError(format!(
+            "Failed to create branch: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    Ok(())
+}
+
+/// Fast-forward an existing PR worktree to the fetched head, if it is clean.
+fn fast_forward_pr_worktree(
+    worktree_path: &Path,
+    pr_number: u64,
+    head: &str,
+) -> Result<(), WorktreeError> {
+    if has_uncommitted_changes(worktree_path) || is_ancestor(worktree_path, head, "HEAD") {
+        return Ok(());
+    }
+    if !is_ancestor(worktree_path, "HEAD", head) {
+        let branch_name = current_branch(worktree_path).unwrap_or_else(|| "HEAD".to_string());
+        return Err(diverged(pr_number, &branch_name));
+    }
+
+    let output = Command::new("git")
+        .current_dir(worktree_path)
+        .args(["merge", "--ff-only", "--quiet", head])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(WorktreeError::GitError(format!(
+            "Failed to update PR #{}: {}",
+            pr_number,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Configure a local branch to push to a PR's head branch.
+///
+/// Uses `branch.<name>.pushRemote` so only PR branches set up here are affected;
+/// the remote branch name is stored as the branch's merge ref.
+fn set_push_target(
+    local_path: &Path,
+    branch_name: &str,
+    target: &PushTarget,
+) -> Result<(), WorktreeError> {
+    let has_remote = Command::new("git")
+        .current_dir(local_path)
+        .args(["remote", "get-url", &target.remote])
+        .output()
+        .is_ok_and(|o| o.status.success());
+
+    if !has_remote {
+        let output = Command::new("git")
+            .current_dir(local_path)
+            .args(["remote", "add", &target.remote, &target.url])
+            .output()?;
+        if !output.status.success() {
+            return Err(WorktreeError::GitError(format!(
+                "Failed to add remote {}: {}",
+                target.remote,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    }
+
+    let settings = [
+        (format!("branch.{}.remote", branch_name), target.remote.clone()),
+        (format!("branch.{}.pushRemote", branch_name), target.remote.clone()),
+        (
+            format!("branch.{}.merge", branch_name),
+            format!("refs/heads/{}", target.branch),
+        ),
+    ];
+    for (key, value) in settings {
+        let output = Command::new("git")
+            .current_dir(local_path)
+            .args(["config", &key, &value])
+            .output()?;
+        if !output.status.success() {
+            return Err(WorktreeError::GitError(format!(
+                "Failed to set {}: {}",
+                key,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the push target configured by `set_push_target`, as (remote, remote branch).
+fn configured_push_target(repo_path: &Path, branch_name: &str) -> Option<(String, String)> {
+    let read = |key: String| {
+        Command::new("git")
+            .current_dir(repo_path)
+            .args(["config", "--get", &key])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let remote = read(format!("branch.{}.pushRemote", branch_name))?;
+    let merge = read(format!("branch.{}.merge", branch_name))?;
+    let remote_branch = merge.strip_prefix("refs/heads/").unwrap_or(&merge).to_string();
+    Some((remote, remote_branch))
 }
 
 /// Shared implementation for creating worktrees.
 fn create_worktree_impl(
     local_path: &Path,
+    project: &str,
     branch_name: &str,
     worktree_name: &str,
     base_branch: Option<&str>,
@@ -218,10 +534,24 @@ fn create_worktree_impl(
             String::from_utf8_lossy(&output.stderr)
         )));
     }
+    set_up(local_path, project, &worktree_path)?;
 
     Ok((worktree_path, branch_name.to_string()))
 }
 
+/// Run the project's worktree setup in a worktree just added.
+///
+/// On failure the worktree is removed, keeping its branch, so the next dispatch starts
+/// over with a fresh worktree instead of an agent in a half-prepared one.
+fn set_up(local_path: &Path, project: &str, worktree_path: &Path) -> Result<(), WorktreeError> {
+    let Some(config) = project_setup(project) else {
+        return Ok(());
+    };
+    setup_worktree(local_path, worktree_path, &config).inspect_err(|_| {
+        let _ = remove_worktree(local_path, worktree_path, false);
+    })
+}
+
 /// Remove a git worktree.
 ///
 /// # Arguments
@@ -330,6 +660,155 @@ pub fn get_diff_stats(worktree_path: &Path) -> (usize, usize, usize) {
     (0, 0, 0)
 }
 
+/// Get the full diff of a worktree against the merge-base with the default branch.
+///
+/// Includes uncommitted changes. Returns an empty string when no diff is available.
+pub fn get_diff(worktree_path: &Path) -> String {
+    let base = find_merge_base(worktree_path).unwrap_or_else(|| "HEAD".to_string());
+
+    Command::new("git")
+        .current_dir(worktree_path)
+        .args(["diff", &base])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .unwrap_or_default()
+}
+
+/// Files changed in a worktree against the merge-base with the default branch,
+/// with lines added and deleted. Includes uncommitted changes.
+pub fn get_changed_files(worktree_path: &Path) -> Vec<(String, usize, usize)> {
+    let base = find_merge_base(worktree_path).unwrap_or_else(|| "HEAD".to_string());
+
+    Command::new("git")
+        .current_dir(worktree_path)
+        .args(["diff", "--numstat", &base])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| parse_numstat_files(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default()
+}
+
+/// Push a branch and set it as upstream.
+///
+/// PR worktrees push to their head repository branch; other branches go to origin.
+pub fn push_branch(worktree_path: &Path, branch_name: &str) -> Result<(), WorktreeError> {
+    let output = match configured_push_target(worktree_path, branch_name) {
+        Some((remote, remote_branch)) => Command::new("git")
+            .current_dir(worktree_path)
+            .args(["push", &remote, &format!("HEAD:{}", remote_branch)])
+            .output()?,
+        None => Command::new("git")
+            .current_dir(worktree_path)
+            .args(["push", "-u", "origin", branch_name])
+            .output()?,
+    };
+
+    if !output.status.success() {
+        return Err(WorktreeError::GitError(format!(
+            "Failed to push {}: {}",
+            branch_name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Whether a worktree has uncommitted changes, untracked files included.
+pub fn has_uncommitted_changes(worktree_path: &Path) -> bool {
+    Command::new("git")
+        .current_dir(worktree_path)
+        .args(["status", "--porcelain"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .is_some_and(|o| !o.stdout.trim_ascii().is_empty())
+}
+
+/// Diff of the uncommitted changes of a worktree, untracked files included.
+pub fn get_uncommitted_diff(worktree_path: &Path) -> String {
+    // Register untracked files without staging them, so they show in the diff
+    let _ = Command::new("git")
+        .current_dir(worktree_path)
+        .args(["add", "--intent-to-add", "--all", "--", ".", ":!.assistant-*"])
+        .output();
+    Command::new("git")
+        .current_dir(worktree_path)
+        .args(["diff", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .unwrap_or_default()
+}
+
+/// Commit all changes of a worktree, leaving out the assistant's own `.assistant-*` files.
+/// Returns false when there was nothing to commit.
+pub fn commit_all(worktree_path: &Path, message: &str) -> Result<bool, WorktreeError> {
+    let output = Command::new("git")
+        .current_dir(worktree_path)
+        .args(["add", "--all", "--", ".", ":!.assistant-*"])
+        .output()?;
+    if !output.status.success() {
+        return Err(WorktreeError::GitError(format!(
+            "Failed to stage changes: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let staged = Command::new("git")
+        .current_dir(worktree_path)
+        .args(["diff", "--cached", "--quiet"])
+        .status()?;
+    if staged.success() {
+        return Ok(false);
+    }
+
+    let output = Command::new("git")
+        .current_dir(worktree_path)
+        .args(["commit", "-m", message])
+        .output()?;
+    if !output.status.success() {
+        return Err(WorktreeError::GitError(format!(
+            "Failed to commit: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(true)
+}
+
+/// Get the branch currently checked out in a worktree.
+pub fn current_branch(worktree_path: &Path) -> Option<String> {
+    Command::new("git")
+        .current_dir(worktree_path)
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|b| !b.is_empty() && b != "HEAD")
+}
+
+/// Get the commit checked out in a worktree.
+pub fn head_sha(worktree_path: &Path) -> Option<String> {
+    Command::new("git")
+        .current_dir(worktree_path)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|sha| !sha.is_empty())
+}
+
+/// Detect the default branch of the repository a worktree belongs to.
+pub fn default_branch(worktree_path: &Path) -> Option<String> {
+    detect_default_branch(worktree_path)
+}
+
 /// Find the merge-base commit with the default branch.
 fn find_merge_base(worktree_path: &Path) -> Option<String> {
     ["main", "master", "develop"].iter().find_map(|branch| {
@@ -366,6 +845,19 @@ fn parse_numstat(stdout: &str) -> (usize, usize, usize) {
     (lines_added, lines_deleted, files_changed)
 }
 
+/// Per-file `git diff --numstat` output; binary files count no lines.
+fn parse_numstat_files(stdout: &str) -> Vec<(String, usize, usize)> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            let added = parts.next()?.parse().unwrap_or(0);
+            let deleted = parts.next()?.parse().unwrap_or(0);
+            Some((parts.next()?.to_string(), added, deleted))
+        })
+        .collect()
+}
+
 /// Information about a worktree on disk
 #[derive(Debug, Clone)]
 pub struct WorktreeInfo {
@@ -377,10 +869,16 @@ pub struct WorktreeInfo {
     pub project: String,
     /// Issue number extracted from the worktree name
     pub issue_number: Option<u64>,
+    /// Pull request number for worktrees created from a PR ("project-pr-123")
+    pub pr_number: Option<u64>,
     /// Whether this worktree has an active session
     pub has_session: bool,
     /// Whether there's a running tmux session for this worktree
     pub has_tmux: bool,
+    /// Latest agent session working in this worktree
+    pub session_id: Option<String>,
+    /// Outcome of that session's verification commands
+    pub verification: Option<super::Verification>,
 }
 
 /// List all worktrees in the cache directory with their status.
@@ -397,16 +895,25 @@ pub fn list_worktrees() -> Vec<WorktreeInfo> {
             let path = entry.path();
             if path.is_dir() {
                 let name = entry.file_name().to_string_lossy().to_string();
-                // Parse project and issue number from name (format: "project-123")
-                let (project, issue_number) = parse_worktree_name(&name);
+                // Parse project and issue/PR number from name ("project-123" or "project-pr-123")
+                let (project, issue_number, pr_number) = match parse_pr_worktree_name(&name) {
+                    Some((project, pr)) => (project, None, Some(pr)),
+                    None => {
+                        let (project, issue) = parse_worktree_name(&name);
+                        (project, issue, None)
+                    }
+                };
 
                 worktrees.push(WorktreeInfo {
                     path,
                     name,
                     project,
                     issue_number,
+                    pr_number,
                     has_session: false, // Will be filled in by caller
                     has_tmux: false,    // Will be filled in by caller
+                    session_id: None,
+                    verification: None,
                 });
             }
         }
@@ -416,9 +923,26 @@ pub fn list_worktrees() -> Vec<WorktreeInfo> {
     worktrees
 }
 
+/// Parse a PR worktree name into project and PR number.
+/// Format: "project-name-pr-123" -> Some(("project-name", 123))
+fn parse_pr_worktree_name(name: &str) -> Option<(String, u64)> {
+    let pos = name.rfind("-pr-")?;
+    let number = name[pos + 4..].parse::<u64>().ok()?;
+    Some((name[..pos].to_string(), number))
+}
+
 /// Parse worktree name into project and issue number.
-/// Format: "project-name-123" -> ("project-name", Some(123))
+/// Format: "project-name-123" -> ("project-name", Some(123)); attempts at an issue
+/// ("project-name-123-b") belong to the issue too.
 fn parse_worktree_name(name: &str) -> (String, Option<u64>) {
+    if let Some((rest, attempt)) = name.rsplit_once('-')
+        && attempt.len() == 1
+        && attempt.chars().all(|c| c.is_ascii_lowercase())
+        && let (project, Some(num)) = parse_worktree_name(rest)
+    {
+        return (project, Some(num));
+    }
+
     // Find the last dash followed by digits
     if let Some(pos) = name.rfind('-') {
         let (project, num_part) = name.split_at(pos);
@@ -521,6 +1045,102 @@ fn find_parent_repo(worktree_path: &Path) -> Option<PathBuf> {
 mod tests {
     use super::*;
 
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn commit_all_leaves_out_assistant_files() {
+        let dir = std::env::temp_dir().join(format!("assistant-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "-q"]);
+        git(&dir, &["config", "user.email", "test@example.com"]);
+        git(&dir, &["config", "user.name", "Test"]);
+        std::fs::write(dir.join("README.md"), "readme\n").unwrap();
+        git(&dir, &["add", "README.md"]);
+        git(&dir, &["commit", "-qm", "init"]);
+        assert!(!has_uncommitted_changes(&dir));
+
+        std::fs::write(dir.join("new.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.join(".assistant-plan.md"), "plan\n").unwrap();
+        assert!(has_uncommitted_changes(&dir));
+        assert!(get_uncommitted_diff(&dir).contains("+fn main() {}"));
+
+        assert!(commit_all(&dir, "Add main").unwrap());
+        let output = Command::new("git")
+            .current_dir(&dir)
+            .args(["show", "--name-only", "--format=%s", "HEAD"])
+            .output()
+            .unwrap();
+        let shown = String::from_utf8_lossy(&output.stdout).to_string();
+        assert!(shown.starts_with("Add main"));
+        assert!(shown.contains("new.rs"));
+        assert!(!shown.contains(".assistant-plan.md"));
+
+        // Only the assistant's file is left
+        assert!(!commit_all(&dir, "Nothing").unwrap());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn pr_branch_is_fast_forwarded_but_never_overwritten() {
+        let dir = std::env::temp_dir().join(format!("assistant-test-{}", uuid::Uuid::new_v4()));
+        let (origin, local, wt) = (dir.join("origin"), dir.join("local"), dir.join("wt"));
+        std::fs::create_dir_all(&origin).unwrap();
+        let commit = |repo: &Path, message: &str| {
+            let identity = ["-c", "user.name=Test", "-c", "user.email=test@example.com"];
+            git(repo, &[&identity[..], &["commit", "-q", "--allow-empty", "-m", message]].concat());
+        };
+        let rev = |repo: &Path, rev: &str| {
+            let output = Command::new("git")
+                .current_dir(repo)
+                .args(["rev-parse", rev])
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        git(&origin, &["init", "-q"]);
+        commit(&origin, "first");
+        git(&origin, &["update-ref", "refs/pull/7/head", "HEAD"]);
+        git(&dir, &["clone", "-q", origin.to_str().unwrap(), "local"]);
+
+        let head = fetch_pr_head(&local, 7).unwrap();
+        ensure_pr_branch(&local, "pr-7", 7, &head).unwrap();
+        git(&local, &["worktree", "add", "-q", wt.to_str().unwrap(), "pr-7"]);
+
+        // New commits on the PR are fast-forwarded
+        commit(&origin, "second");
+        git(&origin, &["update-ref", "refs/pull/7/head", "HEAD"]);
+        fetch_pr_head(&local, 7).unwrap();
+        fast_forward_pr_worktree(&wt, 7, &head).unwrap();
+        assert_eq!(rev(&wt, "HEAD"), rev(&origin, "HEAD"));
+
+        // Local commits not pushed yet are kept
+        commit(&wt, "local");
+        let local_head = rev(&wt, "HEAD");
+        fast_forward_pr_worktree(&wt, 7, &head).unwrap();
+        ensure_pr_branch(&local, "pr-7", 7, &head).unwrap();
+        assert_eq!(rev(&wt, "HEAD"), local_head);
+
+        // A force-pushed PR is reported rather than overwriting them
+        git(&origin, &["checkout", "-q", "--detach", "HEAD~1"]);
+        commit(&origin, "rewritten");
+        git(&origin, &["update-ref", "refs/pull/7/head", "HEAD"]);
+        fetch_pr_head(&local, 7).unwrap();
+        assert!(fast_forward_pr_worktree(&wt, 7, &head).is_err());
+        assert!(ensure_pr_branch(&local, "pr-7", 7, &head).is_err());
+        assert_eq!(rev(&local, "pr-7"), local_head);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn worktree_path_generation() {
         let path = worktrees_dir().join("test-project-123");
@@ -549,6 +1169,76 @@ mod tests {
         assert_eq!(num, None);
     }
 
+    #[test]
+    fn parse_worktree_name_attempt() {
+        assert_eq!(parse_worktree_name("my-project-42-b"), ("my-project".to_string(), Some(42)));
+        assert_eq!(parse_worktree_name("my-project-a"), ("my-project-a".to_string(), None));
+    }
+
+    #[test]
+    fn parse_numstat_files_lists_changes() {
+        let files = parse_numstat_files("3\t1\tsrc/lib.rs\n-\t-\tlogo.png\n");
+        assert_eq!(
+            files,
+            vec![("src/lib.rs".to_string(), 3, 1), ("logo.png".to_string(), 0, 0)]
+        );
+    }
+
+    #[test]
+    fn parse_pr_worktree_name_variants() {
+        assert_eq!(
+            parse_pr_worktree_name("my-project-pr-17"),
+            Some(("my-project".to_string(), 17))
+        );
+        assert_eq!(parse_pr_worktree_name("my-project-17"), None);
+        assert_eq!(parse_pr_worktree_name("my-project-pr-abc"), None);
+    }
+
+    #[test]
+    fn push_target_same_repo_uses_origin() {
+        let target = PushTarget::for_pull_request(
+            "owner/repo",
+            Some("owner/repo"),
+            Some("https://github.com/owner/repo.git"),
+            "feature/x",
+            false,
+        );
+        assert_eq!(
+            target,
+            Some(PushTarget {
+                remote: "origin".to_string(),
+                url: "https://github.com/owner/repo.git".to_string(),
+                branch: "feature/x".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn push_target_fork_requires_maintainer_can_modify() {
+        let fork = |can_modify| {
+            PushTarget::for_pull_request(
+                "owner/repo",
+                Some("contributor/repo"),
+                Some("https://github.com/contributor/repo.git"),
+                "fix-typo",
+                can_modify,
+            )
+        };
+
+        assert_eq!(fork(false), None);
+        let target = fork(true).unwrap();
+        assert_eq!(target.remote, "contributor");
+        assert_eq!(target.branch, "fix-typo");
+    }
+
+    #[test]
+    fn push_target_deleted_fork() {
+        assert_eq!(
+            PushTarget::for_pull_request("owner/repo", None, None, "fix-typo", true),
+            None
+        );
+    }
+
     // Integration tests would require actual git repos
     // and are skipped in unit tests
 }
@@ -0,0 +1,237 @@
+//! Failing CI: trimming job logs and building the fix prompt.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::github::{FailedCheck, PullRequestDetail};
+
+/// Maximum log excerpt kept per failed job, in bytes.
+const MAX_LOG_BYTES: usize = 8_000;
+
+/// Lines kept before and after each error line.
+const CONTEXT_BEFORE: usize = 15;
+const CONTEXT_AFTER: usize = 5;
+
+/// Lines kept from the end of a log without any recognizable error.
+const TAIL_LINES: usize = 80;
+
+/// A failed check and the relevant part of its log, when available
+#[derive(Debug, Clone)]
+pub struct CheckFailure {
+    pub check: FailedCheck,
+    pub log: Option<String>,
+}
+
+/// Strip the timestamp GitHub Actions puts in front of every log line.
+fn strip_timestamp(line: &str) -> &str {
+    match line.split_once(' ') {
+        Some((prefix, rest))
+            if prefix.len() >= 20 && prefix.ends_with('Z') && prefix.as_bytes()[4] == b'-' =>
+        {
+            rest
+        }
+        _ => line,
+    }
+}
+
+/// Pattern of log lines reporting an error or failure
+fn error_pattern() -> &'static Regex {
+    static ERROR: OnceLock<Regex> = OnceLock::new();
+    ERROR.get_or_init(|| {
+        Regex::new(
+            r"(?i)(##\[error\]|\berror(\[E\d+\])?:|\bfailed\b|\bfailures?:|panicked at|\bFAIL\b|Traceback|exit code [1-9])",
+        )
+        .unwrap()
+    })
+}
+
+/// Whether a log line reports an error or failure.
+pub fn is_error_line(line: &str) -> bool {
+    error_pattern().is_match(line)
+}
+
+/// Reduce a job log to the sections around errors.
+///
+/// Keeps a window of context around each error line, merging overlapping windows.
+/// Falls back to the end of the log when no error line is found, and keeps the
+/// last sections when the excerpt exceeds the size budget.
+pub fn trim_log(log: &str) -> String {
+    let ansi = Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").unwrap();
+
+    let lines: Vec<String> = log
+        .lines()
+        .map(|l| ansi.replace_all(strip_timestamp(l), "").into_owned())
+        .filter(|l| !l.starts_with("##[group]") && !l.starts_with("##[endgroup]"))
+        .collect();
+
+    // Line ranges to keep, merged when they overlap
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if !is_error_line(line) {
+            continue;
+        }
+        let start = i.saturating_sub(CONTEXT_BEFORE);
+        let end = (i + CONTEXT_AFTER + 1).min(lines.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+    if ranges.is_empty() {
+        ranges.push((lines.len().saturating_sub(TAIL_LINES), lines.len()));
+    }
+
+    let sections: Vec<String> = ranges
+        .iter()
+        .map(|&(start, end)| lines[start..end].join("\n"))
+        .collect();
+
+    // Keep the last sections within the budget: the final errors are usually the cause
+    let mut kept: Vec<&str> = Vec::new();
+    let mut size = 0;
+    for section in sections.iter().rev() {
+        if size + section.len() > MAX_LOG_BYTES {
+            if kept.is_empty() {
+                let cut = section.len() - MAX_LOG_BYTES;
+                let cut = (cut..section.len())
+                    .find(|&i| section.is_char_boundary(i))
+                    .unwrap_or(section.len());
+                kept.push(&section[cut..]);
+            }
+            break;
+        }
+        size += section.len();
+        kept.push(section);
+    }
+    kept.reverse();
+
+    let mut excerpt = kept.join("\n...\n");
+    if kept.len() < sections.len() {
+        excerpt = format!("[{} earlier sections omitted]\n{}", sections.len() - kept.len(), excerpt);
+    }
+    excerpt
+}
+
+/// Build the prompt asking an agent to fix the failing checks of a pull request.
+pub fn build_ci_fix_prompt(pr: &PullRequestDetail, failures: &[CheckFailure]) -> String {
+    let names: Vec<&str> = failures.iter().map(|f| f.check.name.as_str()).collect();
+    let mut prompt = format!(
+        "CI is failing on PR #{}: {}\n\nFailing checks: {}\n",
+        pr.number,
+        pr.title,
+        names.join(", ")
+    );
+
+    for failure in failures {
+        prompt.push_str(&format!(
+            "\n### {} ({})\n{}\n",
+            failure.check.name, failure.check.conclusion, failure.check.html_url
+        ));
+        match &failure.log {
+            Some(log) => prompt.push_str(&format!("\n```\n{}\n```\n", log)),
+            None => prompt.push_str("\nNo log available for this check.\n"),
+        }
+    }
+
+    prompt.push_str(
+        "\nFind the root cause of these failures and fix it. Reproduce locally when you can. \
+        Commit your changes when you are done, but do not push.",
+    );
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str) -> FailedCheck {
+        FailedCheck {
+            id: 1,
+            name: name.to_string(),
+            conclusion: "failure".to_string(),
+            html_url: "https://github.com/test/test/runs/1".to_string(),
+            is_actions_job: true,
+        }
+    }
+
+    #[test]
+    fn trim_log_keeps_context_around_errors() {
+        let mut log = String::new();
+        for i in 0..200 {
+            log.push_str(&format!("2024-01-01T00:00:00.0000000Z step {}\n", i));
+        }
+        log.push_str("2024-01-01T00:00:00.0000000Z \x1b[31merror[E0308]: mismatched types\x1b[0m\n");
+        for i in 0..50 {
+            log.push_str(&format!("2024-01-01T00:00:00.0000000Z after {}\n", i));
+        }
+
+        let trimmed = trim_log(&log);
+        assert!(trimmed.contains("error[E0308]: mismatched types"));
+        assert!(!trimmed.contains("\x1b["));
+        assert!(!trimmed.contains("2024-01-01T"));
+        assert!(trimmed.contains("step 199"));
+        assert!(!trimmed.contains("step 100"));
+        assert!(trimmed.contains("after 4"));
+        assert!(!trimmed.contains("after 10"));
+    }
+
+    #[test]
+    fn trim_log_without_errors_keeps_tail() {
+        let log: String = (0..500).map(|i| format!("line {}\n", i)).collect();
+        let trimmed = trim_log(&log);
+        assert!(trimmed.contains("line 499"));
+        assert!(!trimmed.contains("line 100\n"));
+    }
+
+    #[test]
+    fn trim_log_respects_budget() {
+        let log: String = (0..2000)
+            .map(|i| format!("error: failure number {} {}\n", i, "x".repeat(40)))
+            .collect();
+        let trimmed = trim_log(&log);
+        assert!(trimmed.len() <= MAX_LOG_BYTES + 100);
+        assert!(trimmed.contains("failure number 1999"));
+    }
+
+    #[test]
+    fn build_ci_fix_prompt_lists_checks_and_logs() {
+        let pr = PullRequestDetail {
+            number: 5,
+            title: "Add parser".to_string(),
+            body: None,
+            html_url: "https://github.com/test/test/pull/5".to_string(),
+            labels: vec![],
+            state: "Open".to_string(),
+            assignees: vec![],
+            author: "octocat".to_string(),
+            draft: false,
+            head_ref: "parser".to_string(),
+            head_sha: "abc123".to_string(),
+            base_ref: "main".to_string(),
+            mergeable: None,
+            requested_reviewers: vec![],
+            head_repo: None,
+            head_clone_url: None,
+            maintainer_can_modify: false,
+            comments: vec![],
+        };
+        let failures = vec![
+            CheckFailure {
+                check: check("test"),
+                log: Some("error: assertion failed".to_string()),
+            },
+            CheckFailure {
+                check: check("external"),
+                log: None,
+            },
+        ];
+
+        let prompt = build_ci_fix_prompt(&pr, &failures);
+        assert!(prompt.contains("CI is failing on PR #5: Add parser"));
+        assert!(prompt.contains("Failing checks: test, external"));
+        assert!(prompt.contains("```\nerror: assertion failed\n```"));
+        assert!(prompt.contains("No log available"));
+        assert!(prompt.contains("do not push"));
+    }
+}
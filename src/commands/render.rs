@@ -76,6 +76,11 @@ pub fn generate_full_help() -> Vec<Line<'static>> {
     lines.push(Line::from(""));
     lines.extend(help_lines_for_context(CommandContext::PullRequestList));
 
+    // PR Detail section
+    lines.push(section_header("PULL REQUEST DETAIL"));
+    lines.push(Line::from(""));
+    lines.extend(help_lines_for_context(CommandContext::PullRequestDetail));
+
     // Embedded Terminal section
     lines.push(section_header("EMBEDDED TERMINAL"));
     lines.push(Line::from(""));
@@ -139,7 +144,14 @@ pub fn status_bar_shortcuts(context: CommandContext) -> &'static [Shortcut] {
         CommandContext::PullRequestDetail => &[
             Shortcut::OpenInBrowser,
             Shortcut::ReviewPR,
+            Shortcut::AddressFeedback,
+            Shortcut::FixFailingCI,
             Shortcut::MergePR,
+            Shortcut::ToggleDraftPR,
+            Shortcut::RequestReviewers,
+            Shortcut::EditPR,
+            Shortcut::EditLabels,
+            Shortcut::ClosePR,
             Shortcut::GoBack,
         ],
         CommandContext::EmbeddedTmux => &[
@@ -41,15 +41,31 @@ pub enum Shortcut {
     ViewLogs,
     CreatePR,
     KillAgent,
+    DispatchQueue,
+    CompareAttempts,
+    ReviewPlan,
+    QuickReply,
+    ResumeSession,
     OpenIDE,
     DeleteWorktree,
     CreateWorktree,
+    VerifyWorktree,
 
     // PR actions
     SwitchToIssues,
     CheckoutBranch,
     ReviewPR,
     MergePR,
+    ToggleDraftPR,
+    RequestReviewers,
+    EditPR,
+    ClosePR,
+    ReopenPR,
+    EditLabels,
+    PostReviewFindings,
+    AddressFeedback,
+    ReplyAddressed,
+    FixFailingCI,
 
     // Embedded terminal
     ExitTerminal,
@@ -90,13 +106,29 @@ impl Shortcut {
             Self::ViewLogs,
             Self::CreatePR,
             Self::KillAgent,
+            Self::DispatchQueue,
+            Self::CompareAttempts,
+            Self::ReviewPlan,
+            Self::QuickReply,
+            Self::ResumeSession,
             Self::OpenIDE,
             Self::DeleteWorktree,
             Self::CreateWorktree,
+            Self::VerifyWorktree,
             Self::SwitchToIssues,
             Self::CheckoutBranch,
             Self::ReviewPR,
             Self::MergePR,
+            Self::ToggleDraftPR,
+            Self::RequestReviewers,
+            Self::EditPR,
+            Self::ClosePR,
+            Self::ReopenPR,
+            Self::EditLabels,
+            Self::PostReviewFindings,
+            Self::AddressFeedback,
+            Self::ReplyAddressed,
+            Self::FixFailingCI,
             Self::ExitTerminal,
             Self::PrevSession,
             Self::NextSession,
@@ -135,13 +167,29 @@ impl Shortcut {
             Self::ViewLogs => "l",
             Self::CreatePR => "p",
             Self::KillAgent => "K",
+            Self::DispatchQueue => "Q",
+            Self::CompareAttempts => "A",
+            Self::ReviewPlan => "P",
+            Self::QuickReply => "r",
+            Self::ResumeSession => "u",
             Self::OpenIDE => "o",
             Self::DeleteWorktree => "d/W",
             Self::CreateWorktree => "n",
+            Self::VerifyWorktree => "v",
             Self::SwitchToIssues => "Tab",
             Self::CheckoutBranch => "c",
             Self::ReviewPR => "r",
             Self::MergePR => "m",
+            Self::ToggleDraftPR => "D",
+            Self::RequestReviewers => "v",
+            Self::EditPR => "e",
+            Self::ClosePR => "x",
+            Self::ReopenPR => "X",
+            Self::EditLabels => "l",
+            Self::PostReviewFindings => "F",
+            Self::AddressFeedback => "a",
+            Self::ReplyAddressed => "A",
+            Self::FixFailingCI => "C",
             Self::ExitTerminal => "Ctrl+Q",
             Self::PrevSession => "Shift+\u{2190}",
             Self::NextSession => "Shift+\u{2192}",
@@ -179,14 +227,30 @@ impl Shortcut {
             Self::OpenAnyTmux => "Open any tmux session",
             Self::ViewLogs => "View agent logs",
             Self::CreatePR => "Create pull request",
-            Self::KillAgent => "Kill agent",
+            Self::KillAgent => "Kill agent / remove from queue",
+            Self::DispatchQueue => "Show dispatch queue",
+            Self::CompareAttempts => "Compare attempts at the issue",
+            Self::ReviewPlan => "Review the agent's implementation plan",
+            Self::QuickReply => "Reply to agents waiting for input",
+            Self::ResumeSession => "Resume the ended agent session",
             Self::OpenIDE => "Open in IDE",
             Self::DeleteWorktree => "Delete worktree",
             Self::CreateWorktree => "Create worktree",
+            Self::VerifyWorktree => "Run verification commands",
             Self::SwitchToIssues => "Switch to issues",
             Self::CheckoutBranch => "Checkout as worktree",
             Self::ReviewPR => "Review with agent",
             Self::MergePR => "Merge PR",
+            Self::ToggleDraftPR => "Toggle draft / ready for review",
+            Self::RequestReviewers => "Request / remove reviewers",
+            Self::EditPR => "Edit title, body and base",
+            Self::ClosePR => "Close PR",
+            Self::ReopenPR => "Reopen PR",
+            Self::EditLabels => "Add / remove labels",
+            Self::PostReviewFindings => "Post agent review findings",
+            Self::AddressFeedback => "Send review feedback to an agent",
+            Self::ReplyAddressed => "Push fix and reply on addressed threads",
+            Self::FixFailingCI => "Dispatch agent to fix failing CI",
             Self::ExitTerminal => "Exit terminal",
             Self::PrevSession => "Previous session",
             Self::NextSession => "Next session",
@@ -212,6 +276,12 @@ impl Shortcut {
             Self::SelectIssue => "select",
             Self::ViewLogs => "logs",
             Self::KillAgent => "kill",
+            Self::DispatchQueue => "queue",
+            Self::CompareAttempts => "attempts",
+            Self::ReviewPlan => "plan",
+            Self::QuickReply => "reply",
+            Self::ResumeSession => "resume",
+            Self::VerifyWorktree => "verify",
             Self::SwitchToPRs => "prs",
             Self::SwitchToIssues => "issues",
             Self::GoBack => "back",
@@ -221,6 +291,15 @@ impl Shortcut {
             Self::MergePR => "merge",
             Self::ReviewPR => "review",
             Self::CheckoutBranch => "checkout",
+            Self::ToggleDraftPR => "draft",
+            Self::RequestReviewers => "reviewers",
+            Self::EditPR => "edit",
+            Self::ClosePR => "close",
+            Self::EditLabels => "labels",
+            Self::PostReviewFindings => "findings",
+            Self::AddressFeedback => "address",
+            Self::ReplyAddressed => "replied",
+            Self::FixFailingCI => "fix CI",
             _ => self.description(),
         }
     }
@@ -253,9 +332,15 @@ impl Shortcut {
             | Self::StartAgent
             | Self::ViewLogs
             | Self::KillAgent
+            | Self::DispatchQueue
+            | Self::CompareAttempts
+            | Self::ReviewPlan
+            | Self::QuickReply
+            | Self::ResumeSession
             | Self::OpenIDE
             | Self::DeleteWorktree
             | Self::CreateWorktree
+            | Self::VerifyWorktree
             | Self::CreatePR => CommandCategory::Agent,
 
             Self::OpenTmux
@@ -268,7 +353,17 @@ impl Shortcut {
             | Self::SwitchToIssues
             | Self::CheckoutBranch
             | Self::ReviewPR
-            | Self::MergePR => CommandCategory::PullRequests,
+            | Self::MergePR
+            | Self::ToggleDraftPR
+            | Self::RequestReviewers
+            | Self::EditPR
+            | Self::ClosePR
+            | Self::ReopenPR
+            | Self::EditLabels
+            | Self::PostReviewFindings
+            | Self::AddressFeedback
+            | Self::ReplyAddressed
+            | Self::FixFailingCI => CommandCategory::PullRequests,
         }
     }
 
@@ -333,7 +428,15 @@ impl Shortcut {
                 CommandContext::IssueList,
                 CommandContext::WorktreeList,
             ],
-            Self::ViewLogs | Self::KillAgent => &[CommandContext::IssueList],
+            Self::ViewLogs
+            | Self::KillAgent
+            | Self::DispatchQueue
+            | Self::CompareAttempts
+            | Self::ReviewPlan => &[CommandContext::IssueList],
+            Self::QuickReply | Self::ResumeSession => &[
+                CommandContext::IssueList,
+                CommandContext::WorktreeList,
+            ],
             Self::OpenIDE => &[
                 CommandContext::IssueList,
                 CommandContext::WorktreeList,
@@ -347,6 +450,7 @@ impl Shortcut {
                 CommandContext::WorktreeList,
             ],
             Self::CreateWorktree => &[CommandContext::WorktreeList],
+            Self::VerifyWorktree => &[CommandContext::WorktreeList],
             Self::StartAgent => &[CommandContext::WorktreeList],
 
             // Browser open
@@ -368,6 +472,18 @@ impl Shortcut {
                 CommandContext::PullRequestList,
                 CommandContext::PullRequestDetail,
             ],
+            Self::ToggleDraftPR
+            | Self::RequestReviewers
+            | Self::EditPR
+            | Self::ClosePR
+            | Self::ReopenPR
+            | Self::EditLabels
+            | Self::AddressFeedback
+            | Self::ReplyAddressed => &[CommandContext::PullRequestDetail],
+            Self::PostReviewFindings | Self::FixFailingCI => &[
+                CommandContext::PullRequestList,
+                CommandContext::PullRequestDetail,
+            ],
 
             // Embedded terminal
             Self::ExitTerminal | Self::PrevSession | Self::NextSession => {
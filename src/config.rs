@@ -10,6 +10,342 @@ pub enum CodingAgentType {
     #[default]
     Claude,
     Opencode,
+    /// Agent defined in `custom_agents`, by name
+    Custom(String),
+}
+
+impl CodingAgentType {
+    /// Name shown in the UI
+    pub fn display_name(&self) -> &str {
+        match self {
+            CodingAgentType::Claude => "Claude Code",
+            CodingAgentType::Opencode => "Opencode",
+            CodingAgentType::Custom(name) => name,
+        }
+    }
+}
+
+/// Where agent sessions run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionBackendKind {
+    /// tmux sessions
+    Tmux,
+    /// zellij sessions
+    Zellij,
+    /// Pseudo-terminals owned by a supervisor process, no tmux needed
+    Native,
+}
+
+/// Coding agent defined in the configuration file
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct CustomAgentConfig {
+    pub name: String,
+    /// Shell command launching the agent in a worktree.
+    /// `{prompt}`, `{prompt_file}` and `{worktree}` are replaced with quoted values.
+    pub launch: String,
+    /// Regexes matched against the last lines of the agent's pane; any match means idle
+    #[serde(default)]
+    pub idle_patterns: Vec<String>,
+    /// Non-interactive command printing a response, used for text generation.
+    /// Supports `{prompt}` and `{prompt_file}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generate: Option<String>,
+    /// Environment variables set for the agent
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+}
+
+/// Agent, model and extra CLI flags used for a dispatch.
+/// Unset fields keep the value they would otherwise have.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct AgentProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<CodingAgentType>,
+    /// Model passed to the agent with `--model`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Extra CLI flags, e.g. `["--permission-mode", "plan"]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<String>,
+}
+
+/// Dispatch issues carrying `label` with the named agent profile
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LabelRoute {
+    pub label: String,
+    pub profile: String,
+}
+
+/// Agent settings resolved for a dispatch
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedProfile {
+    /// Named profile that was applied, if any
+    pub name: Option<String>,
+    pub agent: CodingAgentType,
+    pub model: Option<String>,
+    pub flags: Vec<String>,
+}
+
+impl ResolvedProfile {
+    /// Plain agent, without model or flags
+    pub fn for_agent(agent: CodingAgentType) -> Self {
+        Self {
+            name: None,
+            agent,
+            model: None,
+            flags: Vec::new(),
+        }
+    }
+
+    fn apply(&mut self, profile: &AgentProfile) {
+        if let Some(agent) = &profile.agent {
+            self.agent = agent.clone();
+        }
+        if let Some(model) = &profile.model {
+            self.model = Some(model.clone());
+        }
+        self.flags.extend(profile.flags.iter().cloned());
+    }
+
+    /// Extra arguments for the agent's command line
+    pub fn cli_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(model) = &self.model {
+            args.push("--model".to_string());
+            args.push(model.clone());
+        }
+        args.extend(self.flags.iter().cloned());
+        args
+    }
+
+    /// One-line description, e.g. "docs: Claude Code · haiku · --verbose"
+    pub fn summary(&self) -> String {
+        let mut parts = vec![self.agent.display_name().to_string()];
+        if let Some(model) = &self.model {
+            parts.push(model.clone());
+        }
+        if !self.flags.is_empty() {
+            parts.push(self.flags.join(" "));
+        }
+        match &self.name {
+            Some(name) => format!("{}: {}", name, parts.join(" · ")),
+            None => parts.join(" · "),
+        }
+    }
+}
+
+/// Recording of agent terminal output to the session's log file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscriptConfig {
+    /// Keep colors and other escape sequences instead of plain text
+    pub keep_ansi: bool,
+    /// Size at which the log file is rotated, in kilobytes
+    pub max_size_kb: u64,
+    /// Number of rotated files kept next to the log (`.log.1` is the most recent)
+    pub rotations: usize,
+    /// Also record an asciicast v2 file (`<id>.cast`) for replay
+    pub asciicast: bool,
+}
+
+impl Default for TranscriptConfig {
+    fn default() -> Self {
+        Self {
+            keep_ansi: false,
+            max_size_kb: 10 * 1024,
+            rotations: 3,
+            asciicast: false,
+        }
+    }
+}
+
+/// What goes into the prompt of a dispatched issue, besides its title and body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PromptConfig {
+    /// Include the comment thread
+    pub comments: bool,
+    /// Comment authors left out, besides GitHub apps (`<name>[bot]`)
+    pub bot_authors: Vec<String>,
+    /// Include the issue's labels
+    pub labels: bool,
+    /// Include the parent issue and the issues referenced as `#<number>`
+    pub linked_issues: bool,
+    /// List the images of the issue and its comments as URLs
+    pub images: bool,
+    /// Repository files with guidance for contributors, read from `local_path`
+    pub guidance_files: Vec<String>,
+    /// Size budget of the prompt, in characters
+    pub max_chars: usize,
+}
+
+impl Default for PromptConfig {
+    fn default() -> Self {
+        Self {
+            comments: true,
+            bot_authors: Vec::new(),
+            labels: true,
+            linked_issues: true,
+            images: true,
+            guidance_files: vec!["AGENTS.md".to_string(), "CONTRIBUTING.md".to_string()],
+            max_chars: 40_000,
+        }
+    }
+}
+
+/// Events that can be sent to notifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    /// An agent waits for input
+    Awaiting,
+    /// An agent session ended
+    Completed,
+    /// An agent session was stopped with an error
+    Failed,
+    /// A pull request was opened for an agent's work
+    PrCreated,
+}
+
+/// How a notifier delivers notifications
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotifierKind {
+    /// Desktop notification (notify-send/D-Bus on Linux, Notification Center on macOS)
+    Desktop,
+    /// Terminal bell and OSC 9 notification in the terminal running the assistant
+    Bell,
+    /// JSON POST to a URL
+    Webhook { url: String },
+    /// Shell command, with the notification in `ASSISTANT_*` environment variables
+    Command { command: String },
+}
+
+/// A notifier and the events routed to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotifierConfig {
+    #[serde(flatten)]
+    pub kind: NotifierKind,
+    /// Events to deliver; all events when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<NotifyEvent>,
+}
+
+impl NotifierConfig {
+    pub fn wants(&self, event: NotifyEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// Opening pull requests for finished agent work without a human in the loop
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoPrConfig {
+    /// Open the PR when the agent session ends
+    pub on_complete: bool,
+    /// Open the PR once the agent has been waiting for input this many minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idle_minutes: Option<u64>,
+    /// Open the PR as a draft
+    pub draft: bool,
+}
+
+impl Default for AutoPrConfig {
+    fn default() -> Self {
+        Self {
+            on_complete: true,
+            idle_minutes: None,
+            draft: true,
+        }
+    }
+}
+
+/// Commands checking an agent's work, run in its worktree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VerifyConfig {
+    /// Shell commands run in order, e.g. `cargo test`; the first failure stops the run
+    pub commands: Vec<String>,
+    /// Verify each time the agent starts waiting for input
+    pub on_idle: bool,
+    /// Verify when the agent session ends
+    pub on_complete: bool,
+    /// Output lines kept from the commands
+    pub tail_lines: usize,
+    /// Failed runs sent back to a waiting agent as a follow-up prompt, per session
+    pub feedback_rounds: u32,
+    /// Seconds a command may run before it is killed and the run fails
+    pub timeout_secs: u64,
+}
+
+impl Default for VerifyConfig {
+    fn default() -> Self {
+        Self {
+            commands: Vec::new(),
+            on_idle: true,
+            on_complete: true,
+            tail_lines: 40,
+            feedback_rounds: 0,
+            timeout_secs: 600,
+        }
+    }
+}
+
+/// Limits on a project's agent sessions, enforced by the session monitor
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionPolicy {
+    /// Stop the session once it has run this many minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_duration_minutes: Option<u64>,
+    /// Act once the agent has been waiting for input this many minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_awaiting_minutes: Option<u64>,
+    /// What to do when `max_awaiting_minutes` is reached
+    pub on_awaiting: AwaitingAction,
+    /// Pause the agent once its diff has this many added and deleted lines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_diff_lines: Option<usize>,
+}
+
+/// Action on an agent waiting for input too long
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AwaitingAction {
+    /// Send a reminder notification
+    #[default]
+    Remind,
+    /// Stop the session
+    Kill,
+}
+
+/// Preparing fresh worktrees with what `git worktree add` leaves out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorktreeSetupConfig {
+    /// Files, directories or globs copied from `local_path`, e.g. `.env*`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub copy: Vec<String>,
+    /// Files, directories or globs symlinked from `local_path`, e.g. `node_modules`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub symlink: Vec<String>,
+    /// Shell commands run in order in the new worktree, e.g. `npm ci`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<String>,
+    /// Seconds a command may run before it is killed and the setup fails
+    pub timeout_secs: u64,
+}
+
+impl Default for WorktreeSetupConfig {
+    fn default() -> Self {
+        Self {
+            copy: Vec::new(),
+            symlink: Vec::new(),
+            commands: Vec::new(),
+            timeout_secs: 600,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +361,24 @@ pub struct ProjectConfig {
     /// Base branch for creating new branches (default: auto-detect main/master)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub base_branch: Option<String>,
+    /// Agent settings for this project, over the global agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<AgentProfile>,
+    /// Label-based profile selection; the first rule matching an issue label wins
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agent_routing: Vec<LabelRoute>,
+    /// Commit, push and open a pull request when an agent is done (off when not set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_pr: Option<AutoPrConfig>,
+    /// Verification commands, e.g. tests and lints (none when not set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify: Option<VerifyConfig>,
+    /// Files to bring over and commands to run when a worktree is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worktree_setup: Option<WorktreeSetupConfig>,
+    /// Time and diff size limits on agent sessions (none when not set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<SessionPolicy>,
 }
 
 impl ProjectConfig {
@@ -52,9 +406,38 @@ pub struct Config {
     /// IDE command to open worktrees (default: auto-detect code/cursor)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ide_command: Option<String>,
-    /// Preferred coding agent for dispatch (claude or opencode)
+    /// Preferred coding agent for dispatch (claude, opencode or {"custom": "<name>"})
     #[serde(default)]
     pub coding_agent: CodingAgentType,
+    /// Additional coding agents, selectable next to the built-in ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_agents: Vec<CustomAgentConfig>,
+    /// Named agent profiles, used by `agent_routing` and the dispatch popup
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub agent_profiles: HashMap<String, AgentProfile>,
+    /// Session backend for agents (default: tmux when installed, native otherwise)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_backend: Option<SessionBackendKind>,
+    /// Agent output recording
+    #[serde(default, skip_serializing_if = "is_default_transcript")]
+    pub transcript: TranscriptConfig,
+    /// Contents of dispatch prompts
+    #[serde(default, skip_serializing_if = "is_default_prompt")]
+    pub prompt: PromptConfig,
+    /// Notification channels (default: desktop notifications for every event)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notifiers: Option<Vec<NotifierConfig>>,
+    /// Agents running at once before batch dispatches are queued (default: 3)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_sessions: Option<usize>,
+}
+
+fn is_default_transcript(transcript: &TranscriptConfig) -> bool {
+    *transcript == TranscriptConfig::default()
+}
+
+fn is_default_prompt(prompt: &PromptConfig) -> bool {
+    *prompt == PromptConfig::default()
 }
 
 #[derive(Debug)]
@@ -116,6 +499,116 @@ impl Config {
         self.projects.get(name)
     }
 
+    /// Automatic pull request settings of the projects that enabled them
+    pub fn auto_pr(&self) -> HashMap<String, AutoPrConfig> {
+        self.projects
+            .iter()
+            .filter_map(|(name, project)| Some((name.clone(), project.auto_pr.clone()?)))
+            .collect()
+    }
+
+    /// Verification settings of the projects with verification commands
+    pub fn verify(&self) -> HashMap<String, VerifyConfig> {
+        self.projects
+            .iter()
+            .filter_map(|(name, project)| Some((name.clone(), project.verify.clone()?)))
+            .filter(|(_, verify)| !verify.commands.is_empty())
+            .collect()
+    }
+
+    /// Session policies, by project
+    pub fn policies(&self) -> HashMap<String, SessionPolicy> {
+        self.projects
+            .iter()
+            .filter_map(|(name, project)| Some((name.clone(), project.policy.clone()?)))
+            .collect()
+    }
+
+    /// Configured notifiers, or desktop notifications for every event
+    pub fn notifiers(&self) -> Vec<NotifierConfig> {
+        self.notifiers.clone().unwrap_or_else(|| {
+            vec![NotifierConfig {
+                kind: NotifierKind::Desktop,
+                events: Vec::new(),
+            }]
+        })
+    }
+
+    /// Agents allowed to run at once; queued dispatches wait for a free slot
+    pub fn max_concurrent_sessions(&self) -> usize {
+        self.max_concurrent_sessions.unwrap_or(3).max(1)
+    }
+
+    pub fn get_custom_agent(&self, name: &str) -> Option<&CustomAgentConfig> {
+        self.custom_agents.iter().find(|a| a.name == name)
+    }
+
+    /// Resolve the agent settings for an issue: the default agent, then the project's
+    /// settings, then the profile of the first routing rule matching one of its labels.
+    pub fn resolve_agent_profile(
+        &self,
+        project: &str,
+        labels: &[String],
+        default_agent: &CodingAgentType,
+    ) -> ResolvedProfile {
+        let project = self.get_project(project);
+        let route = project.and_then(|p| {
+            p.agent_routing.iter().find(|r| {
+                labels.iter().any(|l| l.eq_ignore_ascii_case(&r.label))
+                    && self.agent_profiles.contains_key(&r.profile)
+            })
+        });
+
+        let mut resolved = self.project_profile(project, default_agent);
+        if let Some(route) = route {
+            resolved.apply(&self.agent_profiles[&route.profile]);
+            resolved.name = Some(route.profile.clone());
+        }
+        resolved
+    }
+
+    /// Settings that can be picked instead of the resolved ones: every named profile
+    /// over the project's settings, then every agent on its own.
+    pub fn profile_choices(
+        &self,
+        project: &str,
+        default_agent: &CodingAgentType,
+        agents: &[CodingAgentType],
+    ) -> Vec<ResolvedProfile> {
+        let project = self.get_project(project);
+
+        let mut names: Vec<&String> = self.agent_profiles.keys().collect();
+        names.sort();
+        let mut choices: Vec<ResolvedProfile> = names
+            .into_iter()
+            .map(|name| {
+                let mut resolved = self.project_profile(project, default_agent);
+                resolved.apply(&self.agent_profiles[name]);
+                resolved.name = Some(name.clone());
+                resolved
+            })
+            .collect();
+
+        choices.extend(
+            agents
+                .iter()
+                .map(|agent| ResolvedProfile::for_agent(agent.clone())),
+        );
+        choices
+    }
+
+    fn project_profile(
+        &self,
+        project: Option<&ProjectConfig>,
+        default_agent: &CodingAgentType,
+    ) -> ResolvedProfile {
+        let mut resolved = ResolvedProfile::for_agent(default_agent.clone());
+        if let Some(profile) = project.and_then(|p| p.agent.as_ref()) {
+            resolved.apply(profile);
+        }
+        resolved
+    }
+
     pub fn list_projects(&self) -> Vec<&String> {
         self.projects.keys().collect()
     }
@@ -281,5 +774,234 @@ mod tests {
             serde_json::to_string(&CodingAgentType::Opencode).unwrap(),
             "\"opencode\""
         );
+        assert_eq!(
+            serde_json::to_string(&CodingAgentType::Custom("aider".to_string())).unwrap(),
+            "{\"custom\":\"aider\"}"
+        );
+    }
+
+    #[test]
+    fn deserialize_config_with_custom_agents() {
+        let json = r#"{
+            "coding_agent": { "custom": "aider" },
+            "custom_agents": [
+                {
+                    "name": "aider",
+                    "launch": "aider --message-file {prompt_file}",
+                    "idle_patterns": ["^> $"],
+                    "generate": "aider --no-git --message {prompt}",
+                    "env": { "AIDER_MODEL": "sonnet" }
+                },
+                { "name": "codex", "launch": "codex {prompt}" }
+            ],
+            "projects": {}
+        }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.coding_agent, CodingAgentType::Custom("aider".to_string()));
+        assert_eq!(config.custom_agents.len(), 2);
+
+        let aider = config.get_custom_agent("aider").unwrap();
+        assert_eq!(aider.idle_patterns, vec!["^> $"]);
+        assert_eq!(aider.env.get("AIDER_MODEL").map(String::as_str), Some("sonnet"));
+
+        let codex = config.get_custom_agent("codex").unwrap();
+        assert!(codex.generate.is_none());
+        assert!(codex.env.is_empty());
+        assert!(config.get_custom_agent("gemini").is_none());
+    }
+
+    #[test]
+    fn deserialize_config_with_notifiers() {
+        let json = r#"{
+            "notifiers": [
+                { "type": "desktop", "events": ["awaiting", "failed"] },
+                { "type": "webhook", "url": "https://example.com/hook", "events": ["pr_created"] },
+                { "type": "command", "command": "say done" }
+            ],
+            "projects": {}
+        }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        let notifiers = config.notifiers();
+        assert_eq!(notifiers.len(), 3);
+        assert!(notifiers[0].wants(NotifyEvent::Failed));
+        assert!(!notifiers[0].wants(NotifyEvent::Completed));
+        assert_eq!(
+            notifiers[1].kind,
+            NotifierKind::Webhook {
+                url: "https://example.com/hook".to_string()
+            }
+        );
+        assert!(notifiers[2].wants(NotifyEvent::PrCreated));
+
+        // Without notifiers, every event goes to the desktop
+        let config: Config = serde_json::from_str(r#"{ "projects": {} }"#).unwrap();
+        assert_eq!(config.notifiers()[0].kind, NotifierKind::Desktop);
+        assert!(config.notifiers()[0].wants(NotifyEvent::Awaiting));
+    }
+
+    #[test]
+    fn deserialize_config_with_prompt() {
+        let json = r#"{
+            "prompt": { "comments": false, "bot_authors": ["ci-helper"], "max_chars": 8000 }
+        }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert!(!config.prompt.comments);
+        assert_eq!(config.prompt.bot_authors, vec!["ci-helper"]);
+        assert_eq!(config.prompt.max_chars, 8000);
+        assert!(config.prompt.labels);
+        assert_eq!(config.prompt.guidance_files, vec!["AGENTS.md", "CONTRIBUTING.md"]);
+
+        let config: Config = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.prompt, PromptConfig::default());
+    }
+
+    #[test]
+    fn deserialize_project_with_auto_pr() {
+        let json = r#"{
+            "projects": {
+                "auto": {
+                    "owner": "o", "repo": "r", "labels": [], "auto_pr": { "idle_minutes": 10 }
+                },
+                "manual": { "owner": "o", "repo": "r", "labels": [] }
+            }
+        }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        let auto_pr = config.auto_pr();
+        assert_eq!(auto_pr.len(), 1);
+        let auto = &auto_pr["auto"];
+        assert!(auto.on_complete);
+        assert!(auto.draft);
+        assert_eq!(auto.idle_minutes, Some(10));
+    }
+
+    #[test]
+    fn deserialize_project_with_verify() {
+        let json = r#"{
+            "projects": {
+                "rust": {
+                    "owner": "o", "repo": "r", "labels": [],
+                    "verify": { "commands": ["cargo test"], "feedback_rounds": 2 }
+                },
+                "empty": { "owner": "o", "repo": "r", "labels": [], "verify": {} }
+            }
+        }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        let verify = config.verify();
+        assert_eq!(verify.len(), 1);
+        let rust = &verify["rust"];
+        assert_eq!(rust.commands, vec!["cargo test"]);
+        assert!(rust.on_idle && rust.on_complete);
+        assert_eq!(rust.tail_lines, 40);
+        assert_eq!(rust.feedback_rounds, 2);
+        assert_eq!(rust.timeout_secs, 600);
+    }
+
+    #[test]
+    fn deserialize_project_with_policy() {
+        let json = r#"{
+            "owner": "o", "repo": "r", "labels": [],
+            "policy": {
+                "max_duration_minutes": 240, "max_awaiting_minutes": 60, "on_awaiting": "kill"
+            }
+        }"#;
+
+        let project: ProjectConfig = serde_json::from_str(json).unwrap();
+        let policy = project.policy.unwrap();
+        assert_eq!(policy.max_duration_minutes, Some(240));
+        assert_eq!(policy.max_awaiting_minutes, Some(60));
+        assert_eq!(policy.on_awaiting, AwaitingAction::Kill);
+        assert_eq!(policy.max_diff_lines, None);
+        assert_eq!(SessionPolicy::default().on_awaiting, AwaitingAction::Remind);
+    }
+
+    #[test]
+    fn deserialize_project_with_worktree_setup() {
+        let json = r#"{
+            "owner": "o", "repo": "r", "labels": [],
+            "worktree_setup": { "copy": [".env*"], "commands": ["npm ci"] }
+        }"#;
+
+        let project: ProjectConfig = serde_json::from_str(json).unwrap();
+        let setup = project.worktree_setup.unwrap();
+        assert_eq!(setup.copy, vec![".env*"]);
+        assert!(setup.symlink.is_empty());
+        assert_eq!(setup.commands, vec!["npm ci"]);
+        assert_eq!(setup.timeout_secs, 600);
+    }
+
+    fn routing_config() -> Config {
+        serde_json::from_str(
+            r#"{
+                "agent_profiles": {
+                    "cheap": { "model": "haiku" },
+                    "strict": { "agent": "claude", "flags": ["--permission-mode", "plan"] }
+                },
+                "projects": {
+                    "test": {
+                        "owner": "a", "repo": "r", "labels": [],
+                        "agent": { "agent": "opencode", "flags": ["--verbose"] },
+                        "agent_routing": [
+                            { "label": "security", "profile": "strict" },
+                            { "label": "docs", "profile": "cheap" },
+                            { "label": "bug", "profile": "unknown" }
+                        ]
+                    }
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn resolve_agent_profile_applies_project_then_route() {
+        let config = routing_config();
+        let labels = |l: &[&str]| l.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let plain = config.resolve_agent_profile("test", &labels(&["feature"]), &CodingAgentType::Claude);
+        assert_eq!(plain.name, None);
+        assert_eq!(plain.agent, CodingAgentType::Opencode);
+        assert_eq!(plain.cli_args(), vec!["--verbose"]);
+
+        let docs = config.resolve_agent_profile("test", &labels(&["Docs"]), &CodingAgentType::Claude);
+        assert_eq!(docs.name.as_deref(), Some("cheap"));
+        assert_eq!(docs.agent, CodingAgentType::Opencode);
+        assert_eq!(docs.cli_args(), vec!["--model", "haiku", "--verbose"]);
+        assert_eq!(docs.summary(), "cheap: Opencode · haiku · --verbose");
+
+        // First matching rule wins; rules pointing to unknown profiles are ignored
+        let both = config.resolve_agent_profile(
+            "test",
+            &labels(&["bug", "docs", "security"]),
+            &CodingAgentType::Claude,
+        );
+        assert_eq!(both.name.as_deref(), Some("strict"));
+        assert_eq!(both.agent, CodingAgentType::Claude);
+        assert_eq!(both.flags, vec!["--verbose", "--permission-mode", "plan"]);
+
+        let other = config.resolve_agent_profile("other", &labels(&["docs"]), &CodingAgentType::Claude);
+        assert_eq!(other, ResolvedProfile::for_agent(CodingAgentType::Claude));
+    }
+
+    #[test]
+    fn profile_choices_lists_profiles_then_agents() {
+        let config = routing_config();
+        let agents = [CodingAgentType::Claude, CodingAgentType::Opencode];
+        let choices = config.profile_choices("test", &CodingAgentType::Claude, &agents);
+
+        let summaries: Vec<String> = choices.iter().map(|c| c.summary()).collect();
+        assert_eq!(
+            summaries,
+            vec![
+                "cheap: Opencode · haiku · --verbose",
+                "strict: Claude Code · --verbose --permission-mode plan",
+                "Claude Code",
+                "Opencode",
+            ]
+        );
     }
 }
@@ -1,13 +1,13 @@
-//! Embedded terminal for running tmux sessions within the TUI.
+//! Embedded terminal for running agent sessions within the TUI.
 
-use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use crate::agents::{session_backend, Attachment};
 use std::io::{Read, Write};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use vt100::Parser;
 
-/// Embedded terminal that wraps a tmux session.
+/// Embedded terminal that wraps an agent session.
 pub struct EmbeddedTerminal {
     /// Terminal parser that maintains the screen buffer
     parser: Arc<Mutex<Parser>>,
@@ -15,32 +15,17 @@ pub struct EmbeddedTerminal {
     input_tx: Sender<Vec<u8>>,
     /// Whether the terminal is still running
     running: Arc<Mutex<bool>>,
-    /// Current tmux session name
+    /// Current session name
     pub session_name: String,
 }
 
 impl EmbeddedTerminal {
-    /// Create a new embedded terminal attached to a tmux session.
+    /// Create a new embedded terminal attached to an agent session.
     pub fn new(session_name: &str, rows: u16, cols: u16) -> Result<Self, String> {
-        let pty_system = native_pty_system();
-
-        let pair = pty_system
-            .openpty(PtySize {
-                rows,
-                cols,
-                pixel_width: 0,
-                pixel_height: 0,
-            })
-            .map_err(|e| format!("Failed to open PTY: {}", e))?;
-
-        // Build the tmux attach command
-        let mut cmd = CommandBuilder::new("tmux");
-        cmd.args(["attach", "-t", session_name]);
-
-        let _child = pair
-            .slave
-            .spawn_command(cmd)
-            .map_err(|e| format!("Failed to spawn tmux: {}", e))?;
+        let Attachment {
+            mut reader,
+            mut writer,
+        } = session_backend().attach(session_name, rows, cols)?;
 
         // Create the terminal parser
         let parser = Arc::new(Mutex::new(Parser::new(rows, cols, 0)));
@@ -53,17 +38,6 @@ impl EmbeddedTerminal {
         let running = Arc::new(Mutex::new(true));
         let running_clone = Arc::clone(&running);
 
-        // Get reader and writer from PTY
-        let mut reader = pair
-            .master
-            .try_clone_reader()
-            .map_err(|e| format!("Failed to clone reader: {}", e))?;
-
-        let mut writer = pair
-            .master
-            .take_writer()
-            .map_err(|e| format!("Failed to take writer: {}", e))?;
-
         // Spawn reader thread
         let running_reader = Arc::clone(&running);
         thread::spawn(move || {
@@ -71,7 +45,7 @@ impl EmbeddedTerminal {
             loop {
                 match reader.read(&mut buf) {
                     Ok(0) => {
-                        // EOF - PTY closed
+                        // EOF - session closed
                         *running_reader.lock().unwrap() = false;
                         break;
                     }
@@ -214,26 +188,7 @@ impl EmbeddedTerminal {
     /// Get the current screen contents as lines of styled spans.
     pub fn get_screen(&self) -> Vec<Vec<StyledCell>> {
         let parser = self.parser.lock().unwrap();
-        let screen = parser.screen();
-        let mut lines = Vec::new();
-
-        for row in 0..screen.size().0 {
-            let mut line = Vec::new();
-            for col in 0..screen.size().1 {
-                let cell = screen.cell(row, col).unwrap();
-                line.push(StyledCell {
-                    content: cell.contents().to_string(),
-                    fg: convert_color(cell.fgcolor()),
-                    bg: convert_color(cell.bgcolor()),
-                    bold: cell.bold(),
-                    underline: cell.underline(),
-                    inverse: cell.inverse(),
-                });
-            }
-            lines.push(line);
-        }
-
-        lines
+        screen_cells(parser.screen())
     }
 
     /// Resize the terminal.
@@ -244,6 +199,30 @@ impl EmbeddedTerminal {
     }
 }
 
+/// Cells of a vt100 screen, row by row.
+pub fn screen_cells(screen: &vt100::Screen) -> Vec<Vec<StyledCell>> {
+    let (rows, cols) = screen.size();
+    let mut lines = Vec::new();
+
+    for row in 0..rows {
+        let mut line = Vec::new();
+        for col in 0..cols {
+            let cell = screen.cell(row, col).unwrap();
+            line.push(StyledCell {
+                content: cell.contents().to_string(),
+                fg: convert_color(cell.fgcolor()),
+                bg: convert_color(cell.bgcolor()),
+                bold: cell.bold(),
+                underline: cell.underline(),
+                inverse: cell.inverse(),
+            });
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
 /// A styled cell from the terminal.
 #[derive(Clone)]
 pub struct StyledCell {
@@ -2,12 +2,14 @@ use crate::auth;
 use crate::issues::IssueContent;
 use crate::list::IssueState;
 use octocrab::Octocrab;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct GitHubConfig {
     token: String,
     pub owner: String,
     pub repo: String,
+    api_base: String,
 }
 
 #[derive(Debug, Clone)]
@@ -69,11 +71,83 @@ pub struct PullRequestDetail {
     pub author: String,
     pub draft: bool,
     pub head_ref: String,
+    /// Commit at the head of the PR branch
+    pub head_sha: String,
     pub base_ref: String,
     pub mergeable: Option<bool>,
+    pub requested_reviewers: Vec<String>,
+    /// Head repository as "owner/repo" (None if the fork was deleted)
+    pub head_repo: Option<String>,
+    pub head_clone_url: Option<String>,
+    pub maintainer_can_modify: bool,
     pub comments: Vec<CommentInfo>,
 }
 
+/// Check run that did not pass on a commit
+#[derive(Debug, Clone)]
+pub struct FailedCheck {
+    pub id: u64,
+    pub name: String,
+    pub conclusion: String,
+    pub html_url: String,
+    /// GitHub Actions jobs share their id with the check run, so their logs can be downloaded
+    pub is_actions_job: bool,
+}
+
+/// Unresolved review thread on a pull request
+#[derive(Debug, Clone)]
+pub struct ReviewThread {
+    /// Database id of the first comment, used to reply in the thread
+    pub comment_id: u64,
+    pub path: String,
+    pub line: Option<u64>,
+    pub comments: Vec<CommentInfo>,
+}
+
+/// Inline comment in a pull request review
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ReviewComment {
+    pub path: String,
+    /// Line in the new version of the file
+    pub line: u64,
+    pub body: String,
+}
+
+/// Verdict of a pull request review
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewEvent {
+    Comment,
+    RequestChanges,
+    Approve,
+}
+
+impl ReviewEvent {
+    pub fn as_api_str(&self) -> &'static str {
+        match self {
+            ReviewEvent::Comment => "COMMENT",
+            ReviewEvent::RequestChanges => "REQUEST_CHANGES",
+            ReviewEvent::Approve => "APPROVE",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReviewEvent::Comment => "Comment",
+            ReviewEvent::RequestChanges => "Request changes",
+            ReviewEvent::Approve => "Approve",
+        }
+    }
+
+    /// Next verdict when cycling in the UI
+    pub fn next(&self) -> Self {
+        match self {
+            ReviewEvent::Comment => ReviewEvent::RequestChanges,
+            ReviewEvent::RequestChanges => ReviewEvent::Approve,
+            ReviewEvent::Approve => ReviewEvent::Comment,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum GitHubError {
     NotAuthenticated,
@@ -95,7 +169,18 @@ impl std::error::Error for GitHubError {}
 
 impl GitHubConfig {
     pub fn new(owner: String, repo: String, token: String) -> Self {
-        Self { token, owner, repo }
+        Self {
+            token,
+            owner,
+            repo,
+            api_base: "https://api.github.com".to_string(),
+        }
+    }
+
+    /// Point the client at a different API base URL (GitHub Enterprise, tests).
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.api_base = base_url.to_string();
+        self
     }
 
     pub fn from_keyring(owner: String, repo: String) -> Result<Self, GitHubError> {
@@ -104,7 +189,7 @@ impl GitHubConfig {
     }
 
     fn get_client(&self) -> Result<Octocrab, GitHubError> {
-        self.get_client_with_base_url("https://api.github.com")
+        self.get_client_with_base_url(&self.api_base)
     }
 
     fn get_client_with_base_url(&self, base_url: &str) -> Result<Octocrab, GitHubError> {
@@ -235,6 +320,25 @@ impl GitHubConfig {
         })
     }
 
+    /// Number of the issue this issue is a sub-issue of, if any
+    pub async fn get_parent_issue(&self, number: u64) -> Result<Option<u64>, GitHubError> {
+        let client = self.get_client()?;
+
+        match client
+            .get::<serde_json::Value, _, _>(
+                format!("/repos/{}/{}/issues/{}/parent", self.owner, self.repo, number),
+                None::<&()>,
+            )
+            .await
+        {
+            Ok(parent) => Ok(parent["number"].as_u64()),
+            Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+                Ok(None)
+            }
+            Err(e) => Err(Self::map_api_error(e)),
+        }
+    }
+
     pub async fn add_comment(
         &self,
         issue_number: u64,
@@ -523,6 +627,8 @@ impl GitHubConfig {
             })
             .collect();
 
+        let head_repo = pr.head.repo.as_ref();
+
         Ok(PullRequestDetail {
             number: pr.number,
             title: pr.title.unwrap_or_default(),
@@ -533,9 +639,19 @@ impl GitHubConfig {
             assignees: pr.assignees.iter().flatten().map(|u| u.login.clone()).collect(),
             author: pr.user.map(|u| u.login).unwrap_or_default(),
             draft: pr.draft.unwrap_or(false),
-            head_ref: pr.head.ref_field,
+            head_repo: head_repo.and_then(|r| r.full_name.clone()),
+            head_clone_url: head_repo.and_then(|r| r.clone_url.as_ref().map(|u| u.to_string())),
+            maintainer_can_modify: pr.maintainer_can_modify,
+            head_ref: pr.head.ref_field.clone(),
+            head_sha: pr.head.sha.clone(),
             base_ref: pr.base.ref_field,
             mergeable: pr.mergeable,
+            requested_reviewers: pr
+                .requested_reviewers
+                .iter()
+                .flatten()
+                .map(|u| u.login.clone())
+                .collect(),
             comments,
         })
     }
@@ -559,6 +675,450 @@ impl GitHubConfig {
         Ok(())
     }
 
+    /// Open a pull request from `head` into `base` and return (html_url, PullRequestSummary).
+    /// The head branch must already be pushed.
+    pub async fn create_pull_request(
+        &self,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: &str,
+        draft: bool,
+    ) -> Result<(String, PullRequestSummary), GitHubError> {
+        let client = self.get_client()?;
+
+        let pr: serde_json::Value = client
+            .post(
+                format!("/repos/{}/{}/pulls", self.owner, self.repo),
+                Some(&serde_json::json!({
+                    "title": title,
+                    "head": head,
+                    "base": base,
+                    "body": body,
+                    "draft": draft,
+                })),
+            )
+            .await
+            .map_err(Self::map_api_error)?;
+
+        let number = pr["number"]
+            .as_u64()
+            .ok_or_else(|| GitHubError::ApiError("Response has no PR number".to_string()))?;
+        let html_url = pr["html_url"].as_str().unwrap_or_default().to_string();
+
+        let summary = PullRequestSummary {
+            number,
+            title: pr["title"].as_str().unwrap_or(title).to_string(),
+            html_url: html_url.clone(),
+            labels: vec![],
+            state: "Open".to_string(),
+            assignees: vec![],
+            author: pr["user"]["login"].as_str().unwrap_or_default().to_string(),
+            draft: pr["draft"].as_bool().unwrap_or(draft),
+            head_ref: head.to_string(),
+            base_ref: base.to_string(),
+            mergeable: None,
+            review_decision: None,
+        };
+
+        Ok((html_url, summary))
+    }
+
+    /// Submit a review with a summary and inline comments on the new side of the diff
+    pub async fn submit_review(
+        &self,
+        number: u64,
+        body: &str,
+        event: ReviewEvent,
+        comments: &[ReviewComment],
+    ) -> Result<(), GitHubError> {
+        let client = self.get_client()?;
+
+        let comments: Vec<serde_json::Value> = comments
+            .iter()
+            .map(|c| {
+                serde_json::json!({
+                    "path": c.path,
+                    "line": c.line,
+                    "side": "RIGHT",
+                    "body": c.body,
+                })
+            })
+            .collect();
+
+        let _: serde_json::Value = client
+            .post(
+                format!("/repos/{}/{}/pulls/{}/reviews", self.owner, self.repo, number),
+                Some(&serde_json::json!({
+                    "body": body,
+                    "event": event.as_api_str(),
+                    "comments": comments,
+                })),
+            )
+            .await
+            .map_err(Self::map_api_error)?;
+
+        Ok(())
+    }
+
+    /// List review threads that are not resolved yet.
+    /// Threads are only exposed through GraphQL.
+    pub async fn list_unresolved_review_threads(
+        &self,
+        number: u64,
+    ) -> Result<Vec<ReviewThread>, GitHubError> {
+        let client = self.get_client()?;
+
+        let query = "query($owner: String!, $repo: String!, $number: Int!) { \
+            repository(owner: $owner, name: $repo) { pullRequest(number: $number) { \
+            reviewThreads(first: 100) { nodes { isResolved path line \
+            comments(first: 50) { nodes { databaseId body createdAt author { login } } } } } } } }";
+
+        let response: serde_json::Value = client
+            .graphql(&serde_json::json!({
+                "query": query,
+                "variables": { "owner": self.owner, "repo": self.repo, "number": number },
+            }))
+            .await
+            .map_err(Self::map_api_error)?;
+
+        if let Some(message) = response["errors"][0]["message"].as_str() {
+            return Err(GitHubError::ApiError(message.to_string()));
+        }
+
+        let nodes = response["data"]["repository"]["pullRequest"]["reviewThreads"]["nodes"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+
+        let threads = nodes
+            .iter()
+            .filter(|t| !t["isResolved"].as_bool().unwrap_or(false))
+            .filter_map(|t| {
+                let comments: Vec<CommentInfo> = t["comments"]["nodes"]
+                    .as_array()?
+                    .iter()
+                    .map(|c| CommentInfo {
+                        id: c["databaseId"].as_u64().unwrap_or_default(),
+                        author: c["author"]["login"].as_str().unwrap_or("ghost").to_string(),
+                        body: c["body"].as_str().unwrap_or_default().to_string(),
+                        created_at: c["createdAt"].as_str().unwrap_or_default().to_string(),
+                    })
+                    .collect();
+
+                Some(ReviewThread {
+                    comment_id: comments.first()?.id,
+                    path: t["path"].as_str().unwrap_or_default().to_string(),
+                    line: t["line"].as_u64(),
+                    comments,
+                })
+            })
+            .collect();
+
+        Ok(threads)
+    }
+
+    /// List the latest check runs that failed on a commit.
+    pub async fn list_failed_checks(&self, sha: &str) -> Result<Vec<FailedCheck>, GitHubError> {
+        let client = self.get_client()?;
+
+        let response: serde_json::Value = client
+            .get(
+                format!("/repos/{}/{}/commits/{}/check-runs", self.owner, self.repo, sha),
+                Some(&serde_json::json!({ "filter": "latest", "per_page": 100 })),
+            )
+            .await
+            .map_err(Self::map_api_error)?;
+
+        let checks = response["check_runs"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|run| {
+                let conclusion = run["conclusion"].as_str()?;
+                if !matches!(conclusion, "failure" | "timed_out" | "startup_failure") {
+                    return None;
+                }
+                Some(FailedCheck {
+                    id: run["id"].as_u64()?,
+                    name: run["name"].as_str().unwrap_or_default().to_string(),
+                    conclusion: conclusion.to_string(),
+                    html_url: run["html_url"].as_str().unwrap_or_default().to_string(),
+                    is_actions_job: run["app"]["slug"].as_str() == Some("github-actions"),
+                })
+            })
+            .collect();
+
+        Ok(checks)
+    }
+
+    /// Download the plain-text log of a GitHub Actions job.
+    pub async fn download_job_log(&self, job_id: u64) -> Result<String, GitHubError> {
+        let client = self.get_client()?;
+
+        // The API answers with a redirect to the log storage, which octocrab follows
+        let response = client
+            ._get(format!(
+                "/repos/{}/{}/actions/jobs/{}/logs",
+                self.owner, self.repo, job_id
+            ))
+            .await
+            .map_err(Self::map_api_error)?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(GitHubError::ApiError(format!(
+                "Failed to download log for job {}: HTTP {}",
+                job_id, status
+            )));
+        }
+
+        client
+            .body_to_string(response)
+            .await
+            .map_err(Self::map_api_error)
+    }
+
+    /// Reply to a review comment thread
+    pub async fn reply_to_review_comment(
+        &self,
+        number: u64,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<(), GitHubError> {
+        let client = self.get_client()?;
+
+        let _: serde_json::Value = client
+            .post(
+                format!(
+                    "/repos/{}/{}/pulls/{}/comments/{}/replies",
+                    self.owner, self.repo, number, comment_id
+                ),
+                Some(&serde_json::json!({ "body": body })),
+            )
+            .await
+            .map_err(Self::map_api_error)?;
+
+        Ok(())
+    }
+
+    /// Mark a draft pull request as ready for review, or convert it back to draft.
+    /// The REST API cannot change draft state, so this goes through GraphQL.
+    pub async fn set_pull_request_draft(&self, number: u64, draft: bool) -> Result<(), GitHubError> {
+        let client = self.get_client()?;
+
+        let pr: serde_json::Value = client
+            .get(
+                format!("/repos/{}/{}/pulls/{}", self.owner, self.repo, number),
+                None::<&()>,
+            )
+            .await
+            .map_err(Self::map_api_error)?;
+
+        let node_id = pr["node_id"]
+            .as_str()
+            .ok_or_else(|| GitHubError::ApiError("Pull request has no node_id".to_string()))?;
+
+        let mutation = if draft {
+            "mutation($id: ID!) { convertPullRequestToDraft(input: {pullRequestId: $id}) { pullRequest { isDraft } } }"
+        } else {
+            "mutation($id: ID!) { markPullRequestReadyForReview(input: {pullRequestId: $id}) { pullRequest { isDraft } } }"
+        };
+
+        let response: serde_json::Value = client
+            .graphql(&serde_json::json!({
+                "query": mutation,
+                "variables": { "id": node_id },
+            }))
+            .await
+            .map_err(Self::map_api_error)?;
+
+        if let Some(message) = response["errors"][0]["message"].as_str() {
+            return Err(GitHubError::ApiError(message.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Request reviews from users on a pull request
+    pub async fn request_reviewers(
+        &self,
+        number: u64,
+        reviewers: &[String],
+    ) -> Result<(), GitHubError> {
+        let client = self.get_client()?;
+
+        let _: serde_json::Value = client
+            .post(
+                format!(
+                    "/repos/{}/{}/pulls/{}/requested_reviewers",
+                    self.owner, self.repo, number
+                ),
+                Some(&serde_json::json!({ "reviewers": reviewers })),
+            )
+            .await
+            .map_err(Self::map_api_error)?;
+
+        Ok(())
+    }
+
+    /// Remove requested reviewers from a pull request
+    pub async fn remove_reviewers(
+        &self,
+        number: u64,
+        reviewers: &[String],
+    ) -> Result<(), GitHubError> {
+        let client = self.get_client()?;
+
+        let _: serde_json::Value = client
+            .delete(
+                format!(
+                    "/repos/{}/{}/pulls/{}/requested_reviewers",
+                    self.owner, self.repo, number
+                ),
+                Some(&serde_json::json!({ "reviewers": reviewers })),
+            )
+            .await
+            .map_err(Self::map_api_error)?;
+
+        Ok(())
+    }
+
+    /// Edit the title, body and/or base branch of a pull request.
+    /// Fields left as `None` are not changed.
+    pub async fn update_pull_request(
+        &self,
+        number: u64,
+        title: Option<&str>,
+        body: Option<&str>,
+        base: Option<&str>,
+    ) -> Result<(), GitHubError> {
+        let mut payload = serde_json::Map::new();
+        if let Some(title) = title {
+            payload.insert("title".to_string(), title.into());
+        }
+        if let Some(body) = body {
+            payload.insert("body".to_string(), body.into());
+        }
+        if let Some(base) = base {
+            payload.insert("base".to_string(), base.into());
+        }
+
+        self.patch_pull_request(number, &payload).await
+    }
+
+    pub async fn close_pull_request(&self, number: u64) -> Result<(), GitHubError> {
+        let mut payload = serde_json::Map::new();
+        payload.insert("state".to_string(), "closed".into());
+        self.patch_pull_request(number, &payload).await
+    }
+
+    pub async fn reopen_pull_request(&self, number: u64) -> Result<(), GitHubError> {
+        let mut payload = serde_json::Map::new();
+        payload.insert("state".to_string(), "open".into());
+        self.patch_pull_request(number, &payload).await
+    }
+
+    async fn patch_pull_request(
+        &self,
+        number: u64,
+        payload: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(), GitHubError> {
+        let client = self.get_client()?;
+
+        let _: serde_json::Value = client
+            .patch(
+                format!("/repos/{}/{}/pulls/{}", self.owner, self.repo, number),
+                Some(payload),
+            )
+            .await
+            .map_err(Self::map_api_error)?;
+
+        Ok(())
+    }
+
+    /// Add labels to an issue or pull request
+    pub async fn add_labels(&self, number: u64, labels: &[String]) -> Result<(), GitHubError> {
+        let client = self.get_client()?;
+
+        let _: serde_json::Value = client
+            .post(
+                format!("/repos/{}/{}/issues/{}/labels", self.owner, self.repo, number),
+                Some(&serde_json::json!({ "labels": labels })),
+            )
+            .await
+            .map_err(Self::map_api_error)?;
+
+        Ok(())
+    }
+
+    /// Remove a label from an issue or pull request
+    pub async fn remove_label(&self, number: u64, label: &str) -> Result<(), GitHubError> {
+        let client = self.get_client()?;
+
+        let _: serde_json::Value = client
+            .delete(
+                format!(
+                    "/repos/{}/{}/issues/{}/labels/{}",
+                    self.owner,
+                    self.repo,
+                    number,
+                    encode_path_segment(label)
+                ),
+                None::<&()>,
+            )
+            .await
+            .map_err(Self::map_api_error)?;
+
+        Ok(())
+    }
+
+    /// Fetch a file from the repository's default branch.
+    /// Returns `None` if the file does not exist.
+    pub async fn get_file_content(&self, file_path: &str) -> Result<Option<String>, GitHubError> {
+        let client = self.get_client()?;
+
+        match client
+            .repos(&self.owner, &self.repo)
+            .get_content()
+            .path(file_path)
+            .send()
+            .await
+        {
+            Ok(mut content) => Ok(content
+                .take_items()
+                .into_iter()
+                .next()
+                .and_then(|item| item.decoded_content())),
+            Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+                Ok(None)
+            }
+            Err(e) => Err(Self::map_api_error(e)),
+        }
+    }
+
+    /// List the users named in the repository's CODEOWNERS file (teams are skipped)
+    pub async fn list_code_owners(&self) -> Result<Vec<String>, GitHubError> {
+        for location in [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"] {
+            if let Some(content) = self.get_file_content(location).await? {
+                return Ok(parse_codeowners(&content));
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// Suggest reviewers: code owners first, then contributors.
+    pub async fn suggest_reviewers(&self) -> Result<Vec<String>, GitHubError> {
+        let mut suggestions = self.list_code_owners().await.unwrap_or_default();
+        for contributor in self.list_contributors().await? {
+            if !suggestions.contains(&contributor) {
+                suggestions.push(contributor);
+            }
+        }
+        Ok(suggestions)
+    }
+
     /// Search pull requests using GitHub Search API with optional filters.
     /// This allows filtering by author and finding older PRs that wouldn't appear
     /// in the regular list API's first pages.
@@ -714,6 +1274,23 @@ impl GitHubConfig {
     }
 
     fn map_api_error(e: octocrab::Error) -> GitHubError {
+        // Prefer GitHub's own message (plus the first validation error) over octocrab's
+        // generic Display, which is just "GitHub".
+        if let octocrab::Error::GitHub { source, .. } = &e {
+            if source.status_code.as_u16() == 401 {
+                return GitHubError::TokenExpired;
+            }
+            let detail = source
+                .errors
+                .as_ref()
+                .and_then(|errors| errors.first())
+                .and_then(|err| err["message"].as_str());
+            return match detail {
+                Some(detail) => GitHubError::ApiError(format!("{} ({})", source.message, detail)),
+                None => GitHubError::ApiError(source.message.clone()),
+            };
+        }
+
         let msg = e.to_string();
         if msg.contains("401") || msg.to_lowercase().contains("unauthorized") {
             GitHubError::TokenExpired
@@ -732,10 +1309,49 @@ impl GitHubConfig {
     }
 }
 
+/// Percent-encode a URL path segment, leaving only unreserved characters as they are,
+/// so names like `area/parser` or `needs: #triage` stay within one segment.
+fn encode_path_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Extract user owners from a CODEOWNERS file, in order of first appearance.
+/// Team owners (`@org/team`) and email owners are ignored.
+pub fn parse_codeowners(content: &str) -> Vec<String> {
+    let mut owners: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        // First token is the path pattern, the rest are owners
+        for owner in line.split_whitespace().skip(1) {
+            if let Some(login) = owner.strip_prefix('@')
+                && !login.contains('/')
+                && !owners.iter().any(|o| o == login)
+            {
+                owners.push(login.to_string());
+            }
+        }
+    }
+
+    owners
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{method, path, query_param};
+    use wiremock::matchers::{body_json, method, path, query_param};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[test]
@@ -1306,4 +1922,523 @@ mod tests {
         assert_eq!(unique[0].number, 1);
         assert_eq!(unique[1].number, 2);
     }
+
+    fn test_github(server: &MockServer) -> GitHubConfig {
+        GitHubConfig::new("owner".to_string(), "repo".to_string(), "token".to_string())
+            .with_base_url(&server.uri())
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn set_pull_request_ready_for_review() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/pulls/7"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "number": 7, "node_id": "PR_node7" })),
+            )
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "markPullRequestReadyForReview": { "pullRequest": { "isDraft": false } } }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = test_github(&server).set_pull_request_draft(7, false).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn set_pull_request_draft_graphql_error() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/pulls/7"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "number": 7, "node_id": "PR_node7" })),
+            )
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": null,
+                "errors": [{ "message": "Pull request is already a draft" }]
+            })))
+            .mount(&server)
+            .await;
+
+        let result = test_github(&server).set_pull_request_draft(7, true).await;
+        assert!(result.unwrap_err().to_string().contains("already a draft"));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn get_parent_issue_of_sub_issue() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/issues/12/parent"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "number": 3, "title": "Epic" })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/issues/3/parent"))
+            .respond_with(
+                ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                    "message": "Not Found",
+                    "documentation_url": "https://docs.github.com/rest"
+                })),
+            )
+            .mount(&server)
+            .await;
+
+        let github = test_github(&server);
+        assert_eq!(github.get_parent_issue(12).await.unwrap(), Some(3));
+        assert_eq!(github.get_parent_issue(3).await.unwrap(), None);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn request_reviewers_sends_logins() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls/7/requested_reviewers"))
+            .and(body_json(serde_json::json!({ "reviewers": ["alice", "bob"] })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({ "number": 7 })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = test_github(&server)
+            .request_reviewers(7, &["alice".to_string(), "bob".to_string()])
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn remove_reviewers_sends_delete() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/repos/owner/repo/pulls/7/requested_reviewers"))
+            .and(body_json(serde_json::json!({ "reviewers": ["alice"] })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "number": 7 })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = test_github(&server)
+            .remove_reviewers(7, &["alice".to_string()])
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn update_pull_request_only_sends_changed_fields() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("PATCH"))
+            .and(path("/repos/owner/repo/pulls/7"))
+            .and(body_json(serde_json::json!({ "title": "New title", "base": "develop" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "number": 7 })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = test_github(&server)
+            .update_pull_request(7, Some("New title"), None, Some("develop"))
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn close_and_reopen_pull_request() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("PATCH"))
+            .and(path("/repos/owner/repo/pulls/7"))
+            .and(body_json(serde_json::json!({ "state": "closed" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "number": 7 })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("PATCH"))
+            .and(path("/repos/owner/repo/pulls/7"))
+            .and(body_json(serde_json::json!({ "state": "open" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "number": 7 })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let github = test_github(&server);
+        assert!(github.close_pull_request(7).await.is_ok());
+        assert!(github.reopen_pull_request(7).await.is_ok());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn create_pull_request_sends_draft_and_returns_summary() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls"))
+            .and(body_json(serde_json::json!({
+                "title": "Fix #12: crash on start",
+                "head": "issue-12",
+                "base": "main",
+                "body": "Closes #12",
+                "draft": true,
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "number": 34,
+                "title": "Fix #12: crash on start",
+                "html_url": "https://github.com/owner/repo/pull/34",
+                "draft": true,
+                "user": { "login": "octocat" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let (url, summary) = test_github(&server)
+            .create_pull_request("issue-12", "main", "Fix #12: crash on start", "Closes #12", true)
+            .await
+            .unwrap();
+
+        assert_eq!(url, "https://github.com/owner/repo/pull/34");
+        assert_eq!(summary.number, 34);
+        assert!(summary.draft);
+        assert_eq!(summary.author, "octocat");
+        assert_eq!(summary.head_ref, "issue-12");
+        assert_eq!(summary.base_ref, "main");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn create_pull_request_surfaces_validation_error() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls"))
+            .respond_with(ResponseTemplate::new(422).set_body_json(serde_json::json!({
+                "message": "Validation Failed",
+                "errors": [
+                    { "resource": "PullRequest", "code": "custom", "message": "A pull request already exists for owner:issue-12." }
+                ],
+                "documentation_url": "https://docs.github.com/rest/pulls/pulls#create-a-pull-request"
+            })))
+            .mount(&server)
+            .await;
+
+        let err = test_github(&server)
+            .create_pull_request("issue-12", "main", "title", "body", false)
+            .await
+            .unwrap_err();
+
+        let msg = err.to_string();
+        assert!(msg.contains("Validation Failed"));
+        assert!(msg.contains("A pull request already exists"));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn unauthorized_maps_to_token_expired() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "message": "Bad credentials",
+                "documentation_url": "https://docs.github.com/rest"
+            })))
+            .mount(&server)
+            .await;
+
+        let err = test_github(&server)
+            .create_pull_request("issue-12", "main", "title", "body", false)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, GitHubError::TokenExpired));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn submit_review_posts_inline_comments() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls/9/reviews"))
+            .and(body_json(serde_json::json!({
+                "body": "Two issues",
+                "event": "REQUEST_CHANGES",
+                "comments": [
+                    { "path": "src/cache.rs", "line": 12, "side": "RIGHT", "body": "Off by one" }
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": 1 })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let comments = vec![ReviewComment {
+            path: "src/cache.rs".to_string(),
+            line: 12,
+            body: "Off by one".to_string(),
+        }];
+        test_github(&server)
+            .submit_review(9, "Two issues", ReviewEvent::RequestChanges, &comments)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn list_unresolved_review_threads_skips_resolved() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "repository": { "pullRequest": { "reviewThreads": { "nodes": [
+                    {
+                        "isResolved": false, "path": "src/lib.rs", "line": 10,
+                        "comments": { "nodes": [
+                            { "databaseId": 501, "body": "Handle the error", "createdAt": "2024-01-01T00:00:00Z", "author": { "login": "alice" } },
+                            { "databaseId": 502, "body": "Agreed", "createdAt": "2024-01-02T00:00:00Z", "author": null }
+                        ] }
+                    },
+                    {
+                        "isResolved": true, "path": "src/main.rs", "line": 3,
+                        "comments": { "nodes": [
+                            { "databaseId": 600, "body": "Done", "createdAt": "2024-01-01T00:00:00Z", "author": { "login": "bob" } }
+                        ] }
+                    }
+                ] } } } }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let threads = test_github(&server)
+            .list_unresolved_review_threads(7)
+            .await
+            .unwrap();
+
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].comment_id, 501);
+        assert_eq!(threads[0].path, "src/lib.rs");
+        assert_eq!(threads[0].line, Some(10));
+        assert_eq!(threads[0].comments.len(), 2);
+        assert_eq!(threads[0].comments[1].author, "ghost");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn list_failed_checks_keeps_failures_only() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/commits/abc123/check-runs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "total_count": 3,
+                "check_runs": [
+                    { "id": 11, "name": "test", "conclusion": "failure", "html_url": "https://github.com/owner/repo/runs/11", "app": { "slug": "github-actions" } },
+                    { "id": 12, "name": "lint", "conclusion": "success", "html_url": "https://github.com/owner/repo/runs/12", "app": { "slug": "github-actions" } },
+                    { "id": 13, "name": "ci/external", "conclusion": "timed_out", "html_url": "https://ci.example.com/13", "app": { "slug": "external-ci" } },
+                    { "id": 14, "name": "build", "conclusion": null, "html_url": "https://github.com/owner/repo/runs/14", "app": { "slug": "github-actions" } }
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let checks = test_github(&server).list_failed_checks("abc123").await.unwrap();
+
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].id, 11);
+        assert_eq!(checks[0].name, "test");
+        assert!(checks[0].is_actions_job);
+        assert_eq!(checks[1].conclusion, "timed_out");
+        assert!(!checks[1].is_actions_job);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn download_job_log_returns_text() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/actions/jobs/11/logs"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("line 1\nerror: boom\n"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/actions/jobs/12/logs"))
+            .respond_with(ResponseTemplate::new(410))
+            .mount(&server)
+            .await;
+
+        let github = test_github(&server);
+        assert_eq!(github.download_job_log(11).await.unwrap(), "line 1\nerror: boom\n");
+        assert!(github.download_job_log(12).await.is_err());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn reply_to_review_comment_posts_reply() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls/7/comments/501/replies"))
+            .and(body_json(serde_json::json!({ "body": "Addressed in abc1234" })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({ "id": 900 })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        test_github(&server)
+            .reply_to_review_comment(7, 501, "Addressed in abc1234")
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn review_event_cycles() {
+        assert_eq!(ReviewEvent::Comment.next(), ReviewEvent::RequestChanges);
+        assert_eq!(ReviewEvent::Approve.next(), ReviewEvent::Comment);
+        assert_eq!(ReviewEvent::RequestChanges.as_api_str(), "REQUEST_CHANGES");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn close_pull_request_forbidden() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("PATCH"))
+            .and(path("/repos/owner/repo/pulls/7"))
+            .respond_with(
+                ResponseTemplate::new(403).set_body_json(serde_json::json!({
+                    "message": "Forbidden"
+                })),
+            )
+            .mount(&server)
+            .await;
+
+        assert!(test_github(&server).close_pull_request(7).await.is_err());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn add_and_remove_labels() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/issues/7/labels"))
+            .and(body_json(serde_json::json!({ "labels": ["bug"] })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/repos/owner/repo/issues/7/labels/needs%20review"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let github = test_github(&server);
+        assert!(github.add_labels(7, &["bug".to_string()]).await.is_ok());
+        assert!(github.remove_label(7, "needs review").await.is_ok());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn remove_label_encodes_the_whole_name() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/repos/owner/repo/issues/7/labels/area%2Fparser"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/repos/owner/repo/issues/7/labels/needs%3A%20%23triage%3F"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let github = test_github(&server);
+        assert!(github.remove_label(7, "area/parser").await.is_ok());
+        assert!(github.remove_label(7, "needs: #triage?").await.is_ok());
+    }
+
+    fn mock_content_response(file_path: &str, base64_content: &str) -> serde_json::Value {
+        serde_json::json!({
+            "name": file_path.rsplit('/').next().unwrap_or(file_path),
+            "path": file_path,
+            "sha": "abc123",
+            "encoding": "base64",
+            "content": base64_content,
+            "size": 42,
+            "url": format!("https://api.github.com/repos/owner/repo/contents/{}", file_path),
+            "html_url": format!("https://github.com/owner/repo/blob/main/{}", file_path),
+            "git_url": "https://api.github.com/repos/owner/repo/git/blobs/abc123",
+            "download_url": format!("https://raw.githubusercontent.com/owner/repo/main/{}", file_path),
+            "type": "file",
+            "_links": {
+                "git": "https://api.github.com/repos/owner/repo/git/blobs/abc123",
+                "html": format!("https://github.com/owner/repo/blob/main/{}", file_path),
+                "self": format!("https://api.github.com/repos/owner/repo/contents/{}", file_path)
+            }
+        })
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn list_code_owners_falls_back_to_root_file() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/contents/.github/CODEOWNERS"))
+            .respond_with(
+                ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                    "message": "Not Found"
+                })),
+            )
+            .mount(&server)
+            .await;
+
+        // "* @alice @org/core\n/docs/ @bob alice@example.com\n"
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/contents/CODEOWNERS"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(mock_content_response(
+                "CODEOWNERS",
+                "KiBAYWxpY2UgQG9yZy9jb3JlCi9kb2NzLyBAYm9iIGFsaWNlQGV4YW1wbGUuY29tCg==",
+            )))
+            .mount(&server)
+            .await;
+
+        let owners = test_github(&server).list_code_owners().await.unwrap();
+        assert_eq!(owners, vec!["alice".to_string(), "bob".to_string()]);
+    }
+
+    #[test]
+    fn parse_codeowners_skips_comments_teams_and_duplicates() {
+        let content = "# Global owners\n* @alice @org/core\n\n/src/ @bob @alice # inline\n*.md docs@example.com\n";
+        assert_eq!(
+            parse_codeowners(content),
+            vec!["alice".to_string(), "bob".to_string()]
+        );
+    }
+
+    #[test]
+    fn parse_codeowners_empty() {
+        assert!(parse_codeowners("# nothing here\n").is_empty());
+    }
 }
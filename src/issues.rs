@@ -139,7 +139,7 @@ pub fn generate_issue_with_labels(
 }
 
 /// Extract JSON from a response that may contain markdown fences
-fn extract_json(content: &str) -> Result<String, Box<dyn Error>> {
+pub(crate) fn extract_json(content: &str) -> Result<String, Box<dyn Error>> {
     let trimmed = content.trim();
 
     // Try to find JSON in markdown code block
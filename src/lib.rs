@@ -1,5 +1,6 @@
 pub mod agents;
 pub mod auth;
+pub mod ci;
 pub mod clipboard;
 pub mod commands;
 pub mod config;
@@ -9,9 +10,16 @@ pub mod images;
 pub mod issues;
 pub mod list;
 pub mod llm;
+pub mod log_view;
 pub mod login_screen;
 pub mod markdown;
+pub mod notify;
+pub mod plan;
+pub mod pr_review;
 pub mod project_select;
+pub mod pull_requests;
+pub mod quick_reply;
+pub mod replay;
 pub mod tui;
 pub mod tui_draw;
 pub mod tui_events;
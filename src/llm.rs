@@ -20,6 +20,7 @@ pub struct LlmResponse {
 ///
 /// For Claude: uses `claude -p --output-format text`
 /// For Opencode: uses `opencode run --format default`
+/// For custom agents: runs their `generate` command
 pub fn generate_response(
     messages: &[Message],
     agent_type: &CodingAgentType,
@@ -31,6 +32,9 @@ pub fn generate_response(
     let output = match agent_type {
         CodingAgentType::Claude => execute_claude(&prompt)?,
         CodingAgentType::Opencode => execute_opencode(&prompt)?,
+        CodingAgentType::Custom(name) => crate::agents::CustomAgent::from_config(name)
+            .ok_or_else(|| format!("Custom agent '{}' not found in config", name))?
+            .generate(&prompt)?,
     };
 
     Ok(LlmResponse { content: output })
@@ -0,0 +1,217 @@
+//! Agent log view: a transcript that grows while the agent runs, with search,
+//! follow mode and jumps between errors.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::agents::strip_ansi;
+use crate::ci::is_error_line;
+
+/// Transcript of an agent session, as shown in the log view
+#[derive(Debug, Clone, Default)]
+pub struct LogView {
+    pub session_id: String,
+    pub path: PathBuf,
+    /// Complete lines of the log, without escape sequences
+    pub lines: Vec<String>,
+    /// Bytes of the log file read so far, up to the last complete line
+    loaded: u64,
+    /// First visible line
+    pub scroll: usize,
+    /// First visible column
+    pub hscroll: usize,
+    /// Visible lines, as of the last draw
+    pub page: usize,
+    /// Keep the end of the log in view as it grows
+    pub follow: bool,
+    /// Search being typed
+    pub search_input: Option<String>,
+    /// Last search, matched case-insensitively
+    pub query: String,
+    /// Lines matching the query
+    pub matches: Vec<usize>,
+    /// Line selected by the last jump to a match or error
+    pub cursor: Option<usize>,
+}
+
+impl LogView {
+    /// Open the log of a session, following it when the session is still running.
+    pub fn open(session_id: &str, path: &Path, follow: bool) -> Self {
+        let mut view = Self {
+            session_id: session_id.to_string(),
+            path: path.to_path_buf(),
+            follow,
+            ..Default::default()
+        };
+        view.reload();
+        view
+    }
+
+    /// Read what was appended to the log since the last call.
+    /// A log that shrank was rotated and is read again from the start.
+    pub fn reload(&mut self) {
+        let Ok(mut file) = File::open(&self.path) else {
+            return;
+        };
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        if len < self.loaded {
+            self.lines.clear();
+            self.matches.clear();
+            self.cursor = None;
+            self.loaded = 0;
+        }
+        if len == self.loaded || file.seek(SeekFrom::Start(self.loaded)).is_err() {
+            return;
+        }
+        let mut bytes = Vec::new();
+        if file.take(len - self.loaded).read_to_end(&mut bytes).is_err() {
+            return;
+        }
+        // The last line may still be written to: keep it for the next read
+        let Some(end) = bytes.iter().rposition(|&b| b == b'\n') else {
+            return;
+        };
+        self.loaded += end as u64 + 1;
+
+        let first_new = self.lines.len();
+        let text = String::from_utf8_lossy(&bytes[..end]);
+        self.lines.extend(text.split('\n').map(strip_ansi));
+        if !self.query.is_empty() {
+            let query = self.query.to_lowercase();
+            let new_matches = (first_new..self.lines.len())
+                .filter(|&i| self.lines[i].to_lowercase().contains(&query));
+            self.matches.extend(new_matches);
+        }
+    }
+
+    /// Scroll position keeping the last line at the bottom of the page.
+    pub fn bottom(&self) -> usize {
+        self.lines.len().saturating_sub(self.page.max(1))
+    }
+
+    /// Adjust the scroll position after the page size or the log changed.
+    pub fn set_page(&mut self, page: usize) {
+        self.page = page;
+        self.scroll = if self.follow {
+            self.bottom()
+        } else {
+            self.scroll.min(self.bottom())
+        };
+    }
+
+    /// Scroll by `delta` lines; scrolling up stops following.
+    pub fn scroll_by(&mut self, delta: isize) {
+        if delta < 0 {
+            self.follow = false;
+        }
+        self.scroll = self.scroll.saturating_add_signed(delta).min(self.bottom());
+    }
+
+    /// Toggle follow mode, jumping to the end when turned on.
+    pub fn toggle_follow(&mut self) {
+        self.follow = !self.follow;
+        if self.follow {
+            self.scroll = self.bottom();
+        }
+    }
+
+    /// Search for `query`, jumping to the first match after the current position.
+    pub fn search(&mut self, query: &str) {
+        self.query = query.to_string();
+        let query = query.to_lowercase();
+        self.matches = if query.is_empty() {
+            Vec::new()
+        } else {
+            (0..self.lines.len())
+                .filter(|&i| self.lines[i].to_lowercase().contains(&query))
+                .collect()
+        };
+        self.cursor = None;
+        self.next_match(true);
+    }
+
+    /// Jump to the next or previous match, wrapping around.
+    pub fn next_match(&mut self, forward: bool) {
+        let matches = self.matches.clone();
+        self.jump(matches.into_iter(), forward);
+    }
+
+    /// Jump to the next or previous line reporting an error, wrapping around.
+    pub fn next_error(&mut self, forward: bool) {
+        let errors: Vec<usize> = (0..self.lines.len())
+            .filter(|&i| is_error_line(&self.lines[i]))
+            .collect();
+        self.jump(errors.into_iter(), forward);
+    }
+
+    /// Select the first of `lines` (sorted) after or before the cursor.
+    fn jump(&mut self, lines: impl DoubleEndedIterator<Item = usize> + Clone, forward: bool) {
+        let from = self.cursor.unwrap_or(self.scroll);
+        let target = if forward {
+            let start = if self.cursor.is_some() { from + 1 } else { from };
+            lines.clone().find(|&i| i >= start).or_else(|| lines.clone().next())
+        } else {
+            lines.clone().rev().find(|&i| i < from).or_else(|| lines.clone().next_back())
+        };
+        if let Some(line) = target {
+            self.cursor = Some(line);
+            self.follow = false;
+            // Show some context above the selected line
+            self.scroll = line.saturating_sub(self.page / 3).min(self.bottom());
+        }
+    }
+
+    /// Position of the selected match among all matches, for display.
+    pub fn match_position(&self) -> Option<usize> {
+        let cursor = self.cursor?;
+        self.matches.iter().position(|&i| i == cursor).map(|i| i + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn log_view_reads_appended_lines_and_jumps() {
+        let root = std::env::temp_dir().join(format!("assistant-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join("a.log");
+        std::fs::write(&path, "\x1b[1mCompiling\x1b[0m\nerror: oops\nfixing\npartial").unwrap();
+
+        let mut view = LogView::open("a", &path, true);
+        view.set_page(2);
+        assert_eq!(view.lines, vec!["Compiling", "error: oops", "fixing"]);
+        assert_eq!(view.scroll, 1);
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, " line\ntests FAILED").unwrap();
+        view.reload();
+        view.set_page(2);
+        assert_eq!(view.lines[3..], ["partial line", "tests FAILED"]);
+        assert_eq!(view.scroll, 3);
+
+        view.next_error(true);
+        assert_eq!(view.cursor, Some(4));
+        assert!(!view.follow);
+        view.next_error(true);
+        assert_eq!(view.cursor, Some(1));
+        view.next_error(false);
+        assert_eq!(view.cursor, Some(4));
+
+        view.search("FIX");
+        assert_eq!(view.matches, vec![2]);
+        assert_eq!(view.cursor, Some(2));
+        assert_eq!(view.match_position(), Some(1));
+
+        // Rotation: the log is read again from the start
+        std::fs::write(&path, "fresh\n").unwrap();
+        view.reload();
+        assert_eq!(view.lines, vec!["fresh"]);
+        assert!(view.matches.is_empty());
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
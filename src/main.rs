@@ -1,3 +1,4 @@
+use assistant::agents;
 use assistant::auth;
 use assistant::commands::CommandRegistry;
 use assistant::config;
@@ -12,6 +13,24 @@ async fn main() {
     // Parse CLI arguments
     let args: Vec<String> = std::env::args().collect();
 
+    // Internal: supervisor process of a native agent session
+    if args.get(1).map(String::as_str) == Some(agents::SUPERVISOR_ARG) {
+        agents::run_session_supervisor(&args[2..]);
+    }
+
+    // Internal: transcript recorder of a tmux pane
+    if args.get(1).map(String::as_str) == Some(agents::TRANSCRIPT_ARG) {
+        agents::run_transcript(&args[2..]);
+    }
+
+    // Background monitoring of agent sessions
+    if args.get(1).map(String::as_str) == Some("daemon") {
+        if let Err(e) = agents::run_daemon() {
+            eprintln!("Daemon error: {}", e);
+        }
+        return;
+    }
+
     // Handle --logout flag
     if args.contains(&"--logout".to_string()) {
         match auth::delete_token() {
@@ -0,0 +1,326 @@
+//! Notifications about agent sessions, delivered to the configured notifiers.
+//!
+//! Each notifier in the `notifiers` config gets the events routed to it: desktop
+//! notifications, the terminal bell with an OSC 9 message, a webhook POST or a shell
+//! command. Delivery happens on a background thread so callers never wait on it.
+//!
+//! Bells never go to stdout from that thread: a TUI subscribed with [`subscribe_bells`]
+//! rings them between frames, otherwise they are written to the controlling terminal.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::config::{NotifierConfig, NotifierKind, NotifyEvent};
+
+/// Something worth telling the user about
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub event: NotifyEvent,
+    pub title: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<u64>,
+    /// Pull request URL, for `pr_created`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl Notification {
+    pub fn new(event: NotifyEvent, title: &str, message: &str) -> Self {
+        Self {
+            event,
+            title: title.to_string(),
+            message: message.to_string(),
+            project: None,
+            issue: None,
+            url: None,
+        }
+    }
+
+    /// Set the project and issue the notification is about.
+    pub fn for_issue(mut self, project: &str, issue: u64) -> Self {
+        self.project = Some(project.to_string());
+        self.issue = Some(issue);
+        self
+    }
+
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    /// Event name as used in the configuration
+    fn event_name(&self) -> String {
+        serde_json::to_value(self.event)
+            .ok()
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_default()
+    }
+}
+
+/// Deliver a notification to the notifiers of the configuration file.
+pub fn notify(notification: Notification) {
+    let notifiers = crate::config::load_config()
+        .map(|c| c.notifiers())
+        .unwrap_or_default();
+    dispatch(&notifiers, notification);
+}
+
+/// Deliver a notification to the notifiers that want its event, in the background.
+pub fn dispatch(notifiers: &[NotifierConfig], notification: Notification) {
+    let targets: Vec<NotifierKind> = notifiers
+        .iter()
+        .filter(|n| n.wants(notification.event))
+        .map(|n| n.kind.clone())
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        for target in targets {
+            let _ = deliver(&target, &notification);
+        }
+    });
+}
+
+/// Deliver a notification through one notifier.
+pub fn deliver(kind: &NotifierKind, notification: &Notification) -> Result<(), String> {
+    match kind {
+        NotifierKind::Desktop => desktop(notification),
+        NotifierKind::Bell => bell(notification),
+        NotifierKind::Webhook { url } => tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| e.to_string())?
+            .block_on(webhook(url, notification)),
+        NotifierKind::Command { command } => run_hook(command, notification),
+    }
+}
+
+/// Notification Center notification with sound.
+#[cfg(target_os = "macos")]
+fn desktop(notification: &Notification) -> Result<(), String> {
+    let script = format!(
+        "display notification \"{}\" with title \"{}\" sound name \"Glass\"",
+        notification.message.replace('"', "\\\"").replace('\n', " "),
+        notification.title.replace('"', "\\\"")
+    );
+    // Use output() instead of spawn() to ensure the command completes
+    Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Freedesktop notification through `notify-send`, or straight over D-Bus with
+/// `gdbus` when libnotify's tool is not installed.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn desktop(notification: &Notification) -> Result<(), String> {
+    let sent = Command::new("notify-send")
+        .args(["--app-name", "assistant", &notification.title, &notification.message])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success());
+    if sent {
+        return Ok(());
+    }
+
+    let quote = |s: &str| format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"));
+    let status = Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.Notifications",
+            "--object-path",
+            "/org/freedesktop/Notifications",
+            "--method",
+            "org.freedesktop.Notifications.Notify",
+            "assistant",
+            "0",
+            "",
+            &quote(&notification.title),
+            &quote(&notification.message),
+            "[]",
+            "{}",
+            "-1",
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("Neither notify-send nor gdbus could run: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("No notification service on the session bus".to_string())
+    }
+}
+
+#[cfg(not(unix))]
+fn desktop(_notification: &Notification) -> Result<(), String> {
+    Err("Desktop notifications are not supported on this platform".to_string())
+}
+
+static BELL_SUBSCRIBERS: Mutex<Vec<Sender<String>>> = Mutex::new(Vec::new());
+
+/// Receive the bells rung in this process, as escape sequences to write to the terminal.
+/// While a subscriber is alive, bells are left to it instead of written to the terminal.
+pub fn subscribe_bells() -> Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    BELL_SUBSCRIBERS.lock().unwrap().push(tx);
+    rx
+}
+
+/// Terminal bell followed by an OSC 9 notification, shown by terminals such as
+/// iTerm2, kitty and Windows Terminal.
+fn bell(notification: &Notification) -> Result<(), String> {
+    let text = format!("{}: {}", notification.title, notification.message)
+        .replace(['\x07', '\x1b', '\n'], " ");
+    let sequence = format!("\x07\x1b]9;{}\x07", text);
+
+    let mut subscribers = BELL_SUBSCRIBERS.lock().unwrap();
+    subscribers.retain(|s| s.send(sequence.clone()).is_ok());
+    if !subscribers.is_empty() {
+        return Ok(());
+    }
+    drop(subscribers);
+    ring_terminal(&sequence)
+}
+
+/// Write a bell sequence to the controlling terminal, even when stdout is redirected.
+#[cfg(unix)]
+fn ring_terminal(sequence: &str) -> Result<(), String> {
+    let mut tty = std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/tty")
+        .map_err(|e| format!("No terminal to ring: {}", e))?;
+    tty.write_all(sequence.as_bytes()).map_err(|e| e.to_string())?;
+    tty.flush().map_err(|e| e.to_string())
+}
+
+#[cfg(not(unix))]
+fn ring_terminal(sequence: &str) -> Result<(), String> {
+    let mut out = std::io::stdout().lock();
+    out.write_all(sequence.as_bytes()).map_err(|e| e.to_string())?;
+    out.flush().map_err(|e| e.to_string())
+}
+
+/// POST the notification as JSON.
+pub async fn webhook(url: &str, notification: &Notification) -> Result<(), String> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .json(notification)
+        .send()
+        .await
+        .map_err(|e| format!("Webhook request failed: {}", e))?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Webhook returned {}", response.status()))
+    }
+}
+
+/// Run a shell command with the notification in its environment.
+fn run_hook(command: &str, notification: &Notification) -> Result<(), String> {
+    #[cfg(unix)]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    #[cfg(not(unix))]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    };
+    cmd.env("ASSISTANT_EVENT", notification.event_name())
+        .env("ASSISTANT_TITLE", &notification.title)
+        .env("ASSISTANT_MESSAGE", &notification.message)
+        .env("ASSISTANT_PROJECT", notification.project.as_deref().unwrap_or(""))
+        .env("ASSISTANT_ISSUE", notification.issue.map(|n| n.to_string()).unwrap_or_default())
+        .env("ASSISTANT_URL", notification.url.as_deref().unwrap_or(""))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    let status = cmd.status().map_err(|e| format!("Failed to run hook: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Hook exited with {}", status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn awaiting() -> Notification {
+        Notification::new(NotifyEvent::Awaiting, "Claude Code", "Awaiting input for issue #7")
+            .for_issue("proj", 7)
+    }
+
+    #[tokio::test]
+    async fn webhook_posts_notification_json() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(body_json(serde_json::json!({
+                "event": "awaiting",
+                "title": "Claude Code",
+                "message": "Awaiting input for issue #7",
+                "project": "proj",
+                "issue": 7
+            })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/hook", server.uri());
+        assert!(webhook(&url, &awaiting()).await.is_ok());
+
+        let missing = format!("{}/missing", server.uri());
+        assert!(webhook(&missing, &awaiting()).await.is_err());
+    }
+
+    #[test]
+    fn bells_go_to_subscribers() {
+        let bells = subscribe_bells();
+        bell(&awaiting()).unwrap();
+        assert_eq!(
+            bells.recv().unwrap(),
+            "\x07\x1b]9;Claude Code: Awaiting input for issue #7\x07"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_hook_gets_notification_environment() {
+        let out = std::env::temp_dir().join(format!("assistant-test-{}", uuid::Uuid::new_v4()));
+        let command = format!(
+            "echo \"$ASSISTANT_EVENT $ASSISTANT_PROJECT#$ASSISTANT_ISSUE $ASSISTANT_URL\" > '{}'",
+            out.display()
+        );
+        let notification = Notification::new(NotifyEvent::PrCreated, "PR", "opened")
+            .for_issue("proj", 3)
+            .with_url("https://github.com/o/r/pull/9");
+        run_hook(&command, &notification).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "pr_created proj#3 https://github.com/o/r/pull/9\n"
+        );
+        assert!(run_hook("exit 3", &notification).is_err());
+        let _ = std::fs::remove_file(out);
+    }
+}
@@ -0,0 +1,179 @@
+//! Plan-then-implement dispatch: the agent first writes an implementation plan, which is
+//! reviewed (and possibly edited) before it is sent back as the instruction to implement.
+
+use std::path::Path;
+
+use crate::agents::AgentSession;
+
+/// File the planning agent writes its plan to, relative to the worktree root.
+pub const PLAN_FILE: &str = ".assistant-plan.md";
+
+const PLAN_START: &str = "<plan>";
+const PLAN_END: &str = "</plan>";
+
+/// Build the prompt asking an agent for an implementation plan only, from the issue prompt.
+pub fn build_plan_prompt(issue_prompt: &str) -> String {
+    let mut prompt = issue_prompt.to_string();
+    prompt.push_str(&format!(
+        "\n\n---\n\nDo not change any code yet. First explore the repository and write an \
+        implementation plan for this issue: the approach, the files to change and how, \
+        edge cases and how to test it.\n\n\
+        Write the plan in markdown to `{}` at the repository root (do not commit it). \
+        If you cannot write files, print it between a `{}` line and a `{}` line instead. \
+        Then stop and wait: the plan is reviewed before you implement it.",
+        PLAN_FILE, PLAN_START, PLAN_END
+    ));
+    prompt
+}
+
+/// Build the instruction sent to the agent once its plan is approved.
+pub fn build_implement_prompt(plan: &str, feedback: &str) -> String {
+    let mut prompt = String::from(
+        "The plan is approved. Implement it now, following the plan below as reviewed.\n\n",
+    );
+    prompt.push_str(plan.trim());
+    if !feedback.trim().is_empty() {
+        prompt.push_str("\n\nReviewer notes:\n");
+        prompt.push_str(feedback.trim());
+    }
+    prompt.push_str(&format!("\n\nDo not commit `{}`.", PLAN_FILE));
+    prompt
+}
+
+/// Build the instruction asking the agent to revise its plan.
+pub fn build_revision_prompt(feedback: &str) -> String {
+    format!(
+        "Revise the implementation plan with this feedback, still without changing any \
+        code, and write the new plan to `{}`:\n\n{}",
+        PLAN_FILE,
+        feedback.trim()
+    )
+}
+
+/// Issue comment presenting a plan.
+pub fn plan_comment(plan: &str) -> String {
+    format!("### Implementation plan\n\n{}", plan.trim())
+}
+
+/// Last plan printed between `<plan>` and `</plan>` lines in an agent transcript.
+pub fn extract_plan(transcript: &str) -> Option<String> {
+    let text = crate::agents::strip_ansi(transcript);
+    let lines: Vec<&str> = text.lines().collect();
+    let end = lines.iter().rposition(|l| l.trim() == PLAN_END)?;
+    let start = lines[..end].iter().rposition(|l| l.trim() == PLAN_START)?;
+    let plan = lines[start + 1..end].join("\n");
+    let plan = plan.trim();
+    (!plan.is_empty()).then(|| plan.to_string())
+}
+
+/// Load the plan of a planning session: the plan file, or else the transcript.
+pub fn load_plan(worktree_path: &Path, log_file: &Path) -> Result<String, String> {
+    if let Ok(plan) = std::fs::read_to_string(worktree_path.join(PLAN_FILE))
+        && !plan.trim().is_empty()
+    {
+        return Ok(plan.trim().to_string());
+    }
+    std::fs::read_to_string(log_file)
+        .ok()
+        .and_then(|transcript| extract_plan(&transcript))
+        .ok_or_else(|| format!("No plan yet: the agent has not written {}", PLAN_FILE))
+}
+
+/// Remove the plan file once the plan is approved.
+pub fn clear_plan(worktree_path: &Path) {
+    let _ = std::fs::remove_file(worktree_path.join(PLAN_FILE));
+}
+
+/// Text field being edited in the plan review
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanField {
+    Plan,
+    Feedback,
+}
+
+/// A plan under review in the TUI
+#[derive(Debug, Clone)]
+pub struct PlanReview {
+    pub session: AgentSession,
+    pub plan: String,
+    /// Notes for the agent, sent with the approval or as a revision request
+    pub feedback: String,
+    /// Field being edited, with its edit buffer
+    pub editing: Option<(PlanField, String)>,
+    pub scroll: u16,
+    /// URL of the issue comment the plan was posted as
+    pub comment_url: Option<String>,
+}
+
+impl PlanReview {
+    pub fn new(session: AgentSession, plan: String) -> Self {
+        Self {
+            session,
+            plan,
+            feedback: String::new(),
+            editing: None,
+            scroll: 0,
+            comment_url: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::IssueDetail;
+
+    fn issue() -> IssueDetail {
+        IssueDetail {
+            number: 12,
+            title: "Add retries".to_string(),
+            body: Some("Requests should be retried".to_string()),
+            labels: vec![],
+            state: "Open".to_string(),
+            html_url: "https://github.com/test/test/issues/12".to_string(),
+            assignees: vec![],
+            comments: vec![],
+        }
+    }
+
+    #[test]
+    fn plan_prompt_asks_for_plan_only() {
+        let issue_prompt = crate::agents::build_issue_prompt(&issue(), Some("Keep it small"));
+        let prompt = build_plan_prompt(&issue_prompt);
+        assert!(prompt.starts_with("Read and implement GitHub issue #12: Add retries"));
+        assert!(prompt.contains("Keep it small"));
+        assert!(prompt.contains("Do not change any code yet"));
+        assert!(prompt.contains(PLAN_FILE));
+
+        let prompt = build_implement_prompt("1. Add a retry loop\n", "Cap at 3 retries");
+        assert!(prompt.contains("1. Add a retry loop\n\nReviewer notes:\nCap at 3 retries"));
+        assert!(!build_implement_prompt("plan", " ").contains("Reviewer notes"));
+    }
+
+    #[test]
+    fn extract_plan_takes_last_block() {
+        let transcript = "\x1b[1mthinking\x1b[0m\n<plan>\nold\n</plan>\n\
+            Revised:\n  <plan>\n1. Retry\n2. Test\n  </plan>\n> ";
+        assert_eq!(extract_plan(transcript).as_deref(), Some("1. Retry\n2. Test"));
+        assert_eq!(extract_plan("no plan here"), None);
+        assert_eq!(extract_plan("<plan>\n\n</plan>"), None);
+    }
+
+    #[test]
+    fn load_plan_prefers_plan_file() {
+        let dir = std::env::temp_dir().join(format!("assistant-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("agent.log");
+        assert!(load_plan(&dir, &log).is_err());
+
+        std::fs::write(&log, "<plan>\nfrom transcript\n</plan>\n").unwrap();
+        assert_eq!(load_plan(&dir, &log).unwrap(), "from transcript");
+
+        std::fs::write(dir.join(PLAN_FILE), "# Plan\n\nfrom file\n").unwrap();
+        assert_eq!(load_plan(&dir, &log).unwrap(), "# Plan\n\nfrom file");
+
+        clear_plan(&dir);
+        assert_eq!(load_plan(&dir, &log).unwrap(), "from transcript");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,189 @@
+//! Agent-driven pull request reviews: prompt and structured findings.
+
+use std::error::Error;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::github::{PullRequestDetail, ReviewComment};
+
+/// File the review agent writes its findings to, relative to the worktree root.
+pub const FINDINGS_FILE: &str = ".assistant-review.json";
+
+/// File recording review feedback sent to an agent, relative to the worktree root.
+pub const FEEDBACK_FILE: &str = ".assistant-feedback.json";
+
+/// Review feedback handed to an agent and not yet replied to
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct PendingFeedback {
+    /// Commit the worktree was at when the feedback was sent
+    pub base_sha: String,
+    /// Review comments to reply to once the fix is pushed
+    pub comment_ids: Vec<u64>,
+}
+
+/// Structured output of a review agent
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ReviewFindings {
+    pub summary: String,
+    #[serde(default)]
+    pub comments: Vec<ReviewComment>,
+}
+
+/// Build the prompt for an agent reviewing a pull request.
+pub fn build_review_prompt(pr: &PullRequestDetail, instructions: &str) -> String {
+    let mut prompt = format!("Review this PR #{}: \"{}\"\n\n", pr.number, pr.title);
+    if let Some(body) = &pr.body {
+        prompt.push_str(&format!("Description:\n{}\n\n", body));
+    }
+    prompt.push_str(&format!(
+        "The PR merges into `{}`. Compare against it to see the changes.\n\n",
+        pr.base_ref
+    ));
+    prompt.push_str(
+        "Please review the code changes, check for:\n\
+        - Code quality and best practices\n\
+        - Potential bugs or edge cases\n\
+        - Performance concerns\n\
+        - Security issues\n\n",
+    );
+    prompt.push_str(&format!(
+        "When you are done, write your findings as JSON to `{}` at the repository root, \
+        with this exact shape:\n\n\
+        {{\"summary\": \"overall assessment in markdown\", \"comments\": [{{\"path\": \"src/file.rs\", \"line\": 42, \"body\": \"comment\"}}]}}\n\n\
+        `path` is relative to the repository root and `line` is a line number in the new \
+        version of the file that is part of the diff. Do not commit this file.",
+        FINDINGS_FILE
+    ));
+    if !instructions.is_empty() {
+        prompt.push_str(&format!("\n\nAdditional instructions:\n{}", instructions));
+    }
+    prompt
+}
+
+/// Parse review findings, dropping comments that cannot be posted inline.
+pub fn parse_findings(content: &str) -> Result<ReviewFindings, Box<dyn Error>> {
+    let json_content = crate::issues::extract_json(content)?;
+    let mut findings: ReviewFindings = serde_json::from_str(&json_content)?;
+    findings
+        .comments
+        .retain(|c| !c.path.trim().is_empty() && c.line > 0 && !c.body.trim().is_empty());
+    Ok(findings)
+}
+
+/// Load the findings file from a review worktree.
+pub fn load_findings(worktree_path: &Path) -> Result<ReviewFindings, Box<dyn Error>> {
+    let path = worktree_path.join(FINDINGS_FILE);
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("No review findings at {}: {}", path.display(), e))?;
+    parse_findings(&content)
+}
+
+/// Remove the findings file once the review has been posted.
+pub fn clear_findings(worktree_path: &Path) {
+    let _ = std::fs::remove_file(worktree_path.join(FINDINGS_FILE));
+}
+
+/// Record the feedback sent to the agent working in a worktree.
+pub fn save_pending_feedback(
+    worktree_path: &Path,
+    feedback: &PendingFeedback,
+) -> Result<(), Box<dyn Error>> {
+    let content = serde_json::to_string_pretty(feedback)?;
+    std::fs::write(worktree_path.join(FEEDBACK_FILE), content)?;
+    Ok(())
+}
+
+/// Load the pending feedback of a worktree, if any.
+pub fn load_pending_feedback(worktree_path: &Path) -> Option<PendingFeedback> {
+    let content = std::fs::read_to_string(worktree_path.join(FEEDBACK_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Forget the pending feedback once the threads have been answered.
+pub fn clear_pending_feedback(worktree_path: &Path) {
+    let _ = std::fs::remove_file(worktree_path.join(FEEDBACK_FILE));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pr() -> PullRequestDetail {
+        PullRequestDetail {
+            number: 9,
+            title: "Add cache".to_string(),
+            body: Some("Adds an LRU cache".to_string()),
+            html_url: "https://github.com/test/test/pull/9".to_string(),
+            labels: vec![],
+            state: "Open".to_string(),
+            assignees: vec![],
+            author: "octocat".to_string(),
+            draft: false,
+            head_ref: "cache".to_string(),
+            head_sha: "abc123".to_string(),
+            base_ref: "main".to_string(),
+            mergeable: Some(true),
+            requested_reviewers: vec![],
+            head_repo: None,
+            head_clone_url: None,
+            maintainer_can_modify: false,
+            comments: vec![],
+        }
+    }
+
+    #[test]
+    fn build_review_prompt_asks_for_findings_file() {
+        let prompt = build_review_prompt(&pr(), "Focus on eviction");
+        assert!(prompt.contains("Review this PR #9: \"Add cache\""));
+        assert!(prompt.contains("Adds an LRU cache"));
+        assert!(prompt.contains(FINDINGS_FILE));
+        assert!(prompt.contains("Additional instructions:\nFocus on eviction"));
+    }
+
+    #[test]
+    fn parse_findings_drops_unpostable_comments() {
+        let content = r#"{
+            "summary": "Looks good overall",
+            "comments": [
+                {"path": "src/cache.rs", "line": 12, "body": "Off by one"},
+                {"path": "", "line": 3, "body": "no path"},
+                {"path": "src/lib.rs", "line": 0, "body": "no line"},
+                {"path": "src/lib.rs", "line": 4, "body": "  "}
+            ]
+        }"#;
+
+        let findings = parse_findings(content).unwrap();
+        assert_eq!(findings.summary, "Looks good overall");
+        assert_eq!(findings.comments.len(), 1);
+        assert_eq!(findings.comments[0].path, "src/cache.rs");
+        assert_eq!(findings.comments[0].line, 12);
+    }
+
+    #[test]
+    fn parse_findings_without_comments_or_in_fence() {
+        let findings = parse_findings("```json\n{\"summary\": \"LGTM\"}\n```").unwrap();
+        assert_eq!(findings.summary, "LGTM");
+        assert!(findings.comments.is_empty());
+
+        assert!(parse_findings("not json").is_err());
+    }
+
+    #[test]
+    fn pending_feedback_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("assistant-feedback-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(load_pending_feedback(&dir).is_none());
+
+        let feedback = PendingFeedback {
+            base_sha: "abc1234".to_string(),
+            comment_ids: vec![501, 502],
+        };
+        save_pending_feedback(&dir, &feedback).unwrap();
+        assert_eq!(load_pending_feedback(&dir), Some(feedback));
+
+        clear_pending_feedback(&dir);
+        assert!(load_pending_feedback(&dir).is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
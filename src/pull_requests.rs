@@ -0,0 +1,325 @@
+//! Pull request content generation from a worktree diff and its linked issue.
+
+use std::error::Error;
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::CodingAgentType;
+use crate::github::IssueDetail;
+use crate::llm;
+
+/// Maximum diff size sent to the LLM, in bytes.
+const MAX_DIFF_BYTES: usize = 30_000;
+
+/// Locations GitHub looks at for a pull request template, in priority order.
+const TEMPLATE_PATHS: [&str; 5] = [
+    ".github/pull_request_template.md",
+    ".github/PULL_REQUEST_TEMPLATE.md",
+    "pull_request_template.md",
+    "PULL_REQUEST_TEMPLATE.md",
+    "docs/pull_request_template.md",
+];
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PullRequestContent {
+    pub title: String,
+    pub body: String,
+}
+
+/// Read the repository's pull request template, if any.
+pub fn find_template(repo_path: &Path) -> Option<String> {
+    TEMPLATE_PATHS
+        .iter()
+        .filter_map(|p| std::fs::read_to_string(repo_path.join(p)).ok())
+        .find(|content| !content.trim().is_empty())
+}
+
+/// Cut a diff at a line boundary so it fits in `max_bytes`.
+pub fn truncate_diff(diff: &str, max_bytes: usize) -> String {
+    if diff.len() <= max_bytes {
+        return diff.to_string();
+    }
+
+    let mut kept = String::new();
+    let mut kept_lines = 0;
+    for line in diff.lines() {
+        if kept.len() + line.len() + 1 > max_bytes {
+            break;
+        }
+        kept.push_str(line);
+        kept.push('\n');
+        kept_lines += 1;
+    }
+
+    let remaining = diff.lines().count() - kept_lines;
+    kept.push_str(&format!("[diff truncated: {} more lines]\n", remaining));
+    kept
+}
+
+/// Append a "Closes #N" reference unless the body already links the issue.
+pub fn ensure_closes_reference(body: &str, issue_number: u64) -> String {
+    let reference = format!("#{}", issue_number);
+    let pattern = format!(r"(?i)\b(close[sd]?|fix(e[sd])?|resolve[sd]?)\s+#{}\b", issue_number);
+    let already_linked = Regex::new(&pattern).is_ok_and(|re| re.is_match(body));
+
+    if already_linked {
+        body.to_string()
+    } else if body.trim().is_empty() {
+        format!("Closes {}", reference)
+    } else {
+        format!("{}\n\nCloses {}", body.trim_end(), reference)
+    }
+}
+
+/// Content used when the LLM is unavailable: the template as-is and a title from the issue.
+pub fn fallback_content(
+    issue: Option<&IssueDetail>,
+    branch_name: &str,
+    template: Option<&str>,
+) -> PullRequestContent {
+    let title = match issue {
+        Some(issue) => format!("Fix #{}: {}", issue.number, issue.title),
+        None => branch_name.to_string(),
+    };
+    let body = template.unwrap_or_default().to_string();
+
+    PullRequestContent {
+        title,
+        body: match issue {
+            Some(issue) => ensure_closes_reference(&body, issue.number),
+            None => body,
+        },
+    }
+}
+
+pub fn build_prompt(issue: Option<&IssueDetail>, diff: &str, template: Option<&str>) -> String {
+    let mut prompt = String::from(
+        r#"You write GitHub pull request descriptions for experienced engineers.
+
+Given the diff of a branch (and the issue it addresses, if any), produce a title and a markdown body.
+
+Rules:
+- Always answer in English.
+- Title: max about 72 characters, imperative mood (e.g. "Fix crash when config is missing").
+- Body: summarize what changed and why, grouped by area. Mention risks or follow-ups if the diff implies any.
+- Do not invent changes that are not in the diff.
+- Do not wrap the body in a code fence.
+"#,
+    );
+
+    if let Some(template) = template {
+        prompt.push_str(&format!(
+            "- The repository has a pull request template. Fill it in, keeping its headings and checklists:\n\n<template>\n{}\n</template>\n",
+            template.trim()
+        ));
+    }
+
+    if let Some(issue) = issue {
+        prompt.push_str(&format!(
+            "\nLinked issue #{}: {}\n\n{}\n",
+            issue.number,
+            issue.title,
+            issue.body.as_deref().unwrap_or("(no description)")
+        ));
+        prompt.push_str(&format!(
+            "\nThe body must reference the issue with \"Closes #{}\".\n",
+            issue.number
+        ));
+    }
+
+    prompt.push_str(&format!(
+        "\nDiff:\n\n```diff\n{}\n```\n",
+        truncate_diff(diff, MAX_DIFF_BYTES)
+    ));
+
+    prompt.push_str(
+        r#"
+Required output format (raw JSON, no markdown fences, no extra text):
+
+{"title": "short title", "body": "markdown body"}
+"#,
+    );
+
+    prompt
+}
+
+/// Ask the configured agent for a pull request title and body.
+pub fn generate_pr_content(
+    issue: Option<&IssueDetail>,
+    diff: &str,
+    template: Option<&str>,
+    agent_type: &CodingAgentType,
+) -> Result<PullRequestContent, Box<dyn Error>> {
+    let messages = vec![llm::Message {
+        role: "system".to_string(),
+        content: build_prompt(issue, diff, template),
+    }];
+
+    let response = llm::generate_response(&messages, agent_type)?;
+    let json_content = crate::issues::extract_json(&response.content)?;
+    let mut content: PullRequestContent = serde_json::from_str(&json_content)?;
+
+    if let Some(issue) = issue {
+        content.body = ensure_closes_reference(&content.body, issue.number);
+    }
+
+    Ok(content)
+}
+
+/// Commit message used when the LLM is unavailable.
+pub fn fallback_commit_message(issue: Option<&IssueDetail>, branch_name: &str) -> String {
+    match issue {
+        Some(issue) => format!("Fix #{}: {}", issue.number, issue.title),
+        None => format!("Update {}", branch_name),
+    }
+}
+
+pub fn build_commit_prompt(issue: Option<&IssueDetail>, diff: &str) -> String {
+    let mut prompt = String::from(
+        r#"You write git commit messages for experienced engineers.
+
+Given the diff of uncommitted changes (and the issue they address, if any), write a commit message.
+
+Rules:
+- Always answer in English.
+- First line: max about 72 characters, imperative mood, no trailing period.
+- Then, if useful, a blank line and a short body explaining what changed and why.
+- Do not invent changes that are not in the diff.
+- Answer with the commit message only: no code fence, no extra text.
+"#,
+    );
+
+    if let Some(issue) = issue {
+        prompt.push_str(&format!("\nIssue #{}: {}\n", issue.number, issue.title));
+    }
+
+    prompt.push_str(&format!(
+        "\nDiff:\n\n```diff\n{}\n```\n",
+        truncate_diff(diff, MAX_DIFF_BYTES)
+    ));
+
+    prompt
+}
+
+/// Ask the configured agent for a commit message describing `diff`.
+pub fn generate_commit_message(
+    issue: Option<&IssueDetail>,
+    diff: &str,
+    agent_type: &CodingAgentType,
+) -> Result<String, Box<dyn Error>> {
+    let messages = vec![llm::Message {
+        role: "system".to_string(),
+        content: build_commit_prompt(issue, diff),
+    }];
+
+    let response = llm::generate_response(&messages, agent_type)?;
+    let message = response
+        .content
+        .trim()
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim()
+        .to_string();
+    if message.is_empty() {
+        return Err("Empty commit message".into());
+    }
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue() -> IssueDetail {
+        IssueDetail {
+            number: 42,
+            title: "Crash on start".to_string(),
+            body: Some("The app panics when the config is missing".to_string()),
+            html_url: "https://github.com/test/test/issues/42".to_string(),
+            labels: vec![],
+            state: "Open".to_string(),
+            assignees: vec![],
+            comments: vec![],
+        }
+    }
+
+    #[test]
+    fn build_prompt_includes_template_issue_and_diff() {
+        let prompt = build_prompt(
+            Some(&issue()),
+            "+fn main() {}",
+            Some("## Summary\n\n## Checklist\n- [ ] Tests"),
+        );
+        assert!(prompt.contains("## Checklist"));
+        assert!(prompt.contains("Linked issue #42: Crash on start"));
+        assert!(prompt.contains("Closes #42"));
+        assert!(prompt.contains("+fn main() {}"));
+    }
+
+    #[test]
+    fn build_prompt_without_issue_or_template() {
+        let prompt = build_prompt(None, "+x", None);
+        assert!(!prompt.contains("<template>"));
+        assert!(!prompt.contains("Linked issue"));
+    }
+
+    #[test]
+    fn commit_prompt_and_fallback() {
+        let prompt = build_commit_prompt(Some(&issue()), "+fn main() {}");
+        assert!(prompt.contains("Issue #42: Crash on start"));
+        assert!(prompt.contains("+fn main() {}"));
+
+        assert_eq!(fallback_commit_message(Some(&issue()), "issue-42"), "Fix #42: Crash on start");
+        assert_eq!(fallback_commit_message(None, "feature/x"), "Update feature/x");
+    }
+
+    #[test]
+    fn truncate_diff_keeps_whole_lines() {
+        let diff = "line one\nline two\nline three\n";
+        let truncated = truncate_diff(diff, 18);
+        assert!(truncated.starts_with("line one\nline two\n"));
+        assert!(truncated.contains("[diff truncated: 1 more lines]"));
+
+        assert_eq!(truncate_diff(diff, 1000), diff);
+    }
+
+    #[test]
+    fn ensure_closes_reference_appends_once() {
+        assert_eq!(ensure_closes_reference("", 7), "Closes #7");
+        assert_eq!(
+            ensure_closes_reference("Some summary\n", 7),
+            "Some summary\n\nCloses #7"
+        );
+        assert_eq!(ensure_closes_reference("Fixes #7", 7), "Fixes #7");
+        assert_eq!(
+            ensure_closes_reference("Resolves #70", 7),
+            "Resolves #70\n\nCloses #7"
+        );
+    }
+
+    #[test]
+    fn fallback_content_uses_template_and_issue() {
+        let content = fallback_content(Some(&issue()), "issue-42", Some("## Summary\n"));
+        assert_eq!(content.title, "Fix #42: Crash on start");
+        assert_eq!(content.body, "## Summary\n\nCloses #42");
+
+        let content = fallback_content(None, "feature/x", None);
+        assert_eq!(content.title, "feature/x");
+        assert!(content.body.is_empty());
+    }
+
+    #[test]
+    fn find_template_prefers_github_dir() {
+        let dir = std::env::temp_dir().join(format!("pr-template-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join(".github")).unwrap();
+        std::fs::write(dir.join("PULL_REQUEST_TEMPLATE.md"), "root").unwrap();
+        std::fs::write(dir.join(".github/pull_request_template.md"), "github").unwrap();
+
+        assert_eq!(find_template(&dir).as_deref(), Some("github"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(find_template(&dir), None);
+    }
+}
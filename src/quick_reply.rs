@@ -0,0 +1,192 @@
+//! Quick replies: answer agents waiting for input from a popup, without attaching to them.
+//!
+//! The popup shows the end of the agent's pane and, when it ends with a permission or
+//! selection dialog, offers one-key answers. Replies go to each awaiting session in turn.
+
+use regex::Regex;
+
+use crate::agents::{
+    get_agent, session_backend, AgentDialog, AgentError, AgentSession, DialogAnswer,
+    SessionManager,
+};
+
+/// Pane lines shown in the popup
+pub const PANE_LINES: usize = 15;
+
+/// A reply to a waiting agent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuickReply {
+    /// Answer the dialog the agent is showing
+    Answer(DialogAnswer),
+    /// Type a message and press Enter
+    Text(String),
+}
+
+/// Send a reply to the agent of a session.
+pub fn send_reply(session: &AgentSession, reply: &QuickReply) -> Result<(), AgentError> {
+    let backend = session_backend();
+    match reply {
+        QuickReply::Answer(answer) => {
+            let keys = get_agent(&session.agent_type).answer_keys(*answer);
+            backend.send_keys(&session.session_name(), &keys)
+        }
+        QuickReply::Text(text) => backend.send_message(&session.session_name(), text),
+    }
+}
+
+/// Sessions waiting for input, in the given project if any, oldest first.
+pub fn awaiting_sessions(project: Option<&str>) -> Vec<AgentSession> {
+    let mut sessions: Vec<AgentSession> = SessionManager::load()
+        .list()
+        .iter()
+        .filter(|s| s.is_awaiting() && project.is_none_or(|p| s.project == p))
+        .cloned()
+        .collect();
+    sessions.sort_by_key(|s| s.started_at);
+    sessions
+}
+
+/// Last `count` lines of a pane, without the blank lines at its bottom.
+pub fn pane_tail(pane: &str, count: usize) -> String {
+    let lines: Vec<&str> = pane.trim_end().lines().collect();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
+
+/// Numbered options of the dialog at the bottom of a pane, such as `❯ 1. Yes`.
+pub fn dialog_options(pane: &str) -> Vec<(u8, String)> {
+    let option = Regex::new(r"^[\s│|]*(?:[❯›>]\s*)?([1-9])[.)]\s+(.*\S)").unwrap();
+    let mut options: Vec<(u8, String)> = Vec::new();
+    for line in pane_tail(pane, PANE_LINES).lines() {
+        let Some(captures) = option.captures(line) else {
+            continue;
+        };
+        let number = captures[1].parse().unwrap_or(0);
+        let label = captures[2].trim_end_matches(['│', '|']).trim().to_string();
+        // A new list starting at 1 replaces an earlier one, e.g. in the agent's answer
+        if number == 1 {
+            options.clear();
+        }
+        options.push((number, label));
+    }
+    options
+}
+
+/// Quick reply popup over the issue or worktree list
+#[derive(Debug, Clone)]
+pub struct QuickReplyPopup {
+    /// Sessions waiting for input, replied to in turn
+    pub sessions: Vec<AgentSession>,
+    pub index: usize,
+    /// Last lines of the current session's pane
+    pub pane: String,
+    pub dialog: Option<AgentDialog>,
+    pub options: Vec<(u8, String)>,
+    /// Free-text reply being typed
+    pub input: Option<String>,
+    /// Return to worktree list instead of issue list
+    pub return_to_worktrees: bool,
+}
+
+impl QuickReplyPopup {
+    /// Open on `sessions`, starting with the one with id `first`, if any.
+    pub fn new(
+        sessions: Vec<AgentSession>,
+        first: Option<&str>,
+        return_to_worktrees: bool,
+    ) -> Option<Self> {
+        if sessions.is_empty() {
+            return None;
+        }
+        let index = first
+            .and_then(|id| sessions.iter().position(|s| s.id == id))
+            .unwrap_or(0);
+        let mut popup = Self {
+            sessions,
+            index,
+            pane: String::new(),
+            dialog: None,
+            options: Vec::new(),
+            input: None,
+            return_to_worktrees,
+        };
+        popup.refresh();
+        Some(popup)
+    }
+
+    pub fn session(&self) -> &AgentSession {
+        &self.sessions[self.index]
+    }
+
+    /// Capture the current session's pane again.
+    pub fn refresh(&mut self) {
+        let pane = session_backend()
+            .capture(&self.session().session_name())
+            .unwrap_or_default();
+        self.show(&pane);
+    }
+
+    /// Show a captured pane and the dialog it ends with.
+    fn show(&mut self, pane: &str) {
+        self.dialog = get_agent(&self.session().agent_type).dialog(pane);
+        self.options = if self.dialog.is_some() {
+            dialog_options(pane)
+        } else {
+            Vec::new()
+        };
+        self.pane = pane_tail(pane, PANE_LINES);
+    }
+
+    /// Move to the next (or previous) awaiting session, wrapping around.
+    pub fn cycle(&mut self, forward: bool) {
+        let count = self.sessions.len();
+        self.index = if forward {
+            (self.index + 1) % count
+        } else {
+            (self.index + count - 1) % count
+        };
+        self.input = None;
+        self.refresh();
+    }
+
+    /// Drop the session just replied to and move to the next one.
+    /// Returns false when no session is left.
+    pub fn advance(&mut self) -> bool {
+        self.sessions.remove(self.index);
+        self.input = None;
+        if self.sessions.is_empty() {
+            return false;
+        }
+        if self.index >= self.sessions.len() {
+            self.index = 0;
+        }
+        self.refresh();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_come_from_last_numbered_list() {
+        let pane = "Steps:\n1. Read the code\n2. Fix it\n\n\
+            ╭──────────────────────────────╮\n\
+            │ Do you want to proceed?      │\n\
+            │ ❯ 1. Yes                     │\n\
+            │   2. Yes, and don't ask again │\n\
+            │   3. No (esc)                │\n\
+            ╰──────────────────────────────╯\n\
+              Esc to cancel\n\n\n";
+        assert_eq!(
+            dialog_options(pane),
+            vec![
+                (1, "Yes".to_string()),
+                (2, "Yes, and don't ask again".to_string()),
+                (3, "No (esc)".to_string()),
+            ]
+        );
+        assert!(dialog_options("Working...\n").is_empty());
+        assert_eq!(pane_tail("a\nb\nc\n\n", 2), "b\nc");
+    }
+}
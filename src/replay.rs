@@ -0,0 +1,161 @@
+//! Replay of recorded agent sessions through a vt100 parser.
+
+use std::path::Path;
+use std::time::Instant;
+
+use vt100::Parser;
+
+use crate::agents::Cast;
+use crate::embedded_term::{screen_cells, StyledCell};
+
+/// Playback speeds, cycled with `+` and `-`
+const SPEEDS: [f64; 7] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0];
+
+/// Playback state of a recording
+pub struct Replay {
+    pub session_id: String,
+    cast: Cast,
+    parser: Parser,
+    /// Index of the next event to play
+    next: usize,
+    /// Playback time, in seconds
+    pub position: f64,
+    pub playing: bool,
+    speed: usize,
+    /// When playback time last advanced
+    last_tick: Option<Instant>,
+}
+
+impl Replay {
+    /// Load a recording and start playing it.
+    pub fn open(session_id: &str, path: &Path) -> Result<Self, String> {
+        let cast = Cast::load(path)?;
+        if cast.events.is_empty() {
+            return Err("The recording is empty".to_string());
+        }
+        Ok(Self {
+            session_id: session_id.to_string(),
+            parser: Parser::new(cast.height, cast.width, 0),
+            cast,
+            next: 0,
+            position: 0.0,
+            playing: true,
+            speed: 2,
+            last_tick: None,
+        })
+    }
+
+    /// Advance playback by the time elapsed since the last tick.
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        if let Some(last) = self.last_tick.replace(now)
+            && self.playing
+        {
+            let elapsed = now.duration_since(last).as_secs_f64() * self.speed();
+            self.seek_to(self.position + elapsed);
+        }
+        if self.position >= self.duration() {
+            self.playing = false;
+        }
+    }
+
+    /// Play the recording up to `position`, from the start when going back.
+    pub fn seek_to(&mut self, position: f64) {
+        let position = position.clamp(0.0, self.duration());
+        if position < self.position {
+            self.parser = Parser::new(self.cast.height, self.cast.width, 0);
+            self.next = 0;
+        }
+        while let Some((time, data)) = self.cast.events.get(self.next)
+            && *time <= position
+        {
+            self.parser.process(data.as_bytes());
+            self.next += 1;
+        }
+        self.position = position;
+    }
+
+    /// Pause, or resume playing (from the start when at the end).
+    pub fn toggle_play(&mut self) {
+        if !self.playing && self.position >= self.duration() {
+            self.seek_to(0.0);
+        }
+        self.playing = !self.playing;
+    }
+
+    /// Switch to the next faster (`true`) or slower playback speed.
+    pub fn change_speed(&mut self, faster: bool) {
+        self.speed = if faster {
+            (self.speed + 1).min(SPEEDS.len() - 1)
+        } else {
+            self.speed.saturating_sub(1)
+        };
+    }
+
+    pub fn speed(&self) -> f64 {
+        SPEEDS[self.speed]
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.cast.duration()
+    }
+
+    /// The terminal screen at the current position
+    pub fn screen(&self) -> Vec<Vec<StyledCell>> {
+        screen_cells(self.parser.screen())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay(events: &[(f64, &str)]) -> Replay {
+        let cast = Cast {
+            width: 20,
+            height: 3,
+            events: events.iter().map(|(t, d)| (*t, d.to_string())).collect(),
+        };
+        Replay {
+            session_id: "a".to_string(),
+            parser: Parser::new(cast.height, cast.width, 0),
+            cast,
+            next: 0,
+            position: 0.0,
+            playing: true,
+            speed: 2,
+            last_tick: None,
+        }
+    }
+
+    fn first_row(replay: &Replay) -> String {
+        replay.screen()[0].iter().map(|c| c.content.as_str()).collect::<String>()
+    }
+
+    #[test]
+    fn replay_seeks_forward_and_back() {
+        let mut replay = replay(&[(0.0, "one"), (1.0, "\r\x1b[1mtwo"), (2.5, "\rthree")]);
+        replay.seek_to(1.2);
+        assert_eq!(first_row(&replay).trim_end(), "two");
+        assert!(replay.screen()[0][0].bold);
+
+        replay.seek_to(0.5);
+        assert_eq!(first_row(&replay).trim_end(), "one");
+
+        replay.seek_to(10.0);
+        assert_eq!(replay.position, 2.5);
+        assert!(first_row(&replay).starts_with("three"));
+
+        replay.playing = false;
+        replay.toggle_play();
+        assert!(replay.playing);
+        assert_eq!(replay.position, 0.0);
+
+        replay.change_speed(true);
+        assert_eq!(replay.speed(), 2.0);
+        for _ in 0..10 {
+            replay.change_speed(false);
+        }
+        assert_eq!(replay.speed(), 0.25);
+    }
+}
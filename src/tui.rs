@@ -37,6 +37,13 @@ use fuzzy_matcher::FuzzyMatcher;
 use ratatui::{backend::CrosstermBackend, widgets::ListState, Terminal};
 use std::io;
 
+/// A dispatch running in the background, while its worktree is created and set up
+pub struct PendingDispatch {
+    pub issue_number: u64,
+    /// Status message to show once done, or the error
+    pub result: std::sync::mpsc::Receiver<Result<String, String>>,
+}
+
 /// Main TUI state
 pub struct IssueBrowser {
     pub all_issues: Vec<IssueSummary>,
@@ -60,6 +67,8 @@ pub struct IssueBrowser {
     pub list_state_filter: crate::list::IssueState,
     // Assignees cache
     pub available_assignees: Vec<String>,
+    // Reviewer suggestions cache (CODEOWNERS first, then contributors)
+    pub available_reviewers: Vec<String>,
     // Project info for Claude Code dispatch
     pub project_name: Option<String>,
     pub local_path: Option<std::path::PathBuf>,
@@ -70,8 +79,14 @@ pub struct IssueBrowser {
     pub session_cache: std::collections::HashMap<u64, crate::agents::AgentSession>,
     // Embedded terminal for tmux sessions
     pub embedded_term: Option<crate::embedded_term::EmbeddedTerminal>,
-    // Last session cache refresh time
-    pub last_session_refresh: std::time::Instant,
+    // Session changes pushed by the session monitor
+    pub session_events: Option<std::sync::mpsc::Receiver<crate::agents::SessionEvent>>,
+    // Worktree setup progress of dispatches, shown in the status bar
+    pub setup_output: Option<std::sync::mpsc::Receiver<crate::agents::SetupOutput>>,
+    // Bells of notifications, rung between frames
+    pub bells: Option<std::sync::mpsc::Receiver<String>>,
+    // Dispatches still creating their worktree
+    pub dispatches: Vec<PendingDispatch>,
     // Project labels for issue creation
     pub project_labels: Vec<String>,
     // Available commands for command palette
@@ -164,13 +179,17 @@ impl IssueBrowser {
             list_labels,
             list_state_filter,
             available_assignees: Vec::new(),
+            available_reviewers: Vec::new(),
             project_name: None,
             local_path: None,
             base_branch: None,
             selected_issues: std::collections::HashSet::new(),
             session_cache: std::collections::HashMap::new(),
             embedded_term: None,
-            last_session_refresh: std::time::Instant::now(),
+            session_events: None,
+            setup_output: None,
+            bells: None,
+            dispatches: Vec::new(),
             project_labels: Vec::new(),
             available_commands: Vec::new(),
             last_esc_press: None,
@@ -226,12 +245,17 @@ impl IssueBrowser {
         let mut worktrees = crate::agents::list_worktrees();
         for wt in &mut worktrees {
             wt.has_session = session_worktrees.contains(&wt.path);
+            let latest = manager.list().iter().rev().find(|s| s.worktree_path == wt.path);
+            if let Some(session) = latest {
+                wt.session_id = Some(session.id.clone());
+                wt.verification = session.verification.clone();
+            }
             if let Some(issue_num) = wt.issue_number {
-                let tmux_name = crate::agents::tmux_session_name(&wt.project, issue_num);
-                wt.has_tmux = crate::agents::is_tmux_session_running(&tmux_name);
+                let tmux_name = crate::agents::issue_session_name(&wt.project, issue_num);
+                wt.has_tmux = crate::agents::is_session_running(&tmux_name);
             } else {
                 // Standalone worktree: session name is the worktree name
-                wt.has_tmux = crate::agents::is_tmux_session_running(&wt.name);
+                wt.has_tmux = crate::agents::is_session_running(&wt.name);
             }
         }
         worktrees
@@ -283,6 +307,7 @@ impl IssueBrowser {
         self.list_labels.clear();
 
         // Clear PR cache
+        self.available_reviewers.clear();
         self.all_pull_requests.clear();
         self.pull_requests.clear();
         self.pr_list_state = ListState::default();
@@ -376,12 +401,36 @@ impl IssueBrowser {
             .collect()
     }
 
+    /// Agent session working on a pull request: dispatched for the PR itself,
+    /// or an issue session that opened it or works on its branch.
+    pub fn pr_session(
+        &self,
+        pr: &crate::github::PullRequestSummary,
+    ) -> Option<&crate::agents::AgentSession> {
+        self.session_cache.get(&pr.number).or_else(|| {
+            self.session_cache.values().find(|s| {
+                s.pr_url.as_deref() == Some(pr.html_url.as_str()) || s.branch_name == pr.head_ref
+            })
+        })
+    }
+
+    /// Linked issues and repository guidance files for the prompt of a dispatched issue
+    pub async fn prompt_context(&self, issue: &IssueDetail) -> crate::agents::PromptContext {
+        let config = crate::config::load_config().unwrap_or_default();
+        let local_path = self.local_path.as_deref();
+        crate::agents::gather_prompt_context(&self.github, issue, local_path, &config.prompt).await
+    }
+
+    /// Prompt of a dispatched issue, with its context and optional additional instructions
+    pub async fn dispatch_prompt(&self, issue: &IssueDetail, instructions: Option<&str>) -> String {
+        let config = crate::config::load_config().unwrap_or_default();
+        let context = self.prompt_context(issue).await;
+        crate::agents::build_dispatch_prompt(issue, &config.prompt, &context, instructions)
+    }
+
     /// Refresh session cache for the current project
     pub fn refresh_sessions(&mut self, project: &str) {
-        let mut manager = crate::agents::SessionManager::load();
-        if manager.sync_with_tmux() {
-            let _ = manager.save();
-        }
+        let manager = crate::agents::SessionManager::load();
         self.session_cache.clear();
         for session in manager.list() {
             if session.project == project {
@@ -390,37 +439,129 @@ impl IssueBrowser {
         }
     }
 
-    /// Refresh session cache with fresh stats calculated from git
+    /// Refresh session cache and ask the monitor for fresh stats, which arrive as events
     pub fn refresh_sessions_with_fresh_stats(&mut self, project: &str) {
-        let mut manager = crate::agents::SessionManager::load();
+        crate::agents::refresh_session_stats();
+        self.refresh_sessions(project);
+    }
 
-        let running_sessions: Vec<_> = manager
-            .running()
-            .iter()
-            .map(|s| (s.id.clone(), s.worktree_path.clone()))
-            .collect();
+    /// Show the dispatch queue, keeping the selection within it.
+    pub fn open_dispatch_queue(&mut self, selected: usize) {
+        let manager = crate::agents::SessionManager::load();
+        let queued: Vec<_> = manager.queued().into_iter().cloned().collect();
+        let max = crate::config::load_config()
+            .unwrap_or_default()
+            .max_concurrent_sessions();
+        self.view = TuiView::DispatchQueue {
+            selected: selected.min(queued.len().saturating_sub(1)),
+            running: manager.running().len(),
+            queued,
+            max,
+        };
+    }
 
-        for (session_id, worktree_path) in running_sessions {
-            let (lines_added, lines_deleted, files_changed) =
-                crate::agents::get_diff_stats(&worktree_path);
-            let stats = crate::agents::AgentStats {
-                lines_output: 0,
-                lines_added,
-                lines_deleted,
-                files_changed,
-            };
-            manager.update_stats(&session_id, stats);
-        }
-        let _ = manager.save();
+    /// Run a dispatch in the background, so that worktree setup output streams to the
+    /// status bar meanwhile. The dispatch resolves to the status message to show.
+    pub fn spawn_dispatch(
+        &mut self,
+        issue_number: u64,
+        dispatch: impl std::future::Future<Output = Result<String, String>> + Send + 'static,
+    ) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime = tokio::runtime::Handle::current();
+        std::thread::spawn(move || {
+            let _ = tx.send(runtime.block_on(dispatch));
+        });
+        self.status_message = Some(format!("Dispatching #{}...", issue_number));
+        self.dispatches.push(PendingDispatch {
+            issue_number,
+            result: rx,
+        });
+    }
+
+    /// Whether a dispatch of the issue is still running in the background
+    pub fn is_dispatching(&self, issue_number: u64) -> bool {
+        self.dispatches.iter().any(|d| d.issue_number == issue_number)
+    }
+
+    /// Show the latest worktree setup output, then the outcome of finished dispatches.
+    pub fn apply_dispatch_progress(&mut self) {
+        use std::sync::mpsc::TryRecvError;
 
-        if manager.sync_with_tmux() {
-            let _ = manager.save();
+        if let Some(output) = self.setup_output.as_ref().and_then(|o| o.try_iter().last()) {
+            self.status_message = Some(format!("Setting up {}: {}", output.worktree, output.line));
+        }
+        let mut finished = Vec::new();
+        self.dispatches.retain(|dispatch| match dispatch.result.try_recv() {
+            Ok(result) => {
+                finished.push(result);
+                false
+            }
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => false,
+        });
+        if finished.is_empty() {
+            return;
+        }
+        for result in finished {
+            self.status_message =
+                Some(result.unwrap_or_else(|e| format!("Failed to dispatch: {}", e)));
         }
+        if let Some(project) = self.project_name.clone() {
+            self.refresh_sessions(&project);
+        }
+    }
 
-        self.session_cache.clear();
-        for session in manager.list() {
-            if session.project == project {
-                self.session_cache.insert(session.issue_number, session.clone());
+    /// Apply the session changes received from the monitor
+    pub fn apply_session_events(&mut self) {
+        use crate::agents::SessionEvent;
+
+        let Some(events) = &self.session_events else {
+            return;
+        };
+        let events: Vec<SessionEvent> = events.try_iter().collect();
+        // Queued dispatches start or fail as sessions change
+        let changed = events
+            .iter()
+            .any(|e| !matches!(e, SessionEvent::Bell { .. } | SessionEvent::Setup { .. }));
+        if changed
+            && let TuiView::DispatchQueue { selected, .. } = self.view
+        {
+            self.open_dispatch_queue(selected);
+        }
+        for event in events {
+            match event {
+                SessionEvent::Added { session } => {
+                    if self.project_name.as_deref() == Some(session.project.as_str()) {
+                        self.session_cache.insert(session.issue_number, *session);
+                    }
+                }
+                SessionEvent::StatsUpdated { id, stats } => {
+                    if let Some(session) = self.session_cache.values_mut().find(|s| s.id == id) {
+                        session.stats = stats;
+                    }
+                }
+                SessionEvent::StatusChanged { id, status } => {
+                    if let Some(session) = self.session_cache.values_mut().find(|s| s.id == id) {
+                        session.status = status;
+                    }
+                }
+                SessionEvent::Bell { sequence } => ring_bell(&sequence),
+                SessionEvent::Setup { worktree, line } => {
+                    self.status_message = Some(format!("Setting up {}: {}", worktree, line));
+                }
+                SessionEvent::Verified { id, verification } => {
+                    if let TuiView::WorktreeList { worktrees, .. } = &mut self.view {
+                        for wt in worktrees.iter_mut() {
+                            if wt.session_id.as_deref() == Some(id.as_str()) {
+                                wt.verification = Some(verification.clone());
+                            }
+                        }
+                    }
+                    if let Some(session) = self.session_cache.values_mut().find(|s| s.id == id) {
+                        session.verification = Some(verification);
+                    }
+                }
             }
         }
     }
@@ -576,6 +717,32 @@ impl IssueBrowser {
         scored.into_iter().map(|(_, name)| name.clone()).collect()
     }
 
+    /// Load reviewer suggestions from CODEOWNERS and contributors
+    pub async fn load_reviewers(&mut self) {
+        if self.available_reviewers.is_empty()
+            && let Ok(reviewers) = self.github.suggest_reviewers().await
+        {
+            self.available_reviewers = reviewers;
+        }
+    }
+
+    /// Get filtered reviewer suggestions based on input (fuzzy matching)
+    pub fn get_reviewer_suggestions(&self, input: &str) -> Vec<String> {
+        if input.is_empty() {
+            return self.available_reviewers.clone();
+        }
+
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<(i64, &String)> = self
+            .available_reviewers
+            .iter()
+            .filter_map(|name| matcher.fuzzy_match(name, input).map(|score| (score, name)))
+            .collect();
+
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, name)| name.clone()).collect()
+    }
+
     /// Extract image URLs from issue content
     pub fn extract_images_from_issue(&mut self, issue: &IssueDetail) {
         let mut images = Vec::new();
@@ -827,17 +994,31 @@ impl IssueBrowser {
         }
     }
 
-    /// Create worktree for a branch (used for PR checkout)
-    pub fn create_worktree_for_branch(
+    /// Create worktree for a pull request by number, fetching `pull/<n>/head`.
+    /// Works for fork PRs and for summaries without a head ref.
+    pub fn create_worktree_for_pr(
         &self,
-        branch: &str,
+        pr: &crate::github::PullRequestDetail,
     ) -> Result<(std::path::PathBuf, String), String> {
         let local_path = self.local_path.as_ref().ok_or("No local_path configured")?;
         let project_name = self.project_name.as_deref().ok_or("No project configured")?;
-        let base_branch = self.base_branch.as_deref();
 
-        crate::agents::create_worktree_with_branch(local_path, project_name, branch, base_branch)
-            .map_err(|e| e.to_string())
+        let base_repo = format!("{}/{}", self.github.owner, self.github.repo);
+        let push_target = crate::agents::PushTarget::for_pull_request(
+            &base_repo,
+            pr.head_repo.as_deref(),
+            pr.head_clone_url.as_deref(),
+            &pr.head_ref,
+            pr.maintainer_can_modify,
+        );
+
+        crate::agents::create_worktree_for_pr(
+            local_path,
+            project_name,
+            pr.number,
+            push_target.as_ref(),
+        )
+        .map_err(|e| e.to_string())
     }
 
     /// Dispatch agent for a worktree with instructions
@@ -849,10 +1030,11 @@ impl IssueBrowser {
         let session_name = format!("pr-review-{}", uuid::Uuid::new_v4().to_string().split('-').next().unwrap_or("unknown"));
 
         // Launch agent interactively with tmux
+        let project = self.project_name.as_deref().unwrap_or_default();
         crate::agents::launch_agent_interactive(
             worktree_path,
             &session_name,
-            &self.coding_agent,
+            &crate::agents::resolve_agent_profile(project, &[], &self.coding_agent),
             Some(instructions),
         )
         .map_err(|e| e.to_string())?;
@@ -861,6 +1043,11 @@ impl IssueBrowser {
     }
 }
 
+/// Write a bell sequence to the terminal between frames.
+fn ring_bell(sequence: &str) {
+    let _ = execute!(io::stdout(), crossterm::style::Print(sequence));
+}
+
 /// Run the TUI application
 pub async fn run_issue_browser(
     issues: Vec<IssueSummary>,
@@ -943,18 +1130,19 @@ pub async fn run_issue_browser_with_pagination(
     browser.set_ide_command(ide_command);
     browser.set_coding_agent(coding_agent);
 
-    // Resume monitoring threads for any running sessions from previous process
-    crate::agents::resume_monitoring_for_running_sessions();
+    // Monitor running sessions, including those from a previous process
+    if crate::agents::start_session_monitor() {
+        browser.status_message = Some("Connected to session daemon".to_string());
+    }
+    browser.session_events = Some(crate::agents::subscribe_session_events());
+    browser.setup_output = Some(crate::agents::subscribe_setup_output());
+    browser.bells = Some(crate::notify::subscribe_bells());
 
     while !browser.should_quit {
-        // Auto-refresh session cache every 2 seconds when in List view
-        if matches!(browser.view, TuiView::List)
-            && browser.last_session_refresh.elapsed() >= std::time::Duration::from_secs(2)
-        {
-            if let Some(project) = browser.project_name.clone() {
-                browser.refresh_sessions(&project);
-            }
-            browser.last_session_refresh = std::time::Instant::now();
+        browser.apply_session_events();
+        browser.apply_dispatch_progress();
+        for sequence in browser.bells.iter().flat_map(|b| b.try_iter()) {
+            ring_bell(&sequence);
         }
 
         terminal.draw(|f| draw_ui(f, &mut browser))?;
@@ -4,11 +4,18 @@ use std::path::Path;
 
 use std::collections::HashSet;
 
+use crate::agents::{AgentDialog, VerifyStatus};
 use crate::commands::{format_status_bar, generate_full_help, CommandContext};
-use crate::github::{IssueDetail, PullRequestDetail};
+use crate::embedded_term::StyledCell;
+use crate::github::{IssueDetail, PullRequestDetail, ReviewEvent};
 use crate::issues::IssueContent;
+use crate::log_view::LogView;
 use crate::markdown::{parse_markdown_content, render_markdown_line};
-use crate::tui_types::{CommandSuggestion, CreateStage, IssueFilterFocus, IssueStatus, PrFilterFocus, PrStatus, TuiView};
+use crate::plan::{PlanField, PlanReview};
+use crate::pr_review::ReviewFindings;
+use crate::quick_reply::QuickReplyPopup;
+use crate::replay::Replay;
+use crate::tui_types::{CommandSuggestion, CreateStage, IssueFilterFocus, IssueStatus, NewPullRequest, PrCreateField, PrEditField, PrFilterFocus, PrStatus, TuiView};
 use crate::tui_utils::{format_date, truncate_str};
 
 use ratatui::{
@@ -25,20 +32,14 @@ use crate::tui::IssueBrowser;
 pub fn draw_ui(f: &mut Frame, browser: &mut IssueBrowser) {
     let image_count = browser.current_images.len();
 
-    // Auto-refresh log content if viewing running agent logs
-    if let TuiView::AgentLogs {
-        session_id,
-        content,
-        ..
-    } = &mut browser.view
-    {
-        let manager = crate::agents::SessionManager::load();
-        if let Some(session) = manager.get(session_id)
-            && session.is_running()
-                && let Ok(new_content) = std::fs::read_to_string(&session.log_file)
-        {
-            *content = new_content;
-        }
+    // Pick up what the agent appended to its transcript
+    if let TuiView::AgentLogs(log) = &mut browser.view {
+        log.reload();
+        let search_row = u16::from(log.search_input.is_some());
+        log.set_page(f.area().height.saturating_sub(2 + search_row) as usize);
+    }
+    if let TuiView::Replay(replay) = &mut browser.view {
+        replay.tick();
     }
 
     // Clone status message to avoid borrow conflicts
@@ -52,12 +53,45 @@ pub fn draw_ui(f: &mut Frame, browser: &mut IssueBrowser) {
     };
 
     // Extract dispatch instructions data before match to avoid borrow conflicts
-    let dispatch_instructions_data =
-        if let TuiView::DispatchInstructions { issue, input } = &browser.view {
-            Some((issue.number, input.clone()))
-        } else {
-            None
-        };
+    let dispatch_instructions_data = if let TuiView::DispatchInstructions {
+        issue,
+        input,
+        profiles,
+        profile,
+        attempts,
+        plan_first,
+        ..
+    } = &browser.view
+    {
+        let summary = profiles.get(*profile).map(|p| p.summary()).unwrap_or_default();
+        let attempts: Vec<String> = attempts
+            .iter()
+            .filter_map(|&i| profiles.get(i).map(|p| p.summary()))
+            .collect();
+        let resolved = *profile == 0;
+        Some((issue.number, input.clone(), summary, resolved, attempts, *plan_first))
+    } else {
+        None
+    };
+
+    // Final dispatch prompt being previewed, if any
+    let dispatch_prompt_preview = if let TuiView::DispatchInstructions {
+        issue,
+        prompt: Some(prompt),
+        ..
+    } = &browser.view
+    {
+        Some((issue.number, prompt.clone()))
+    } else {
+        None
+    };
+
+    // Extract the quick reply popup, drawn over the issue list
+    let quick_reply = if let TuiView::QuickReply(popup) = &browser.view {
+        Some(popup.clone())
+    } else {
+        None
+    };
 
     // Extract worktree agent instructions data before match to avoid borrow conflicts
     let worktree_instructions_data =
@@ -152,12 +186,49 @@ pub fn draw_ui(f: &mut Frame, browser: &mut IssueBrowser) {
                 &format!("Dispatch #{} to Claude Code? (y/n)", issue.number),
             );
         }
-        TuiView::AgentLogs {
-            session_id,
-            content,
-            scroll,
+        TuiView::AgentLogs(log) => {
+            draw_agent_logs(f, log);
+        }
+        TuiView::Replay(replay) => {
+            draw_replay(f, replay);
+        }
+        TuiView::CompareAttempts {
+            issue_number,
+            attempts,
+            selected,
+            confirm_keep,
+        } => {
+            draw_compare_attempts(f, *issue_number, attempts, *selected);
+            if *confirm_keep
+                && let Some(attempt) = attempts.get(*selected)
+            {
+                let chunks = Layout::vertical([Constraint::Min(3), Constraint::Length(3)])
+                    .split(f.area());
+                draw_confirmation(
+                    f,
+                    chunks[1],
+                    &format!(
+                        "Keep attempt {} and discard the {} others with their worktrees? (y/n)",
+                        attempt.session.attempt.as_deref().unwrap_or("?"),
+                        attempts.len() - 1
+                    ),
+                );
+            }
+        }
+        TuiView::DispatchQueue {
+            queued,
+            running,
+            max,
+            selected,
         } => {
-            draw_agent_logs(f, session_id, content, *scroll);
+            if let Some(ref msg) = status_msg {
+                let chunks = Layout::vertical([Constraint::Min(3), Constraint::Length(3)])
+                    .split(f.area());
+                draw_dispatch_queue(f, chunks[0], queued, *running, *max, *selected);
+                draw_status_bar(f, chunks[1], msg);
+            } else {
+                draw_dispatch_queue(f, f.area(), queued, *running, *max, *selected);
+            }
         }
         TuiView::EmbeddedTmux {
             available_sessions,
@@ -169,8 +240,8 @@ pub fn draw_ui(f: &mut Frame, browser: &mut IssueBrowser) {
         TuiView::ProjectSelect { projects, selected } => {
             draw_project_select_inline(f, projects, *selected);
         }
-        TuiView::AgentSelect { selected } => {
-            draw_agent_select(f, *selected, &browser.coding_agent);
+        TuiView::AgentSelect { agents, selected } => {
+            draw_agent_select(f, agents, *selected, &browser.coding_agent);
         }
         TuiView::Command {
             input,
@@ -241,8 +312,20 @@ pub fn draw_ui(f: &mut Frame, browser: &mut IssueBrowser) {
             // Draw the list behind the popup
             draw_list_view(f, browser);
             // Draw centered instructions popup on top
-            if let Some((issue_number, input)) = &dispatch_instructions_data {
-                draw_dispatch_instructions(f, *issue_number, input);
+            if let Some((issue_number, prompt)) = &dispatch_prompt_preview {
+                draw_dispatch_prompt_preview(f, *issue_number, prompt);
+            } else if let Some((issue_number, input, profile, resolved, attempts, plan_first)) =
+                &dispatch_instructions_data
+            {
+                draw_dispatch_instructions(
+                    f,
+                    *issue_number,
+                    input,
+                    profile,
+                    *resolved,
+                    attempts,
+                    *plan_first,
+                );
             }
         }
         TuiView::WorktreeAgentInstructions { .. } => {
@@ -278,6 +361,74 @@ pub fn draw_ui(f: &mut Frame, browser: &mut IssueBrowser) {
             draw_pr_list_view(f, browser);
             draw_pr_review_popup(f, &pr_clone, &input_clone);
         }
+        TuiView::PrReviewers {
+            pr,
+            input,
+            suggestions,
+            selected,
+        } => {
+            let chunks =
+                Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .split(f.area());
+
+            draw_pr_detail_view(f, chunks[0], pr, browser.scroll_offset);
+            draw_reviewer_picker(f, chunks[1], pr, input, suggestions, *selected);
+        }
+        TuiView::EditPullRequest {
+            pr,
+            title,
+            body,
+            base,
+            field,
+        } => {
+            draw_edit_pull_request(f, pr, title, body, base, *field, status_msg.as_deref());
+        }
+        TuiView::ConfirmClosePr { pr } => {
+            draw_pr_detail_view(f, f.area(), pr, browser.scroll_offset);
+            draw_confirm_pr_state_popup(f, pr, "Close");
+        }
+        TuiView::ConfirmReopenPr { pr } => {
+            draw_pr_detail_view(f, f.area(), pr, browser.scroll_offset);
+            draw_confirm_pr_state_popup(f, pr, "Reopen");
+        }
+        TuiView::ReviewFindings {
+            pr,
+            findings,
+            selected,
+            edit,
+            event,
+        } => {
+            draw_review_findings(f, pr, findings, *selected, *event, status_msg.as_deref());
+            if let Some(buffer) = edit {
+                draw_finding_edit_popup(f, findings, *selected, buffer);
+            }
+        }
+        TuiView::PlanReview(review) => {
+            draw_plan_review(f, review, status_msg.as_deref());
+            if let Some((field, buffer)) = &review.editing {
+                draw_plan_edit_popup(f, *field, buffer);
+            }
+        }
+        TuiView::QuickReply(_) => {
+            if let Some(popup) = &quick_reply {
+                // Coming from the worktree list, the popup is drawn on its own
+                if !popup.return_to_worktrees {
+                    draw_list_view(f, browser);
+                }
+                draw_quick_reply(f, popup, status_msg.as_deref());
+            }
+        }
+        TuiView::CreatePullRequest(form) => {
+            draw_create_pull_request(f, form, status_msg.as_deref());
+        }
+        TuiView::PrLabels {
+            pr,
+            labels,
+            selected,
+        } => {
+            draw_pr_detail_view(f, f.area(), pr, browser.scroll_offset);
+            draw_pr_labels_popup(f, pr, labels, *selected);
+        }
         TuiView::PrFilters {
             status_filter,
             author_filter,
@@ -366,6 +517,7 @@ pub fn draw_list_view_in_area(f: &mut Frame, browser: &mut IssueBrowser, area: R
             let (session_icon, session_color, session_stats) = match session_info {
                 Some(session) => {
                     let (icon, color) = match &session.status {
+                        AgentStatus::Queued => ("◷", Color::Gray),
                         AgentStatus::Running => ("▶", Color::Yellow),
                         AgentStatus::Awaiting => ("⏸", Color::Cyan),
                         AgentStatus::Completed { .. } | AgentStatus::Failed { .. } => {
@@ -421,11 +573,18 @@ pub fn draw_list_view_in_area(f: &mut Frame, browser: &mut IssueBrowser, area: R
             } else {
                 let session_span = if let Some(icon) = session_icon {
                     Span::styled(
-                        format!("{}{}  ", icon, session_stats),
+                        format!("{}{} ", icon, session_stats),
                         Style::default().fg(session_color),
                     )
                 } else {
-                    Span::raw("    ")
+                    Span::raw("   ")
+                };
+                let verify_span = match session_info.and_then(|s| s.verification.as_ref()) {
+                    Some(verification) => {
+                        let (badge, color) = verification_badge(verification);
+                        Span::styled(format!("{} ", badge), Style::default().fg(color))
+                    }
+                    None => Span::raw(" "),
                 };
 
                 Line::from(vec![
@@ -442,6 +601,7 @@ pub fn draw_list_view_in_area(f: &mut Frame, browser: &mut IssueBrowser, area: R
                         Style::default().fg(Color::Cyan),
                     ),
                     session_span,
+                    verify_span,
                     Span::raw(&issue.title),
                     Span::styled(labels_str, Style::default().fg(Color::DarkGray)),
                     Span::styled(assignees_str, Style::default().fg(Color::Magenta)),
@@ -771,27 +931,279 @@ pub fn draw_assignee_picker(
 }
 
 /// Draw agent logs view
-pub fn draw_agent_logs(f: &mut Frame, session_id: &str, content: &str, scroll: u16) {
-    let lines: Vec<Line> = content
-        .lines()
-        .map(|line| Line::from(line.to_string()))
+pub fn draw_agent_logs(f: &mut Frame, log: &LogView) {
+    let query = log.query.to_lowercase();
+    let lines: Vec<Line> = log
+        .lines
+        .iter()
+        .enumerate()
+        .skip(log.scroll)
+        .take(log.page)
+        .map(|(i, line)| {
+            let mut style = if crate::ci::is_error_line(line) {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            };
+            if log.cursor == Some(i) {
+                style = style.bg(Color::DarkGray).add_modifier(Modifier::BOLD);
+            }
+            log_line_spans(line, &query, style)
+        })
         .collect();
 
-    let short_id: String = session_id.chars().take(8).collect();
-    let title = format!(" Agent {} │ ↑↓ scroll │ q back ", short_id);
+    let short_id: String = log.session_id.chars().take(8).collect();
+    let mut title = format!(" Agent {} │ {} lines", short_id, log.lines.len());
+    if log.follow {
+        title.push_str(" │ FOLLOW");
+    }
+    if !log.query.is_empty() {
+        match log.match_position() {
+            Some(n) => title.push_str(&format!(" │ /{} {}/{}", log.query, n, log.matches.len())),
+            None => title.push_str(&format!(" │ /{} ({})", log.query, log.matches.len())),
+        }
+    }
+    title.push_str(" │ / search n/N match e/E error f follow p replay q back ");
 
-    let text = Text::from(lines);
-    let paragraph = Paragraph::new(text)
+    let area = f.area();
+    let chunks = if log.search_input.is_some() {
+        Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).split(area)
+    } else {
+        Layout::vertical([Constraint::Min(3)]).split(area)
+    };
+
+    let paragraph = Paragraph::new(Text::from(lines))
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .title(title)
                 .border_style(Style::default().fg(Color::Yellow)),
         )
-        .wrap(Wrap { trim: false })
-        .scroll((scroll, 0));
+        .scroll((0, log.hscroll.min(u16::MAX as usize) as u16));
+    f.render_widget(paragraph, chunks[0]);
+
+    if let Some(input) = &log.search_input {
+        let prompt = Paragraph::new(format!("/{}█", input))
+            .style(Style::default().bg(Color::DarkGray).fg(Color::White));
+        f.render_widget(prompt, chunks[1]);
+    }
+}
+
+/// Draw the replay of a recorded session
+pub fn draw_replay(f: &mut Frame, replay: &Replay) {
+    let chunks = Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).split(f.area());
+
+    let short_id: String = replay.session_id.chars().take(8).collect();
+    let state = if replay.playing { "▶" } else { "⏸" };
+    let header = format!(
+        " Replay {} │ {} {:.0}s / {:.0}s │ {}x │ space play/pause ←→ seek +/- speed q back ",
+        short_id,
+        state,
+        replay.position,
+        replay.duration(),
+        replay.speed()
+    );
+    let header =
+        Paragraph::new(header).style(Style::default().bg(Color::DarkGray).fg(Color::White));
+    f.render_widget(header, chunks[0]);
+
+    let screen = Paragraph::new(Text::from(styled_cell_lines(replay.screen())));
+    f.render_widget(screen, chunks[1]);
+}
+
+/// Draw attempts at an issue side by side: their agents and diff stats, and the files
+/// changed by the selected one (files no other attempt touched are highlighted).
+pub fn draw_compare_attempts(
+    f: &mut Frame,
+    issue_number: u64,
+    attempts: &[crate::agents::AttemptSummary],
+    selected: usize,
+) {
+    use crate::agents::AgentStatus;
+
+    let rows = attempts.len() as u16 + 2;
+    let chunks = Layout::vertical([
+        Constraint::Length(rows),
+        Constraint::Min(3),
+        Constraint::Length(1),
+    ])
+    .split(f.area());
+
+    let items: Vec<ListItem> = attempts
+        .iter()
+        .enumerate()
+        .map(|(idx, attempt)| {
+            let session = &attempt.session;
+            let (icon, color) = match &session.status {
+                AgentStatus::Queued => ("◷", Color::Gray),
+                AgentStatus::Running => ("▶", Color::Yellow),
+                AgentStatus::Awaiting => ("⏸", Color::Cyan),
+                AgentStatus::Completed { .. } => ("●", Color::Blue),
+                AgentStatus::Failed { .. } => ("✗", Color::Red),
+            };
+            let added: usize = attempt.files.iter().map(|(_, a, _)| a).sum();
+            let deleted: usize = attempt.files.iter().map(|(_, _, d)| d).sum();
+            let line = Line::from(vec![
+                Span::styled(format!("{} ", icon), Style::default().fg(color)),
+                Span::styled(
+                    format!("{:<3}", session.attempt.as_deref().unwrap_or("?")),
+                    Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("{:<14}", session.agent_type.display_name()),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(format!("+{:<6}", added), Style::default().fg(Color::Green)),
+                Span::styled(format!("-{:<6}", deleted), Style::default().fg(Color::Red)),
+                Span::raw(format!("{:>3} files  ", attempt.files.len())),
+                Span::styled(session.branch_name.clone(), Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    format!("  {}", session.duration_str()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]);
+            let style = if idx == selected {
+                Style::default().bg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            ListItem::new(line).style(style)
+        })
+        .collect();
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Attempts at #{} ", issue_number))
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(list, chunks[0]);
+
+    let files: Vec<Line> = attempts
+        .get(selected)
+        .map(|attempt| {
+            attempt
+                .files
+                .iter()
+                .map(|(path, added, deleted)| {
+                    let unique = attempts.iter().enumerate().all(|(i, other)| {
+                        i == selected || !other.files.iter().any(|f| &f.0 == path)
+                    });
+                    let path_style = if unique {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default()
+                    };
+                    Line::from(vec![
+                        Span::styled(format!("+{:<5}", added), Style::default().fg(Color::Green)),
+                        Span::styled(format!("-{:<5}", deleted), Style::default().fg(Color::Red)),
+                        Span::styled(path.clone(), path_style),
+                    ])
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let title = if files.is_empty() {
+        " No changes ".to_string()
+    } else {
+        format!(" {} changed files (only in this attempt: yellow) ", files.len())
+    };
+    let files = Paragraph::new(files).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(files, chunks[1]);
+
+    let keys = "j/k: select │ Enter: keep attempt │ l: logs │ R: refresh │ q: back";
+    let hint = Paragraph::new(keys)
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+    f.render_widget(hint, chunks[2]);
+}
+
+/// Draw the dispatch queue, next dispatch to start first
+pub fn draw_dispatch_queue(
+    f: &mut Frame,
+    area: Rect,
+    queued: &[crate::agents::AgentSession],
+    running: usize,
+    max: usize,
+    selected: usize,
+) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Dispatch queue │ {}/{} agents running ", running, max))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([Constraint::Min(3), Constraint::Length(2)]).split(inner);
+
+    let keys = "j/k: select │ K/J: move up/down │ x: cancel │ R: refresh │ q: back";
+    let help = Paragraph::new(keys)
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+    f.render_widget(help, chunks[1]);
+
+    if queued.is_empty() {
+        let empty_msg = Paragraph::new("No queued dispatches. Select issues and press 'd'.")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        f.render_widget(empty_msg, chunks[0]);
+        return;
+    }
+
+    let items: Vec<ListItem> = queued
+        .iter()
+        .enumerate()
+        .map(|(idx, session)| {
+            let line = Line::from(vec![
+                Span::styled(format!("{:>3}. ", idx + 1), Style::default().fg(Color::DarkGray)),
+                Span::styled(
+                    format!("{}#{:<5}", session.project, session.issue_number),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(" "),
+                Span::raw(session.issue_title.clone()),
+                Span::styled(
+                    format!(
+                        "  {} · queued {}",
+                        session.agent_type.display_name(),
+                        session.duration_str()
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]);
+            let style = if idx == selected {
+                Style::default().bg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            ListItem::new(line).style(style)
+        })
+        .collect();
+
+    f.render_widget(List::new(items), chunks[0]);
+}
 
-    f.render_widget(paragraph, f.area());
+/// A log line with occurrences of `query` (lowercase) highlighted.
+fn log_line_spans(line: &str, query: &str, style: Style) -> Line<'static> {
+    let highlight = Style::default().bg(Color::Yellow).fg(Color::Black);
+    let lower = line.to_lowercase();
+    // Lowercasing can change byte offsets; only highlight when it does not
+    if query.is_empty() || lower.len() != line.len() {
+        return Line::from(Span::styled(line.to_string(), style));
+    }
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    for (start, _) in lower.match_indices(query) {
+        let end = start + query.len();
+        if start < pos || !line.is_char_boundary(start) || !line.is_char_boundary(end) {
+            continue;
+        }
+        spans.push(Span::styled(line[pos..start].to_string(), style));
+        spans.push(Span::styled(line[start..end].to_string(), highlight));
+        pos = end;
+    }
+    spans.push(Span::styled(line[pos..].to_string(), style));
+    Line::from(spans)
 }
 
 /// Draw embedded tmux terminal view
@@ -824,34 +1236,7 @@ pub fn draw_embedded_tmux(
 
     // Draw terminal content
     if let Some(ref term) = browser.embedded_term {
-        let screen = term.get_screen();
-        let mut lines: Vec<Line> = Vec::new();
-
-        for row in screen {
-            let mut spans: Vec<Span> = Vec::new();
-            for cell in row {
-                let mut style = Style::default().fg(cell.fg).bg(cell.bg);
-                if cell.bold {
-                    style = style.add_modifier(Modifier::BOLD);
-                }
-                if cell.underline {
-                    style = style.add_modifier(Modifier::UNDERLINED);
-                }
-                if cell.inverse {
-                    style = style.add_modifier(Modifier::REVERSED);
-                }
-                let content = if cell.content.is_empty() {
-                    " ".to_string()
-                } else {
-                    cell.content
-                };
-                spans.push(Span::styled(content, style));
-            }
-            lines.push(Line::from(spans));
-        }
-
-        let text = Text::from(lines);
-        let paragraph = Paragraph::new(text);
+        let paragraph = Paragraph::new(Text::from(styled_cell_lines(term.get_screen())));
         f.render_widget(paragraph, chunks[1]);
     } else {
         let placeholder = Paragraph::new("Starting terminal...")
@@ -861,6 +1246,36 @@ pub fn draw_embedded_tmux(
     }
 }
 
+/// Terminal screen cells as styled lines.
+fn styled_cell_lines(screen: Vec<Vec<StyledCell>>) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line> = Vec::new();
+
+    for row in screen {
+        let mut spans: Vec<Span> = Vec::new();
+        for cell in row {
+            let mut style = Style::default().fg(cell.fg).bg(cell.bg);
+            if cell.bold {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            if cell.underline {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            if cell.inverse {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            let content = if cell.content.is_empty() {
+                " ".to_string()
+            } else {
+                cell.content
+            };
+            spans.push(Span::styled(content, style));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines
+}
+
 /// Draw project selection inline
 pub fn draw_project_select_inline(f: &mut Frame, projects: &[String], selected: usize) {
     let area = f.area();
@@ -904,6 +1319,7 @@ pub fn draw_project_select_inline(f: &mut Frame, projects: &[String], selected:
 /// Draw agent selection screen
 pub fn draw_agent_select(
     f: &mut Frame,
+    agents: &[crate::config::CodingAgentType],
     selected: usize,
     current_agent: &crate::config::CodingAgentType,
 ) {
@@ -917,15 +1333,11 @@ pub fn draw_agent_select(
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    let agents = [
-        ("Claude Code", crate::config::CodingAgentType::Claude),
-        ("Opencode", crate::config::CodingAgentType::Opencode),
-    ];
-
     let items: Vec<ListItem> = agents
         .iter()
         .enumerate()
-        .map(|(i, (name, agent_type))| {
+        .map(|(i, agent_type)| {
+            let name = agent_type.display_name();
             let is_current = agent_type == current_agent;
             let style = if i == selected {
                 Style::default()
@@ -1313,6 +1725,15 @@ pub fn draw_direct_issue(
     f.render_widget(help, chunks[3]);
 }
 
+/// Badge for a session's verification outcome
+fn verification_badge(verification: &crate::agents::Verification) -> (&'static str, Color) {
+    match verification.status {
+        VerifyStatus::Running => ("⋯", Color::Yellow),
+        VerifyStatus::Passed => ("✓", Color::Green),
+        VerifyStatus::Failed => ("✗", Color::Red),
+    }
+}
+
 /// Draw worktree list view
 pub fn draw_worktree_list(
     f: &mut Frame,
@@ -1359,10 +1780,11 @@ pub fn draw_worktree_list(
                 Span::styled("○ ", Style::default().fg(Color::DarkGray))
             };
 
-            let issue_str = wt
-                .issue_number
-                .map(|n| format!("#{:<5}", n))
-                .unwrap_or_else(|| "     ".to_string());
+            let issue_str = match (wt.issue_number, wt.pr_number) {
+                (Some(n), _) => format!("#{:<5}", n),
+                (None, Some(n)) => format!("PR#{:<3}", n),
+                (None, None) => "     ".to_string(),
+            };
 
             let has_agent = wt.has_session || wt.has_tmux;
             let name_style = if is_selected {
@@ -1379,6 +1801,18 @@ pub fn draw_worktree_list(
             } else {
                 Span::raw("")
             };
+            let verify_indicator = match &wt.verification {
+                Some(verification) => {
+                    let (badge, color) = verification_badge(verification);
+                    let text = match verification.status {
+                        VerifyStatus::Running => "verifying".to_string(),
+                        VerifyStatus::Passed => "verified".to_string(),
+                        VerifyStatus::Failed => verification.command.clone(),
+                    };
+                    Span::styled(format!("  {} {}", badge, text), Style::default().fg(color))
+                }
+                None => Span::raw(""),
+            };
 
             let line = Line::from(vec![
                 status_icon,
@@ -1393,6 +1827,7 @@ pub fn draw_worktree_list(
                 Span::raw(" "),
                 Span::styled(&wt.name, name_style),
                 orphan_indicator,
+                verify_indicator,
             ]);
 
             let style = if is_selected {
@@ -1405,8 +1840,35 @@ pub fn draw_worktree_list(
         })
         .collect();
 
+    // Output of the selected worktree's last verification, below the list
+    let output = worktrees
+        .get(selected)
+        .and_then(|wt| wt.verification.as_ref())
+        .filter(|v| !v.is_running() && !v.output_tail.is_empty());
+    let list_area = match output {
+        Some(verification) => {
+            let lines = verification.output_tail.lines().count() as u16;
+            let height = lines.min(12) + 1;
+            let split = Layout::vertical([Constraint::Min(3), Constraint::Length(height)])
+                .split(chunks[0]);
+            let (_, color) = verification_badge(verification);
+            let block = Block::default()
+                .borders(Borders::TOP)
+                .title(format!(" {} ", verification.command))
+                .border_style(Style::default().fg(color));
+            // Keep the end of the output in view
+            let scroll = lines.saturating_sub(12);
+            let tail = Paragraph::new(verification.output_tail.as_str())
+                .block(block)
+                .scroll((scroll, 0));
+            f.render_widget(tail, split[1]);
+            split[0]
+        }
+        None => chunks[0],
+    };
+
     let list = List::new(items);
-    f.render_widget(list, chunks[0]);
+    f.render_widget(list, list_area);
 
     // Help bar
     let help = Paragraph::new(format_status_bar(CommandContext::WorktreeList, ""))
@@ -1449,10 +1911,11 @@ pub fn draw_confirm_prune(f: &mut Frame, orphaned: &[crate::agents::WorktreeInfo
     let items: Vec<ListItem> = orphaned
         .iter()
         .map(|wt| {
-            let issue_str = wt
-                .issue_number
-                .map(|n| format!("#{}", n))
-                .unwrap_or_default();
+            let issue_str = match (wt.issue_number, wt.pr_number) {
+                (Some(n), _) => format!("#{}", n),
+                (None, Some(n)) => format!("PR #{}", n),
+                (None, None) => String::new(),
+            };
 
             let line = Line::from(vec![
                 Span::styled("  • ", Style::default().fg(Color::Red)),
@@ -1503,10 +1966,11 @@ pub fn draw_confirm_delete_worktree(f: &mut Frame, worktree: &crate::agents::Wor
     f.render_widget(header, chunks[0]);
 
     // Worktree info
-    let issue_str = worktree
-        .issue_number
-        .map(|n| format!(" (#{}) ", n))
-        .unwrap_or_else(|| " ".to_string());
+    let issue_str = match (worktree.issue_number, worktree.pr_number) {
+        (Some(n), _) => format!(" (#{}) ", n),
+        (None, Some(n)) => format!(" (PR #{}) ", n),
+        (None, None) => " ".to_string(),
+    };
 
     let info = Paragraph::new(Line::from(vec![
         Span::styled("  • ", Style::default().fg(Color::Red)),
@@ -1652,12 +2116,20 @@ pub fn draw_help(f: &mut Frame) {
 }
 
 /// Draw instructions popup for dispatching an issue
-pub fn draw_dispatch_instructions(f: &mut Frame, issue_number: u64, input: &str) {
+pub fn draw_dispatch_instructions(
+    f: &mut Frame,
+    issue_number: u64,
+    input: &str,
+    profile: &str,
+    resolved: bool,
+    attempts: &[String],
+    plan_first: bool,
+) {
     let area = f.area();
 
-    // Calculate centered popup area (60% width, 10 lines tall)
+    // Calculate centered popup area (60% width, 13 lines tall)
     let popup_width = (area.width * 60 / 100).max(40).min(area.width.saturating_sub(4));
-    let popup_height = 10.min(area.height.saturating_sub(4));
+    let popup_height = 13.min(area.height.saturating_sub(4));
     let popup_x = (area.width.saturating_sub(popup_width)) / 2;
     let popup_y = (area.height.saturating_sub(popup_height)) / 2;
 
@@ -1675,8 +2147,36 @@ pub fn draw_dispatch_instructions(f: &mut Frame, issue_number: u64, input: &str)
     let inner = block.inner(popup_area);
     f.render_widget(block, popup_area);
 
-    // Split inner area: instructions text area + hint
-    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(inner);
+    // Split inner area: agent profile and attempts + instructions text area + hint
+    let chunks = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    let origin = if resolved { "(resolved)" } else { "(override)" };
+    let mut agent = vec![
+        Span::styled("Agent: ", Style::default().fg(Color::DarkGray)),
+        Span::styled(profile, Style::default().fg(Color::Cyan)),
+        Span::styled(format!(" {}", origin), Style::default().fg(Color::DarkGray)),
+    ];
+    if plan_first {
+        agent.push(Span::styled(" │ plan first", Style::default().fg(Color::Yellow)));
+    }
+    let mut lines = vec![Line::from(agent)];
+    if !attempts.is_empty() {
+        let list: Vec<String> = attempts
+            .iter()
+            .enumerate()
+            .map(|(i, summary)| format!("{}. {}", i + 1, summary))
+            .collect();
+        lines.push(Line::from(vec![
+            Span::styled("Attempts: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(list.join(" │ "), Style::default().fg(Color::Magenta)),
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines), chunks[0]);
 
     // Display input with cursor
     let display_text = if input.is_empty() {
@@ -1694,17 +2194,57 @@ pub fn draw_dispatch_instructions(f: &mut Frame, issue_number: u64, input: &str)
     let paragraph = Paragraph::new(display_text)
         .style(style)
         .wrap(Wrap { trim: false });
-    f.render_widget(paragraph, chunks[0]);
+    f.render_widget(paragraph, chunks[1]);
 
     // Draw hint at bottom
-    let hint = Paragraph::new("Shift+Enter: newline │ Enter: dispatch │ Esc: cancel")
+    let hint =
+        "Tab: agent │ ^A/^R: attempts │ ^P: plan │ ^O: prompt │ Enter: dispatch │ Esc: cancel";
+    let hint = Paragraph::new(hint)
         .style(Style::default().fg(Color::DarkGray))
         .alignment(Alignment::Center);
-    f.render_widget(hint, chunks[1]);
+    f.render_widget(hint, chunks[2]);
 }
 
-/// Draw instructions popup for starting agent on worktree
-pub fn draw_worktree_agent_instructions(f: &mut Frame, branch_name: &str, input: &str) {
+/// Draw the final prompt of a dispatch, being previewed and edited
+fn draw_dispatch_prompt_preview(f: &mut Frame, issue_number: u64, prompt: &str) {
+    let area = centered_rect(80, 80, f.area());
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            " Prompt for #{} │ {} chars ",
+            issue_number,
+            prompt.chars().count()
+        ))
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(area);
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).split(inner);
+
+    // Keep the end of the prompt, where typing happens, in view
+    let paragraph = Paragraph::new(format!("{}_", prompt))
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false });
+    let width = chunks[0].width.max(1) as usize;
+    let lines: usize = prompt
+        .split('\n')
+        .map(|line| line.chars().count().max(1).div_ceil(width))
+        .sum();
+    let scroll = (lines as u16).saturating_sub(chunks[0].height);
+    f.render_widget(paragraph.scroll((scroll, 0)), chunks[0]);
+
+    let hint = Paragraph::new("Shift+Enter: newline │ Enter: dispatch │ Esc: back to instructions")
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+    f.render_widget(hint, chunks[1]);
+}
+
+/// Draw instructions popup for starting agent on worktree
+pub fn draw_worktree_agent_instructions(f: &mut Frame, branch_name: &str, input: &str) {
     let area = f.area();
 
     // Calculate centered popup area (60% width, 10 lines tall)
@@ -1763,6 +2303,8 @@ pub fn draw_pr_list_view(f: &mut Frame, browser: &mut IssueBrowser) {
 
 /// Draw PR list view in a specific area
 pub fn draw_pr_list_view_in_area(f: &mut Frame, browser: &mut IssueBrowser, area: Rect) {
+    use crate::agents::AgentStatus;
+
     let items: Vec<ListItem> = browser
         .pull_requests
         .iter()
@@ -1775,6 +2317,21 @@ pub fn draw_pr_list_view_in_area(f: &mut Frame, browser: &mut IssueBrowser, area
                 Style::default().fg(Color::Cyan),
             ));
 
+            // Agent session working on the PR
+            match browser.pr_session(pr) {
+                Some(session) => {
+                    let (icon, color) = match &session.status {
+                        AgentStatus::Queued => ("◷", Color::Gray),
+                        AgentStatus::Running => ("▶", Color::Yellow),
+                        AgentStatus::Awaiting => ("⏸", Color::Cyan),
+                        AgentStatus::Completed { .. } => ("●", Color::Blue),
+                        AgentStatus::Failed { .. } => ("✗", Color::Red),
+                    };
+                    spans.push(Span::styled(format!("{} ", icon), Style::default().fg(color)));
+                }
+                None => spans.push(Span::raw("  ")),
+            }
+
             // Status indicator
             let (status_text, status_style) = if pr.draft {
                 ("DRAFT ", Style::default().fg(Color::DarkGray))
@@ -1926,6 +2483,29 @@ pub fn draw_pr_detail_view(
         Span::styled(format!("@{}", pr.author), Style::default().fg(Color::Magenta)),
     ]));
 
+    // Reviewers
+    if !pr.requested_reviewers.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("Reviewers: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(
+                pr.requested_reviewers
+                    .iter()
+                    .map(|r| format!("@{}", r))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]));
+    }
+
+    // Labels
+    if !pr.labels.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("Labels: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(pr.labels.join(", "), Style::default().fg(Color::Yellow)),
+        ]));
+    }
+
     // Mergeable status
     if let Some(mergeable) = pr.mergeable {
         let (text, style) = if mergeable {
@@ -1986,7 +2566,7 @@ pub fn draw_pr_detail_view(
         }
     }
 
-    let title = " PR Detail │ o:browser │ m:merge │ r:review │ Esc:back ";
+    let title = format_status_bar(CommandContext::PullRequestDetail, "PR Detail");
     let text = Text::from(lines);
     let paragraph = Paragraph::new(text)
         .block(Block::default().borders(Borders::ALL).title(title))
@@ -2096,6 +2676,660 @@ fn draw_pr_review_popup(f: &mut Frame, pr: &PullRequestDetail, input: &str) {
     f.render_widget(hint, chunks[2]);
 }
 
+/// Draw reviewer picker for a pull request
+fn draw_reviewer_picker(
+    f: &mut Frame,
+    area: Rect,
+    pr: &PullRequestDetail,
+    input: &str,
+    suggestions: &[String],
+    selected: usize,
+) {
+    let chunks = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Min(3),
+    ])
+    .split(area);
+
+    // Currently requested reviewers
+    let reviewers_text = if pr.requested_reviewers.is_empty() {
+        "No review requested".to_string()
+    } else {
+        pr.requested_reviewers.join(", ")
+    };
+    let reviewers_paragraph = Paragraph::new(reviewers_text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Requested Reviewers "),
+        )
+        .style(Style::default().fg(Color::Cyan));
+    f.render_widget(reviewers_paragraph, chunks[0]);
+
+    // Input field
+    let input_block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Type to search (Enter request/remove, Esc back) ")
+        .border_style(Style::default().fg(Color::Yellow));
+    let input_paragraph = Paragraph::new(format!("@{}", input))
+        .block(input_block)
+        .style(Style::default().fg(Color::White));
+    f.render_widget(input_paragraph, chunks[1]);
+
+    // Suggestions list
+    let items: Vec<ListItem> = suggestions
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let style = if i == selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let prefix = if pr.requested_reviewers.contains(name) {
+                "✓ "
+            } else {
+                "  "
+            };
+            ListItem::new(Line::from(format!("{}{}", prefix, name))).style(style)
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Suggestions (code owners first) "),
+    );
+    f.render_widget(list, chunks[2]);
+}
+
+/// Draw pull request edit form
+fn draw_edit_pull_request(
+    f: &mut Frame,
+    pr: &PullRequestDetail,
+    title: &str,
+    body: &str,
+    base: &str,
+    field: PrEditField,
+    status_message: Option<&str>,
+) {
+    let area = f.area();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Edit PR #{} ", pr.number))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(5),
+        Constraint::Length(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    let fields = [
+        (PrEditField::Title, " Title ", title, chunks[0]),
+        (PrEditField::Body, " Body ", body, chunks[1]),
+        (PrEditField::Base, " Base branch ", base, chunks[2]),
+    ];
+    for (kind, label, value, rect) in fields {
+        let editing = kind == field;
+        let border_style = if editing {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        let block_title = if editing {
+            format!("{}(editing) ", label)
+        } else {
+            label.to_string()
+        };
+        let text = if editing {
+            format!("{}_", value)
+        } else {
+            value.to_string()
+        };
+        let para = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(block_title)
+                    .border_style(border_style),
+            )
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false });
+        f.render_widget(para, rect);
+    }
+
+    // Status message
+    if let Some(msg) = status_message {
+        let status = Paragraph::new(msg)
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center);
+        f.render_widget(status, chunks[3]);
+    }
+
+    let help_text = if field == PrEditField::Body {
+        "Enter: newline │ Tab: next field │ Shift+Enter/Ctrl+S: save │ Esc: cancel"
+    } else {
+        "Enter/Tab: next field │ Shift+Enter/Ctrl+S: save │ Esc: cancel"
+    };
+    let help = Paragraph::new(help_text)
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+    f.render_widget(help, chunks[4]);
+}
+
+/// Draw agent review findings before submission
+fn draw_review_findings(
+    f: &mut Frame,
+    pr: &PullRequestDetail,
+    findings: &ReviewFindings,
+    selected: usize,
+    event: ReviewEvent,
+    status_message: Option<&str>,
+) {
+    let area = f.area();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            " Review findings for PR #{} │ verdict: {} ",
+            pr.number,
+            event.label()
+        ))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    let highlight = |idx: usize| {
+        if idx == selected {
+            Style::default().bg(Color::DarkGray)
+        } else {
+            Style::default()
+        }
+    };
+
+    let mut items: Vec<ListItem> = Vec::new();
+
+    let mut summary_lines = vec![Line::from(Span::styled(
+        "Summary",
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    if findings.summary.is_empty() {
+        summary_lines.push(Line::from(Span::styled(
+            "  (empty)",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for line in findings.summary.lines() {
+        summary_lines.push(Line::from(format!("  {}", line)));
+    }
+    summary_lines.push(Line::from(""));
+    items.push(ListItem::new(summary_lines).style(highlight(0)));
+
+    for (i, comment) in findings.comments.iter().enumerate() {
+        let mut lines = vec![Line::from(Span::styled(
+            format!("{}:{}", comment.path, comment.line),
+            Style::default().fg(Color::Cyan),
+        ))];
+        for line in comment.body.lines() {
+            lines.push(Line::from(format!("  {}", line)));
+        }
+        lines.push(Line::from(""));
+        items.push(ListItem::new(lines).style(highlight(i + 1)));
+    }
+
+    let mut list_state = ratatui::widgets::ListState::default();
+    list_state.select(Some(selected));
+    f.render_stateful_widget(List::new(items), chunks[0], &mut list_state);
+
+    if let Some(msg) = status_message {
+        let status = Paragraph::new(msg)
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center);
+        f.render_widget(status, chunks[1]);
+    }
+
+    let help = Paragraph::new(
+        "j/k: select │ e/Enter: edit │ d: remove │ t: verdict │ S: submit review │ Esc: back",
+    )
+    .style(Style::default().fg(Color::DarkGray))
+    .alignment(Alignment::Center);
+    f.render_widget(help, chunks[2]);
+}
+
+/// Draw edit popup for a review finding
+fn draw_plan_review(f: &mut Frame, review: &PlanReview, status_message: Option<&str>) {
+    let area = f.area();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            " Plan for #{} by {} ",
+            review.session.issue_number,
+            review.session.agent_type.display_name()
+        ))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let feedback_height = if review.feedback.is_empty() {
+        0
+    } else {
+        review.feedback.lines().count().min(6) as u16 + 1
+    };
+    let chunks = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(feedback_height),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    let lines: Vec<Line> = review.plan.lines().map(render_markdown_line).collect();
+    let plan = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((review.scroll, 0));
+    f.render_widget(plan, chunks[0]);
+
+    if !review.feedback.is_empty() {
+        let mut lines = vec![Line::from(Span::styled(
+            "Feedback",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ))];
+        for line in review.feedback.lines() {
+            lines.push(Line::from(format!("  {}", line)));
+        }
+        f.render_widget(Paragraph::new(lines), chunks[1]);
+    }
+
+    let status = status_message
+        .map(String::from)
+        .or_else(|| review.comment_url.as_ref().map(|url| format!("Posted: {}", url)));
+    if let Some(msg) = status {
+        let status = Paragraph::new(msg)
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center);
+        f.render_widget(status, chunks[2]);
+    }
+
+    let help = Paragraph::new(
+        "e: edit │ f: feedback │ c: post as comment │ a: approve │ r: revise │ R: reload │ q: back",
+    )
+    .style(Style::default().fg(Color::DarkGray))
+    .alignment(Alignment::Center);
+    f.render_widget(help, chunks[3]);
+}
+
+fn draw_plan_edit_popup(f: &mut Frame, field: PlanField, buffer: &str) {
+    let area = centered_rect(70, 50, f.area());
+
+    let title = match field {
+        PlanField::Plan => " Edit plan ",
+        PlanField::Feedback => " Feedback for the agent ",
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(area);
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).split(inner);
+
+    let paragraph = Paragraph::new(format!("{}_", buffer))
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, chunks[0]);
+
+    let hint = Paragraph::new("Enter: newline │ Shift+Enter/Ctrl+S: save │ Esc: cancel")
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+    f.render_widget(hint, chunks[1]);
+}
+
+/// Draw the quick reply popup: the end of the agent's pane and the answers to its dialog
+fn draw_quick_reply(f: &mut Frame, popup: &QuickReplyPopup, status_message: Option<&str>) {
+    let area = centered_rect(80, 70, f.area());
+    let session = popup.session();
+
+    let title = format!(
+        " Reply to #{}: {} ({}/{}) ",
+        session.issue_number,
+        truncate_str(&session.issue_title, 40),
+        popup.index + 1,
+        popup.sessions.len()
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(area);
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(2),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    // Bottom of the pane, where the agent asks its question
+    let lines: Vec<Line> = popup.pane.lines().map(|l| Line::from(l.to_string())).collect();
+    let hidden = lines.len().saturating_sub(chunks[0].height as usize);
+    let pane = Paragraph::new(lines[hidden..].to_vec()).style(Style::default().fg(Color::Gray));
+    f.render_widget(pane, chunks[0]);
+
+    let answers = match popup.dialog {
+        Some(AgentDialog::Permission) => "Permission dialog: y approve │ n deny".to_string(),
+        Some(AgentDialog::Selection) => "Selection dialog: y select │ n cancel".to_string(),
+        None => "No dialog: type a reply with i".to_string(),
+    };
+    let options = if popup.options.is_empty() {
+        String::new()
+    } else {
+        let numbers: Vec<String> = popup.options.iter().map(|(n, _)| n.to_string()).collect();
+        format!(" │ {}: pick option", numbers.join("/"))
+    };
+    let dialog = Paragraph::new(Line::from(vec![Span::styled(
+        format!("{}{}", answers, options),
+        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+    )]))
+    .block(Block::default().borders(Borders::TOP));
+    f.render_widget(dialog, chunks[1]);
+
+    let input = match &popup.input {
+        Some(input) => Paragraph::new(format!("> {}_", input))
+            .style(Style::default().fg(Color::White)),
+        None => Paragraph::new(status_message.unwrap_or("").to_string())
+            .style(Style::default().fg(Color::Yellow)),
+    };
+    f.render_widget(input, chunks[2]);
+
+    let hint = if popup.input.is_some() {
+        "Enter: send │ Esc: cancel"
+    } else {
+        "i: reply │ Tab/Shift+Tab: next/previous agent │ R: refresh │ t: attach │ q: close"
+    };
+    let hint = Paragraph::new(hint)
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+    f.render_widget(hint, chunks[3]);
+}
+
+fn draw_finding_edit_popup(f: &mut Frame, findings: &ReviewFindings, selected: usize, buffer: &str) {
+    let area = centered_rect(70, 50, f.area());
+
+    let title = match selected {
+        0 => " Edit summary ".to_string(),
+        n => findings
+            .comments
+            .get(n - 1)
+            .map(|c| format!(" Edit comment on {}:{} ", c.path, c.line))
+            .unwrap_or_default(),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(area);
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).split(inner);
+
+    let paragraph = Paragraph::new(format!("{}_", buffer))
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, chunks[0]);
+
+    let hint = Paragraph::new("Enter: newline │ Shift+Enter/Ctrl+S: save │ Esc: cancel")
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+    f.render_widget(hint, chunks[1]);
+}
+
+/// Draw pull request preview before creation
+fn draw_create_pull_request(f: &mut Frame, form: &NewPullRequest, status_message: Option<&str>) {
+    let area = f.area();
+
+    let kind = if form.draft { "Draft PR" } else { "PR" };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" New {}: {} → {} ", kind, form.branch, form.base))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(5),
+        Constraint::Length(3),
+        Constraint::Length(8),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    let field_block = |label: &str, editing: bool| {
+        Block::default()
+            .borders(Borders::ALL)
+            .title(if editing {
+                format!("{}(editing) ", label)
+            } else {
+                label.to_string()
+            })
+            .border_style(if editing {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            })
+    };
+    let field_text = |value: &str, editing: bool| {
+        if editing {
+            format!("{}_", value)
+        } else {
+            value.to_string()
+        }
+    };
+
+    let editing = form.field == PrCreateField::Title;
+    f.render_widget(
+        Paragraph::new(field_text(&form.title, editing)).block(field_block(" Title ", editing)),
+        chunks[0],
+    );
+
+    let editing = form.field == PrCreateField::Body;
+    f.render_widget(
+        Paragraph::new(field_text(&form.body, editing))
+            .block(field_block(" Body ", editing))
+            .wrap(Wrap { trim: false }),
+        chunks[1],
+    );
+
+    let editing = form.field == PrCreateField::Base;
+    f.render_widget(
+        Paragraph::new(field_text(&form.base, editing)).block(field_block(" Base branch ", editing)),
+        chunks[2],
+    );
+
+    // Reviewers: selected ones on top, fuzzy-filtered suggestions below
+    let editing = form.field == PrCreateField::Reviewers;
+    let reviewers_block = field_block(" Reviewers ", editing);
+    let reviewers_inner = reviewers_block.inner(chunks[3]);
+    f.render_widget(reviewers_block, chunks[3]);
+
+    let reviewer_chunks =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).split(reviewers_inner);
+    let selected_text = if form.reviewers.is_empty() {
+        "none".to_string()
+    } else {
+        form.reviewers.join(", ")
+    };
+    let input_line = Line::from(vec![
+        Span::styled(
+            format!("@{}", field_text(&form.reviewer_input, editing)),
+            Style::default().fg(Color::White),
+        ),
+        Span::styled(
+            format!("   requested: {}", selected_text),
+            Style::default().fg(Color::Cyan),
+        ),
+    ]);
+    f.render_widget(Paragraph::new(input_line), reviewer_chunks[0]);
+
+    let items: Vec<ListItem> = form
+        .suggestions
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let style = if editing && i == form.selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let prefix = if form.reviewers.contains(name) { "✓ " } else { "  " };
+            ListItem::new(Line::from(format!("{}{}", prefix, name))).style(style)
+        })
+        .collect();
+    let mut list_state = ratatui::widgets::ListState::default();
+    list_state.select(editing.then_some(form.selected));
+    f.render_stateful_widget(List::new(items), reviewer_chunks[1], &mut list_state);
+
+    // Status message
+    if let Some(msg) = status_message {
+        let status = Paragraph::new(msg)
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center);
+        f.render_widget(status, chunks[4]);
+    }
+
+    let help_text = match form.field {
+        PrCreateField::Body => {
+            "Enter: newline │ Tab: next field │ Ctrl+D: toggle draft │ Shift+Enter/Ctrl+S: push & create │ Esc: cancel"
+        }
+        PrCreateField::Reviewers => {
+            "↑↓: select │ Enter: toggle reviewer │ Tab: next field │ Ctrl+D: toggle draft │ Ctrl+S: push & create │ Esc: cancel"
+        }
+        _ => "Enter/Tab: next field │ Ctrl+D: toggle draft │ Shift+Enter/Ctrl+S: push & create │ Esc: cancel",
+    };
+    let help = Paragraph::new(help_text)
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+    f.render_widget(help, chunks[5]);
+}
+
+/// Draw close/reopen confirmation popup for a pull request
+fn draw_confirm_pr_state_popup(f: &mut Frame, pr: &PullRequestDetail, action: &str) {
+    let area = centered_rect(60, 30, f.area());
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Confirm {} ", action))
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(area);
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(block, area);
+
+    let text = vec![
+        Line::from(""),
+        Line::from(format!("{} PR #{}?", action, pr.number)),
+        Line::from(""),
+        Line::from(pr.title.clone()),
+        Line::from(""),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+            Span::raw(format!(": Yes, {} │ ", action.to_lowercase())),
+            Span::styled("n", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+            Span::raw(": No, cancel"),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, inner);
+}
+
+/// Draw label picker popup for a pull request
+fn draw_pr_labels_popup(f: &mut Frame, pr: &PullRequestDetail, labels: &[String], selected: usize) {
+    let area = centered_rect(50, 60, f.area());
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Labels for PR #{} ", pr.number))
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(area);
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).split(inner);
+
+    if labels.is_empty() {
+        let empty = Paragraph::new("No labels configured for this project")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        f.render_widget(empty, chunks[0]);
+    } else {
+        let items: Vec<ListItem> = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let style = if i == selected {
+                    Style::default()
+                        .bg(Color::DarkGray)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let prefix = if pr.labels.contains(label) { "✓ " } else { "  " };
+                ListItem::new(Line::from(format!("{}{}", prefix, label))).style(style)
+            })
+            .collect();
+        f.render_widget(List::new(items), chunks[0]);
+    }
+
+    let hint = Paragraph::new("Space/Enter: toggle │ Esc: back")
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+    f.render_widget(hint, chunks[1]);
+}
+
 /// Draw PR filters popup
 fn draw_pr_filters_popup(
     f: &mut Frame,
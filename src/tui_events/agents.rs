@@ -1,31 +1,222 @@
 //! Agent-related views event handling.
 
+use crate::agents::{cast_path, compare_attempts, keep_attempt};
+use crate::log_view::LogView;
+use crate::replay::Replay;
 use crate::tui::IssueBrowser;
 use crate::tui_types::TuiView;
 use crossterm::event::KeyCode;
 
-pub fn handle_agent_logs_key(browser: &mut IssueBrowser, key: KeyCode, scroll: &mut u16) {
+/// Handle a key in the agent log view. The log is edited in place: it can be large.
+pub fn handle_agent_logs_key(browser: &mut IssueBrowser, key: KeyCode) {
+    let TuiView::AgentLogs(log) = &mut browser.view else {
+        return;
+    };
+
+    // Typing a search
+    if let Some(input) = &mut log.search_input {
+        match key {
+            KeyCode::Esc => log.search_input = None,
+            KeyCode::Enter => {
+                let query = input.clone();
+                log.search_input = None;
+                log.search(&query);
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+        return;
+    }
+
+    let page = log.page.max(1) as isize;
     match key {
-        KeyCode::Esc | KeyCode::Char('q') => {
-            browser.view = TuiView::List;
+        KeyCode::Esc | KeyCode::Char('q') => browser.view = TuiView::List,
+        KeyCode::Up | KeyCode::Char('k') => log.scroll_by(-1),
+        KeyCode::Down | KeyCode::Char('j') => log.scroll_by(1),
+        KeyCode::PageUp => log.scroll_by(-page),
+        KeyCode::PageDown => log.scroll_by(page),
+        KeyCode::Left | KeyCode::Char('h') => log.hscroll = log.hscroll.saturating_sub(8),
+        KeyCode::Right | KeyCode::Char('l') => log.hscroll += 8,
+        KeyCode::Home | KeyCode::Char('g') => {
+            log.follow = false;
+            log.scroll = 0;
         }
-        KeyCode::Up | KeyCode::Char('k') => {
-            *scroll = scroll.saturating_sub(1);
+        KeyCode::End | KeyCode::Char('G') => {
+            log.scroll = log.bottom();
         }
-        KeyCode::Down | KeyCode::Char('j') => {
-            *scroll = scroll.saturating_add(1);
+        KeyCode::Char('f') => log.toggle_follow(),
+        KeyCode::Char('/') => log.search_input = Some(String::new()),
+        KeyCode::Char('n') => log.next_match(true),
+        KeyCode::Char('N') => log.next_match(false),
+        KeyCode::Char('e') => log.next_error(true),
+        KeyCode::Char('E') => log.next_error(false),
+        KeyCode::Char('p') => {
+            let session_id = log.session_id.clone();
+            match Replay::open(&session_id, &cast_path(&log.path)) {
+                Ok(replay) => browser.view = TuiView::Replay(Box::new(replay)),
+                Err(e) => browser.status_message = Some(e),
+            }
         }
-        KeyCode::PageUp => {
-            *scroll = scroll.saturating_sub(20);
+        _ => {}
+    }
+}
+
+/// Handle a key in the session replay view.
+pub fn handle_replay_key(browser: &mut IssueBrowser, key: KeyCode) {
+    let TuiView::Replay(replay) = &mut browser.view else {
+        return;
+    };
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') => browser.view = TuiView::List,
+        KeyCode::Char(' ') => replay.toggle_play(),
+        KeyCode::Char('+') | KeyCode::Char('=') => replay.change_speed(true),
+        KeyCode::Char('-') => replay.change_speed(false),
+        KeyCode::Right | KeyCode::Char('l') => replay.seek_to(replay.position + 5.0),
+        KeyCode::Left | KeyCode::Char('h') => replay.seek_to(replay.position - 5.0),
+        KeyCode::Home | KeyCode::Char('g') => replay.seek_to(0.0),
+        KeyCode::End | KeyCode::Char('G') => replay.seek_to(replay.duration()),
+        _ => {}
+    }
+}
+
+/// Show the attempts at an issue side by side.
+pub fn open_attempts(browser: &mut IssueBrowser, issue_number: u64, selected: usize) {
+    let project = browser.project_name.clone().unwrap_or_default();
+    let attempts = compare_attempts(&project, issue_number);
+    if attempts.is_empty() {
+        browser.status_message = Some(format!(
+            "No attempts at #{}. Add attempts with Ctrl+A when dispatching.",
+            issue_number
+        ));
+        return;
+    }
+    browser.view = TuiView::CompareAttempts {
+        issue_number,
+        selected: selected.min(attempts.len() - 1),
+        attempts,
+        confirm_keep: false,
+    };
+}
+
+/// Handle a key in the attempt comparison: keep the selected attempt, discarding the others.
+pub fn handle_compare_attempts_key(browser: &mut IssueBrowser, key: KeyCode) {
+    let TuiView::CompareAttempts {
+        issue_number,
+        attempts,
+        selected,
+        confirm_keep,
+    } = &mut browser.view
+    else {
+        return;
+    };
+    let issue_number = *issue_number;
+    let Some(attempt) = attempts.get(*selected).map(|a| a.session.clone()) else {
+        return;
+    };
+
+    if *confirm_keep {
+        *confirm_keep = false;
+        if key != KeyCode::Char('y') {
+            return;
+        }
+        let Some(local_path) = browser.local_path.clone() else {
+            browser.status_message = Some("No local_path configured for this project.".to_string());
+            return;
+        };
+        match keep_attempt(&attempt.id, &local_path) {
+            Ok(discarded) => {
+                browser.status_message = Some(format!(
+                    "Kept attempt {} on {}, discarded {} other attempts.",
+                    attempt.attempt.as_deref().unwrap_or("?"),
+                    attempt.branch_name,
+                    discarded
+                ));
+            }
+            Err(e) => browser.status_message = Some(format!("Failed to discard attempts: {}", e)),
+        }
+        browser.view = TuiView::List;
+        if let Some(project) = browser.project_name.clone() {
+            browser.refresh_sessions(&project);
+        }
+        return;
+    }
+
+    let len = attempts.len();
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') => browser.view = TuiView::List,
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(len - 1),
+        KeyCode::Enter => *confirm_keep = true,
+        KeyCode::Char('l') => {
+            browser.view = TuiView::AgentLogs(LogView::open(
+                &attempt.id,
+                &attempt.log_file,
+                attempt.is_running(),
+            ));
+        }
+        KeyCode::Char('R') => {
+            let selected = *selected;
+            open_attempts(browser, issue_number, selected);
+        }
+        _ => {}
+    }
+}
+
+/// Handle a key in the dispatch queue: select, reorder and cancel queued dispatches.
+pub fn handle_dispatch_queue_key(browser: &mut IssueBrowser, key: KeyCode) {
+    let TuiView::DispatchQueue {
+        queued, selected, ..
+    } = &browser.view
+    else {
+        return;
+    };
+    let selected = *selected;
+    let id = queued.get(selected).map(|s| s.id.clone());
+    let len = queued.len();
+
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            browser.view = TuiView::List;
+            if let Some(project) = browser.project_name.clone() {
+                browser.refresh_sessions(&project);
+            }
         }
-        KeyCode::PageDown => {
-            *scroll = scroll.saturating_add(20);
+        KeyCode::Up | KeyCode::Char('k') => browser.open_dispatch_queue(selected.saturating_sub(1)),
+        KeyCode::Down | KeyCode::Char('j') => browser.open_dispatch_queue(selected + 1),
+        KeyCode::Char('K') | KeyCode::Char('J') => {
+            let up = key == KeyCode::Char('K');
+            let moved = id.is_some_and(|id| crate::agents::move_queued(&id, up).unwrap_or(false));
+            let selected = match (moved, up) {
+                (true, true) => selected - 1,
+                (true, false) => (selected + 1).min(len - 1),
+                (false, _) => selected,
+            };
+            browser.open_dispatch_queue(selected);
+        }
+        KeyCode::Char('x') | KeyCode::Char('d') | KeyCode::Delete => {
+            if let Some(id) = id {
+                match crate::agents::cancel_queued(&id) {
+                    Ok(true) => browser.status_message = Some("Removed from the queue".to_string()),
+                    Ok(false) => browser.status_message = Some("Already started".to_string()),
+                    Err(e) => browser.status_message = Some(format!("Failed to cancel: {}", e)),
+                }
+            }
+            browser.open_dispatch_queue(selected);
         }
+        KeyCode::Char('R') => browser.open_dispatch_queue(selected),
         _ => {}
     }
 }
 
-pub fn handle_agent_select_key(browser: &mut IssueBrowser, key: KeyCode, selected: &mut usize) {
+pub fn handle_agent_select_key(
+    browser: &mut IssueBrowser,
+    key: KeyCode,
+    agents: &[crate::config::CodingAgentType],
+    selected: &mut usize,
+) {
     match key {
         KeyCode::Esc => {
             browser.view = TuiView::List;
@@ -36,17 +227,15 @@ pub fn handle_agent_select_key(browser: &mut IssueBrowser, key: KeyCode, selecte
             }
         }
         KeyCode::Down | KeyCode::Char('j') => {
-            if *selected < 1 {
+            if *selected + 1 < agents.len() {
                 *selected += 1;
             }
         }
         KeyCode::Enter => {
-            let new_agent = if *selected == 0 {
-                crate::config::CodingAgentType::Claude
-            } else {
-                crate::config::CodingAgentType::Opencode
+            let Some(new_agent) = agents.get(*selected).cloned() else {
+                return;
             };
-            let agent_name = crate::agents::get_agent(&new_agent).name();
+            let agent_name = new_agent.display_name().to_string();
             browser.coding_agent = new_agent;
             browser.view = TuiView::List;
             browser.status_message = Some(format!("Dispatch agent set to {}.", agent_name));
@@ -85,7 +85,12 @@ pub async fn handle_command_key(
                         }
                     }
                     "agent" => {
-                        browser.view = TuiView::AgentSelect { selected: 0 };
+                        let agents = crate::agents::available_agents();
+                        let selected = agents
+                            .iter()
+                            .position(|a| a == &browser.coding_agent)
+                            .unwrap_or(0);
+                        browser.view = TuiView::AgentSelect { agents, selected };
                     }
                     _ => {
                         if let Some(filter_labels) = labels {
@@ -1,7 +1,9 @@
 //! Common helpers for TUI event handling.
 
+use crate::plan::PlanReview;
+use crate::quick_reply::QuickReplyPopup;
 use crate::tui::IssueBrowser;
-use crate::tui_types::{CommandSuggestion, CreateStage, TuiView};
+use crate::tui_types::{CommandSuggestion, CreateStage, PrCreateField, PrEditField, TuiView};
 
 /// Filter commands based on input
 pub fn filter_commands(commands: &[CommandSuggestion], input: &str) -> Vec<CommandSuggestion> {
@@ -61,6 +63,12 @@ pub fn handle_paste(browser: &mut IssueBrowser, content: &str) {
         TuiView::AssignUser { input, .. } => {
             input.push_str(&clean_content.replace('\n', " "));
         }
+        TuiView::DispatchInstructions {
+            prompt: Some(prompt),
+            ..
+        } => {
+            prompt.push_str(&clean_content);
+        }
         TuiView::DispatchInstructions { input, .. } => {
             input.push_str(&clean_content);
         }
@@ -70,6 +78,44 @@ pub fn handle_paste(browser: &mut IssueBrowser, content: &str) {
         TuiView::DispatchPrReview { input, .. } => {
             input.push_str(&clean_content);
         }
+        TuiView::PrReviewers { input, .. } => {
+            input.push_str(&clean_content.replace('\n', " "));
+        }
+        TuiView::ReviewFindings {
+            edit: Some(buffer), ..
+        } => {
+            buffer.push_str(&clean_content);
+        }
+        TuiView::PlanReview(PlanReview {
+            editing: Some((_, buffer)),
+            ..
+        }) => {
+            buffer.push_str(&clean_content);
+        }
+        TuiView::QuickReply(QuickReplyPopup {
+            input: Some(input), ..
+        }) => {
+            input.push_str(&clean_content);
+        }
+        TuiView::CreatePullRequest(form) => match form.field {
+            PrCreateField::Title => form.title.push_str(&clean_content.replace('\n', " ")),
+            PrCreateField::Body => form.body.push_str(&clean_content),
+            PrCreateField::Base => form.base.push_str(clean_content.trim()),
+            PrCreateField::Reviewers => {
+                form.reviewer_input.push_str(clean_content.trim());
+            }
+        },
+        TuiView::EditPullRequest {
+            title,
+            body,
+            base,
+            field,
+            ..
+        } => match field {
+            PrEditField::Title => title.push_str(&clean_content.replace('\n', " ")),
+            PrEditField::Body => body.push_str(&clean_content),
+            PrEditField::Base => base.push_str(clean_content.trim()),
+        },
         _ => {}
     }
 }
@@ -84,8 +84,8 @@ pub async fn handle_detail_key(browser: &mut IssueBrowser, key: KeyCode, issue:
         }
         KeyCode::Char('d') => {
             if let Some(project) = browser.project_name.clone() {
-                let tmux_name = crate::agents::tmux_session_name(&project, issue.number);
-                if crate::agents::is_tmux_session_running(&tmux_name) {
+                let tmux_name = crate::agents::issue_session_name(&project, issue.number);
+                if crate::agents::is_session_running(&tmux_name) {
                     browser.status_message = Some(format!(
                         "Session already running for #{}. Use 't' to open tmux or 'K' to kill it.",
                         issue.number
@@ -371,36 +371,39 @@ pub async fn handle_confirm_dispatch_key(
             if let (Some(project), Some(local_path)) =
                 (&browser.project_name, &browser.local_path)
             {
-                let tmux_name = crate::agents::tmux_session_name(project, number);
-                if crate::agents::is_tmux_session_running(&tmux_name) {
+                let tmux_name = crate::agents::issue_session_name(project, number);
+                if crate::agents::is_session_running(&tmux_name) {
                     browser.status_message = Some(format!(
                         "Session already running for #{}. Use 't' to open tmux or 'K' to kill it.",
                         number
                     ));
+                } else if browser.is_dispatching(number) {
+                    browser.status_message = Some(format!("Already dispatching #{}", number));
                 } else {
-                    let agent = crate::agents::get_agent(&browser.coding_agent);
-                    match crate::agents::dispatch_to_agent(
-                        issue,
-                        local_path,
-                        project,
-                        &browser.coding_agent,
-                        browser.base_branch.as_deref(),
-                        None,
-                    )
-                    .await
-                    {
-                        Ok(session) => {
-                            browser.status_message = Some(format!(
-                                "Dispatched #{} to {} (session {})",
-                                number,
-                                agent.name(),
-                                &session.id[..8]
-                            ));
-                        }
-                        Err(e) => {
-                            browser.status_message = Some(format!("Failed to dispatch: {}", e));
-                        }
-                    }
+                    let prompt = browser.dispatch_prompt(issue, None).await;
+                    let (project, local_path) = (project.clone(), local_path.clone());
+                    let agent_type = browser.coding_agent.clone();
+                    let base_branch = browser.base_branch.clone();
+                    let issue = issue.clone();
+                    browser.spawn_dispatch(number, async move {
+                        let session = crate::agents::dispatch_to_agent(
+                            &issue,
+                            &local_path,
+                            &project,
+                            &agent_type,
+                            base_branch.as_deref(),
+                            &prompt,
+                            None,
+                        )
+                        .await
+                        .map_err(|e| e.to_string())?;
+                        Ok(format!(
+                            "Dispatched #{} to {} (session {})",
+                            number,
+                            session.agent_type.display_name(),
+                            &session.id[..8]
+                        ))
+                    });
                 }
             } else {
                 browser.status_message = Some(
@@ -1,56 +1,160 @@
 //! Dispatch instructions views event handling.
 
+use crate::agents::{build_dispatch_prompt, PromptContext};
+use crate::config::ResolvedProfile;
 use crate::github::IssueDetail;
 use crate::tui::IssueBrowser;
 use crate::tui_types::TuiView;
 use crossterm::event::{KeyCode, KeyModifiers};
 use std::path::PathBuf;
 
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_dispatch_instructions_key(
     browser: &mut IssueBrowser,
     key: KeyCode,
     modifiers: KeyModifiers,
     issue: &IssueDetail,
     input: &mut String,
+    profiles: &[ResolvedProfile],
+    profile: &mut usize,
+    attempts: &mut Vec<usize>,
+    plan_first: &mut bool,
+    context: &PromptContext,
+    prompt: &mut Option<String>,
 ) {
+    // Previewing the final prompt: typing edits it, Esc goes back to the instructions
+    if let Some(text) = prompt {
+        match key {
+            KeyCode::Esc => {
+                *prompt = None;
+                return;
+            }
+            KeyCode::Enter if modifiers.contains(KeyModifiers::SHIFT) => {
+                text.push('\n');
+                return;
+            }
+            KeyCode::Backspace => {
+                text.pop();
+                return;
+            }
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                text.push(c);
+                return;
+            }
+            _ => {}
+        }
+    }
+
     match key {
         KeyCode::Esc => {
             browser.view = TuiView::List;
         }
+        KeyCode::Char('o') if modifiers.contains(KeyModifiers::CONTROL) => {
+            *prompt = Some(final_prompt(issue, context, input, prompt));
+        }
+        KeyCode::Tab if !profiles.is_empty() => {
+            *profile = (*profile + 1) % profiles.len();
+        }
+        KeyCode::BackTab if !profiles.is_empty() => {
+            *profile = (*profile + profiles.len() - 1) % profiles.len();
+        }
+        // Best-of-N: collect the profiles of attempts to run side by side
+        KeyCode::Char('a') if modifiers.contains(KeyModifiers::CONTROL) => {
+            attempts.push(*profile);
+        }
+        KeyCode::Char('r') if modifiers.contains(KeyModifiers::CONTROL) => {
+            attempts.pop();
+        }
+        KeyCode::Char('p') if modifiers.contains(KeyModifiers::CONTROL) => {
+            *plan_first = !*plan_first;
+        }
         KeyCode::Enter if modifiers.contains(KeyModifiers::SHIFT) => {
             input.push('\n');
         }
+        KeyCode::Enter if browser.is_dispatching(issue.number) => {
+            browser.status_message = Some(format!("Already dispatching #{}", issue.number));
+        }
+        KeyCode::Enter if attempts.len() > 1 => {
+            if let Some(local_path) = browser.local_path.clone() {
+                let project_name = browser.project_name.clone().unwrap_or_default();
+                let prompt = final_prompt(issue, context, input, prompt);
+                let attempt_profiles: Vec<ResolvedProfile> =
+                    attempts.iter().filter_map(|&i| profiles.get(i).cloned()).collect();
+                let base_branch = browser.base_branch.clone();
+                let issue = issue.clone();
+                browser.spawn_dispatch(issue.number, async move {
+                    let sessions = crate::agents::dispatch_attempts(
+                        &issue,
+                        &local_path,
+                        &project_name,
+                        &attempt_profiles,
+                        base_branch.as_deref(),
+                        &prompt,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                    Ok(format!(
+                        "Dispatched {} of {} attempts at #{}. A: compare attempts",
+                        sessions.len(),
+                        attempt_profiles.len(),
+                        issue.number
+                    ))
+                });
+            }
+            browser.view = TuiView::List;
+        }
         KeyCode::Enter => {
+            // A single attempt is a plain dispatch with its profile
+            if let Some(&only) = attempts.first() {
+                *profile = only;
+            }
             // Dispatch with instructions (or without if empty)
             if let Some(local_path) = browser.local_path.clone() {
                 let project_name = browser.project_name.clone().unwrap_or_default();
-                let instructions = if input.trim().is_empty() {
-                    None
-                } else {
-                    Some(input.as_str())
-                };
-                let agent = crate::agents::get_agent(&browser.coding_agent);
-                match crate::agents::dispatch_to_agent(
-                    issue,
-                    &local_path,
-                    &project_name,
-                    &browser.coding_agent,
-                    browser.base_branch.as_deref(),
-                    instructions,
-                )
-                .await
-                {
-                    Ok(_) => {
-                        browser.status_message =
-                            Some(format!("Dispatched #{} to {}.", issue.number, agent.name()));
-                    }
-                    Err(e) => {
-                        browser.status_message = Some(format!("Failed to dispatch: {}", e));
+                let prompt = final_prompt(issue, context, input, prompt);
+                let base_branch = browser.base_branch.clone();
+                let agent_type = browser.coding_agent.clone();
+                let profile = profiles.get(*profile).cloned();
+                let plan_first = *plan_first;
+                let issue = issue.clone();
+                browser.spawn_dispatch(issue.number, async move {
+                    let dispatched = if plan_first {
+                        crate::agents::dispatch_plan_first(
+                            &issue,
+                            &local_path,
+                            &project_name,
+                            &agent_type,
+                            base_branch.as_deref(),
+                            &prompt,
+                            profile.as_ref(),
+                        )
+                        .await
+                    } else {
+                        crate::agents::dispatch_to_agent(
+                            &issue,
+                            &local_path,
+                            &project_name,
+                            &agent_type,
+                            base_branch.as_deref(),
+                            &prompt,
+                            profile.as_ref(),
+                        )
+                        .await
+                    };
+                    match dispatched {
+                        Ok(session) if session.planning => Ok(format!(
+                            "Asked {} for a plan for #{}. P: review the plan",
+                            session.agent_type.display_name(),
+                            issue.number
+                        )),
+                        Ok(session) => Ok(format!(
+                            "Dispatched #{} to {}.",
+                            issue.number,
+                            session.agent_type.display_name()
+                        )),
+                        Err(e) => Err(e.to_string()),
                     }
-                }
-                if let Some(project) = browser.project_name.clone() {
-                    browser.refresh_sessions(&project);
-                }
+                });
             }
             browser.view = TuiView::List;
         }
@@ -64,6 +168,20 @@ pub async fn handle_dispatch_instructions_key(
     }
 }
 
+/// The previewed prompt, or the prompt built from the issue, its context and the instructions
+fn final_prompt(
+    issue: &IssueDetail,
+    context: &PromptContext,
+    input: &str,
+    prompt: &Option<String>,
+) -> String {
+    prompt.clone().unwrap_or_else(|| {
+        let config = crate::config::load_config().unwrap_or_default();
+        let instructions = Some(input).filter(|i| !i.trim().is_empty());
+        build_dispatch_prompt(issue, &config.prompt, context, instructions)
+    })
+}
+
 pub async fn handle_worktree_agent_instructions_key(
     browser: &mut IssueBrowser,
     key: KeyCode,
@@ -95,15 +213,17 @@ pub async fn handle_worktree_agent_instructions_key(
                 Some(input.as_str())
             };
 
+            let profile =
+                crate::agents::resolve_agent_profile(&project_name, &[], &browser.coding_agent);
             match crate::agents::launch_agent_interactive(
                 worktree_path,
                 &session_name,
-                &browser.coding_agent,
+                &profile,
                 initial_prompt,
             ) {
                 Ok(_) => {
                     // Enter the tmux session directly
-                    let sessions = crate::agents::list_tmux_sessions();
+                    let sessions = crate::agents::list_issue_sessions();
                     let mut all_sessions = sessions;
                     if !all_sessions.contains(&session_name) {
                         all_sessions.push(session_name.clone());
@@ -1,5 +1,6 @@
 //! Issue list view event handling.
 
+use crate::log_view::LogView;
 use crate::tui::IssueBrowser;
 use crate::tui_types::{CreateStage, IssueFilterFocus, TuiView};
 use crossterm::event::KeyCode;
@@ -96,23 +97,56 @@ pub async fn handle_list_key(browser: &mut IssueBrowser, key: KeyCode) {
         KeyCode::Char('l') => {
             if let Some(issue) = browser.selected_issue() {
                 if let Some(session) = browser.session_cache.get(&issue.number) {
-                    let content = std::fs::read_to_string(&session.log_file).unwrap_or_default();
-                    browser.view = TuiView::AgentLogs {
-                        session_id: session.id.clone(),
-                        content,
-                        scroll: 0,
-                    };
+                    browser.view = TuiView::AgentLogs(LogView::open(
+                        &session.id,
+                        &session.log_file,
+                        session.is_running(),
+                    ));
                 } else {
                     browser.status_message = Some("No agent session for this issue".to_string());
                 }
             }
         }
         KeyCode::Char('p') => {
-            handle_create_pr(browser);
+            handle_create_pr(browser).await;
         }
         KeyCode::Char('K') => {
             handle_kill_agent(browser);
         }
+        KeyCode::Char('A') => {
+            if let Some(issue) = browser.selected_issue() {
+                let number = issue.number;
+                super::agents::open_attempts(browser, number, 0);
+            }
+        }
+        KeyCode::Char('Q') => {
+            browser.open_dispatch_queue(0);
+        }
+        KeyCode::Char('r') => {
+            let first = browser
+                .selected_issue()
+                .and_then(|issue| browser.session_cache.get(&issue.number))
+                .map(|session| session.id.clone());
+            super::reply::open_quick_reply(browser, first.as_deref(), false);
+        }
+        KeyCode::Char('u') => {
+            let session = browser
+                .selected_issue()
+                .and_then(|issue| browser.session_cache.get(&issue.number))
+                .cloned();
+            match session {
+                Some(session) => resume(browser, &session.id),
+                None => {
+                    browser.status_message = Some("No agent session for this issue".to_string());
+                }
+            }
+        }
+        KeyCode::Char('P') => {
+            if let Some(issue) = browser.selected_issue() {
+                let number = issue.number;
+                super::plan::open_plan_review(browser, number);
+            }
+        }
         KeyCode::Char('o') => {
             handle_open_ide(browser);
         }
@@ -177,58 +211,78 @@ async fn handle_dispatch(browser: &mut IssueBrowser) {
             let issue_number = issue.number;
             let project_name = browser.project_name.clone().unwrap_or_default();
 
-            let tmux_name = crate::agents::tmux_session_name(&project_name, issue_number);
-            if crate::agents::is_tmux_session_running(&tmux_name) {
+            let tmux_name = crate::agents::issue_session_name(&project_name, issue_number);
+            if crate::agents::is_session_running(&tmux_name) {
                 browser.status_message = Some(format!(
                     "Session already running for #{}. Use 't' to open tmux or 'K' to kill it.",
                     issue_number
                 ));
             } else if let Ok(detail) = browser.github.get_issue(issue_number).await {
                 // Open instructions popup instead of dispatching directly
+                let config = crate::config::load_config().unwrap_or_default();
+                let mut profiles = vec![config.resolve_agent_profile(
+                    &project_name,
+                    &detail.labels,
+                    &browser.coding_agent,
+                )];
+                for choice in config.profile_choices(
+                    &project_name,
+                    &browser.coding_agent,
+                    &crate::agents::available_agents(),
+                ) {
+                    if !profiles.contains(&choice) {
+                        profiles.push(choice);
+                    }
+                }
+                let context = browser.prompt_context(&detail).await;
                 browser.view = TuiView::DispatchInstructions {
                     issue: detail,
                     input: String::new(),
+                    profiles,
+                    profile: 0,
+                    attempts: Vec::new(),
+                    plan_first: false,
+                    context,
+                    prompt: None,
                 };
             }
         }
     } else {
-        // Batch dispatch - no instructions popup, dispatch directly
+        // Batch dispatch - no instructions popup, queued to limit concurrent agents
         let project_name = browser.project_name.clone().unwrap_or_default();
         let local_path = browser.local_path.clone().unwrap();
-        let mut dispatched = 0;
+        let mut queued = 0;
         let mut skipped = 0;
 
-        let agent = crate::agents::get_agent(&browser.coding_agent);
         for issue_number in browser.selected_issues.iter() {
-            let tmux_name = crate::agents::tmux_session_name(&project_name, *issue_number);
-            if crate::agents::is_tmux_session_running(&tmux_name) {
+            let tmux_name = crate::agents::issue_session_name(&project_name, *issue_number);
+            if crate::agents::is_session_running(&tmux_name) {
                 skipped += 1;
                 continue;
             }
-            if let Ok(detail) = browser.github.get_issue(*issue_number).await
-                && crate::agents::dispatch_to_agent(
+            if let Ok(detail) = browser.github.get_issue(*issue_number).await {
+                let prompt = browser.dispatch_prompt(&detail, None).await;
+                match crate::agents::queue_dispatch(
                     &detail,
                     &local_path,
                     &project_name,
                     &browser.coding_agent,
                     browser.base_branch.as_deref(),
-                    None,
-                )
-                .await
-                .is_ok()
-            {
-                dispatched += 1;
+                    &prompt,
+                ) {
+                    Ok(_) => queued += 1,
+                    Err(_) => skipped += 1,
+                }
             }
         }
 
         if skipped > 0 {
             browser.status_message = Some(format!(
-                "Dispatched {} issues ({} skipped, already running).",
-                dispatched, skipped
+                "Queued {} issues ({} skipped, already queued or running). Q: queue",
+                queued, skipped
             ));
         } else {
-            browser.status_message =
-                Some(format!("Dispatched {} issues to {}.", dispatched, agent.name()));
+            browser.status_message = Some(format!("Queued {} issues. Q: queue", queued));
         }
         browser.selected_issues.clear();
         if let Some(project) = browser.project_name.clone() {
@@ -237,13 +291,32 @@ async fn handle_dispatch(browser: &mut IssueBrowser) {
     }
 }
 
+/// Relaunch the agent of an ended session in its worktree.
+pub(super) fn resume(browser: &mut IssueBrowser, id: &str) {
+    match crate::agents::resume_session(id) {
+        Ok(session) => {
+            browser.status_message = Some(format!(
+                "Resumed {} on #{}",
+                session.agent_type.display_name(),
+                session.issue_number
+            ));
+            browser.refresh_sessions(&session.project);
+        }
+        Err(e) => browser.status_message = Some(format!("Failed to resume: {}", e)),
+    }
+}
+
 fn handle_open_tmux(browser: &mut IssueBrowser) {
     if let Some(issue) = browser.selected_issue() {
         let issue_number = issue.number;
         if let Some(project) = browser.project_name.clone() {
-            let tmux_name = crate::agents::tmux_session_name(&project, issue_number);
-            if crate::agents::is_tmux_session_running(&tmux_name) {
-                let all_sessions = crate::agents::list_tmux_sessions();
+            // The cached session may be one of several attempts at the issue
+            let tmux_name = match browser.session_cache.get(&issue_number) {
+                Some(session) => session.session_name(),
+                None => crate::agents::issue_session_name(&project, issue_number),
+            };
+            if crate::agents::is_session_running(&tmux_name) {
+                let all_sessions = crate::agents::list_issue_sessions();
                 let current_idx = all_sessions
                     .iter()
                     .position(|s| s == &tmux_name)
@@ -277,7 +350,7 @@ fn handle_open_tmux(browser: &mut IssueBrowser) {
 }
 
 fn handle_open_any_tmux(browser: &mut IssueBrowser) {
-    let all_sessions = crate::agents::list_tmux_sessions();
+    let all_sessions = crate::agents::list_issue_sessions();
     if !all_sessions.is_empty() {
         let area = crossterm::terminal::size().unwrap_or((80, 24));
         match crate::embedded_term::EmbeddedTerminal::new(
@@ -302,30 +375,31 @@ fn handle_open_any_tmux(browser: &mut IssueBrowser) {
     }
 }
 
-fn handle_create_pr(browser: &mut IssueBrowser) {
+async fn handle_create_pr(browser: &mut IssueBrowser) {
     if let Some(issue) = browser.selected_issue() {
         let issue_number = issue.number;
-        if let Some(session) = browser.session_cache.get(&issue_number) {
+        if let Some(project) = browser.project_name.clone() {
+            browser.refresh_sessions(&project);
+        }
+        if let Some(session) = browser.session_cache.get(&issue_number).cloned() {
             if session.is_running() {
                 browser.status_message = Some("Agent is still running".to_string());
             } else if session.pr_url.is_some() {
                 browser.status_message = Some("PR already created".to_string());
             } else {
-                match crate::agents::create_pr(session, browser.base_branch.as_deref()) {
-                    Ok(url) => {
-                        browser.status_message = Some(format!("PR created: {}", url));
-                    }
-                    Err(e) => {
-                        browser.status_message = Some(format!("Failed to create PR: {}", e));
-                    }
-                }
+                super::pr::open_create_pull_request(
+                    browser,
+                    session.worktree_path,
+                    session.branch_name,
+                    Some(issue_number),
+                    Some(session.id),
+                    false,
+                )
+                .await;
             }
         } else {
             browser.status_message = Some("No agent session for this issue".to_string());
         }
-        if let Some(project) = browser.project_name.clone() {
-            browser.refresh_sessions(&project);
-        }
     }
 }
 
@@ -336,6 +410,9 @@ fn handle_kill_agent(browser: &mut IssueBrowser) {
             if session.is_running() {
                 let _ = crate::agents::kill_agent(&session.id);
                 browser.status_message = Some(format!("Killed agent for #{}", issue_number));
+            } else if session.is_queued() {
+                let _ = crate::agents::cancel_queued(&session.id);
+                browser.status_message = Some(format!("Removed #{} from the queue", issue_number));
             } else {
                 browser.status_message = Some("Agent is not running".to_string());
             }
@@ -382,9 +459,7 @@ fn handle_cleanup_worktree(browser: &mut IssueBrowser) {
                 browser.status_message =
                     Some(format!("Cleaned up worktree for #{}", issue_number));
 
-                let mut manager = crate::agents::SessionManager::load();
-                manager.remove(&session.id);
-                let _ = manager.save();
+                let _ = crate::agents::SessionManager::update(|m| m.remove(&session.id));
             }
         } else {
             browser.status_message = Some("No agent session for this issue".to_string());
@@ -11,6 +11,8 @@
 //! - `pr`: Pull request views
 //! - `filters`: Filter dialogs
 //! - `agents`: Agent logs and selection
+//! - `plan`: Implementation plan review
+//! - `reply`: Quick replies to waiting agents
 //! - `project`: Project selection
 //! - `embedded`: Embedded tmux terminal
 //! - `help`: Help view
@@ -26,8 +28,10 @@ mod embedded;
 mod filters;
 mod help;
 mod list;
+mod plan;
 mod pr;
 mod project;
+mod reply;
 mod search;
 mod worktree;
 
@@ -108,16 +112,52 @@ pub async fn handle_key_event(browser: &mut IssueBrowser, key: KeyCode, modifier
             detail::handle_confirm_dispatch_key(browser, key, &issue).await;
         }
 
-        TuiView::DispatchInstructions { issue, input } => {
+        TuiView::DispatchInstructions {
+            issue,
+            input,
+            profiles,
+            profile,
+            attempts,
+            plan_first,
+            context,
+            prompt,
+        } => {
             let issue = issue.clone();
             let mut input = input.clone();
-            dispatch::handle_dispatch_instructions_key(browser, key, modifiers, &issue, &mut input)
-                .await;
+            let profiles = profiles.clone();
+            let mut profile = *profile;
+            let mut attempts = attempts.clone();
+            let mut plan_first = *plan_first;
+            let context = context.clone();
+            let mut prompt = prompt.clone();
+            dispatch::handle_dispatch_instructions_key(
+                browser,
+                key,
+                modifiers,
+                &issue,
+                &mut input,
+                &profiles,
+                &mut profile,
+                &mut attempts,
+                &mut plan_first,
+                &context,
+                &mut prompt,
+            )
+            .await;
             if let TuiView::DispatchInstructions {
-                input: ref mut i, ..
+                input: ref mut i,
+                profile: ref mut p,
+                attempts: ref mut a,
+                plan_first: ref mut f,
+                prompt: ref mut pr,
+                ..
             } = browser.view
             {
                 *i = input;
+                *p = profile;
+                *a = attempts;
+                *f = plan_first;
+                *pr = prompt;
             }
         }
 
@@ -146,22 +186,37 @@ pub async fn handle_key_event(browser: &mut IssueBrowser, key: KeyCode, modifier
             }
         }
 
-        TuiView::AgentLogs { scroll, .. } => {
-            let mut scroll = *scroll;
-            agents::handle_agent_logs_key(browser, key, &mut scroll);
-            if let TuiView::AgentLogs {
-                scroll: ref mut s, ..
-            } = browser.view
-            {
-                *s = scroll;
-            }
+        TuiView::AgentLogs(_) => {
+            agents::handle_agent_logs_key(browser, key);
         }
 
-        TuiView::AgentSelect { selected } => {
+        TuiView::Replay(_) => {
+            agents::handle_replay_key(browser, key);
+        }
+
+        TuiView::CompareAttempts { .. } => {
+            agents::handle_compare_attempts_key(browser, key);
+        }
+
+        TuiView::DispatchQueue { .. } => {
+            agents::handle_dispatch_queue_key(browser, key);
+        }
+
+        TuiView::PlanReview(_) => {
+            plan::handle_plan_review_key(browser, key, modifiers).await;
+        }
+
+        TuiView::QuickReply(_) => {
+            reply::handle_quick_reply_key(browser, key);
+        }
+
+        TuiView::AgentSelect { agents, selected } => {
+            let agents = agents.clone();
             let mut selected = *selected;
-            agents::handle_agent_select_key(browser, key, &mut selected);
+            agents::handle_agent_select_key(browser, key, &agents, &mut selected);
             if let TuiView::AgentSelect {
                 selected: ref mut s,
+                ..
             } = browser.view
             {
                 *s = selected;
@@ -285,7 +340,8 @@ pub async fn handle_key_event(browser: &mut IssueBrowser, key: KeyCode, modifier
         } => {
             let worktrees_clone = worktrees.clone();
             let mut selected = *selected;
-            worktree::handle_worktree_list_key(browser, key, &worktrees_clone, &mut selected);
+            worktree::handle_worktree_list_key(browser, key, &worktrees_clone, &mut selected)
+                .await;
             if let TuiView::WorktreeList {
                 selected: ref mut s,
                 ..
@@ -359,7 +415,7 @@ pub async fn handle_key_event(browser: &mut IssueBrowser, key: KeyCode, modifier
 
         TuiView::PullRequestDetail(pr_detail) => {
             let pr_detail = pr_detail.clone();
-            pr::handle_pr_detail_key(browser, key, &pr_detail);
+            pr::handle_pr_detail_key(browser, key, &pr_detail).await;
         }
 
         TuiView::ConfirmMerge { pr: pr_detail } => {
@@ -382,6 +438,132 @@ pub async fn handle_key_event(browser: &mut IssueBrowser, key: KeyCode, modifier
             }
         }
 
+        TuiView::PrReviewers {
+            pr: pr_detail,
+            input,
+            suggestions,
+            selected,
+        } => {
+            let pr_detail = pr_detail.clone();
+            let input = input.clone();
+            let suggestions = suggestions.clone();
+            let selected = *selected;
+            pr::handle_pr_reviewers_key(browser, key, &pr_detail, &input, &suggestions, selected)
+                .await;
+        }
+
+        TuiView::EditPullRequest {
+            pr: pr_detail,
+            title,
+            body,
+            base,
+            field,
+        } => {
+            let pr_detail = pr_detail.clone();
+            let mut title = title.clone();
+            let mut body = body.clone();
+            let mut base = base.clone();
+            let mut field = *field;
+            pr::handle_edit_pr_key(
+                browser,
+                key,
+                modifiers,
+                &pr_detail,
+                &mut title,
+                &mut body,
+                &mut base,
+                &mut field,
+            )
+            .await;
+            if let TuiView::EditPullRequest {
+                title: ref mut t,
+                body: ref mut b,
+                base: ref mut ba,
+                field: ref mut f,
+                ..
+            } = browser.view
+            {
+                *t = title;
+                *b = body;
+                *ba = base;
+                *f = field;
+            }
+        }
+
+        TuiView::ConfirmClosePr { pr: pr_detail } => {
+            let pr_detail = pr_detail.clone();
+            pr::handle_confirm_close_pr_key(browser, key, &pr_detail).await;
+        }
+
+        TuiView::ConfirmReopenPr { pr: pr_detail } => {
+            let pr_detail = pr_detail.clone();
+            pr::handle_confirm_reopen_pr_key(browser, key, &pr_detail).await;
+        }
+
+        TuiView::PrLabels {
+            pr: pr_detail,
+            labels,
+            selected,
+        } => {
+            let pr_detail = pr_detail.clone();
+            let labels = labels.clone();
+            let mut selected = *selected;
+            pr::handle_pr_labels_key(browser, key, &pr_detail, &labels, &mut selected).await;
+            if let TuiView::PrLabels {
+                selected: ref mut s,
+                ..
+            } = browser.view
+            {
+                *s = selected;
+            }
+        }
+
+        TuiView::ReviewFindings {
+            pr: pr_detail,
+            findings,
+            selected,
+            edit,
+            event,
+        } => {
+            let pr_detail = pr_detail.clone();
+            let mut findings = findings.clone();
+            let mut selected = *selected;
+            let mut edit = edit.clone();
+            let mut event = *event;
+            pr::handle_review_findings_key(
+                browser,
+                key,
+                modifiers,
+                &pr_detail,
+                &mut findings,
+                &mut selected,
+                &mut edit,
+                &mut event,
+            )
+            .await;
+            if let TuiView::ReviewFindings {
+                findings: ref mut fi,
+                selected: ref mut s,
+                edit: ref mut ed,
+                event: ref mut ev,
+                ..
+            } = browser.view
+            {
+                *fi = findings;
+                *s = selected;
+                *ed = edit;
+                *ev = event;
+            }
+        }
+
+        TuiView::CreatePullRequest(form) => {
+            let mut form = form.clone();
+            pr::handle_create_pull_request_key(browser, key, modifiers, &mut form).await;
+            if let TuiView::CreatePullRequest(ref mut f) = browser.view {
+                *f = form;
+            }
+        }
+
         TuiView::PrFilters {
             status_filter,
             author_filter,
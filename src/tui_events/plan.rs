@@ -0,0 +1,139 @@
+//! Implementation plan review event handling.
+
+use crate::agents::{send_to_session, SessionManager};
+use crate::plan::{
+    build_implement_prompt, build_revision_prompt, clear_plan, load_plan, plan_comment, PlanField,
+    PlanReview,
+};
+use crate::tui::IssueBrowser;
+use crate::tui_types::TuiView;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/// Show the plan written by the agent dispatched plan first on an issue.
+pub fn open_plan_review(browser: &mut IssueBrowser, issue_number: u64) {
+    let Some(session) = browser.session_cache.get(&issue_number).cloned() else {
+        browser.status_message = Some(format!("No agent session for #{}.", issue_number));
+        return;
+    };
+    if !session.planning {
+        browser.status_message = Some(format!(
+            "#{} was not dispatched plan first (Ctrl+P in the dispatch popup).",
+            issue_number
+        ));
+        return;
+    }
+    match load_plan(&session.worktree_path, &session.log_file) {
+        Ok(plan) => browser.view = TuiView::PlanReview(PlanReview::new(session, plan)),
+        Err(e) => browser.status_message = Some(e),
+    }
+}
+
+/// Handle a key in the plan review: edit, post, approve or send back the plan.
+pub async fn handle_plan_review_key(
+    browser: &mut IssueBrowser,
+    key: KeyCode,
+    modifiers: KeyModifiers,
+) {
+    let TuiView::PlanReview(review) = &mut browser.view else {
+        return;
+    };
+
+    // Editing the plan or the feedback
+    if let Some((field, buffer)) = &mut review.editing {
+        let save = match key {
+            KeyCode::Enter => modifiers.contains(KeyModifiers::SHIFT),
+            KeyCode::Char('s') => modifiers.contains(KeyModifiers::CONTROL),
+            _ => false,
+        };
+        if save {
+            match field {
+                PlanField::Plan => review.plan = buffer.clone(),
+                PlanField::Feedback => review.feedback = buffer.clone(),
+            }
+            review.editing = None;
+            return;
+        }
+        match key {
+            KeyCode::Esc => review.editing = None,
+            KeyCode::Enter => buffer.push('\n'),
+            KeyCode::Backspace => {
+                buffer.pop();
+            }
+            KeyCode::Char(c) => buffer.push(c),
+            _ => {}
+        }
+        return;
+    }
+
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') => browser.view = TuiView::List,
+        KeyCode::Up | KeyCode::Char('k') => review.scroll = review.scroll.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => review.scroll += 1,
+        KeyCode::Char('e') => review.editing = Some((PlanField::Plan, review.plan.clone())),
+        KeyCode::Char('f') => {
+            review.editing = Some((PlanField::Feedback, review.feedback.clone()));
+        }
+        KeyCode::Char('R') => {
+            match load_plan(&review.session.worktree_path, &review.session.log_file) {
+                Ok(plan) => {
+                    review.plan = plan;
+                    review.scroll = 0;
+                }
+                Err(e) => browser.status_message = Some(e),
+            }
+        }
+        KeyCode::Char('c') => {
+            let issue_number = review.session.issue_number;
+            let body = plan_comment(&review.plan);
+            match browser.github.add_comment(issue_number, &body).await {
+                Ok(url) => {
+                    browser.status_message = Some(format!("Plan posted on #{}", issue_number));
+                    if let TuiView::PlanReview(review) = &mut browser.view {
+                        review.comment_url = Some(url);
+                    }
+                }
+                Err(e) => browser.status_message = Some(format!("Failed to post plan: {}", e)),
+            }
+        }
+        KeyCode::Char('a') => {
+            let session = review.session.clone();
+            let prompt = build_implement_prompt(&review.plan, &review.feedback);
+            if let Err(e) = send_to_session(&session.session_name(), &prompt) {
+                browser.status_message = Some(format!("Failed to send the plan: {}", e));
+                return;
+            }
+            clear_plan(&session.worktree_path);
+            let _ = SessionManager::update(|manager| manager.approve_plan(&session.id));
+            browser.status_message = Some(format!(
+                "Plan approved: {} is implementing #{}.",
+                session.agent_type.display_name(),
+                session.issue_number
+            ));
+            browser.view = TuiView::List;
+            if let Some(project) = browser.project_name.clone() {
+                browser.refresh_sessions(&project);
+            }
+        }
+        KeyCode::Char('r') => {
+            if review.feedback.trim().is_empty() {
+                browser.status_message =
+                    Some("Add feedback with f before asking for a revision.".to_string());
+                return;
+            }
+            let session = review.session.clone();
+            let prompt = build_revision_prompt(&review.feedback);
+            match send_to_session(&session.session_name(), &prompt) {
+                Ok(()) => {
+                    clear_plan(&session.worktree_path);
+                    browser.status_message = Some(format!(
+                        "Asked for a revised plan for #{}. P: review it once ready",
+                        session.issue_number
+                    ));
+                    browser.view = TuiView::List;
+                }
+                Err(e) => browser.status_message = Some(format!("Failed to send feedback: {}", e)),
+            }
+        }
+        _ => {}
+    }
+}
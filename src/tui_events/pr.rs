@@ -1,10 +1,17 @@
 //! Pull request views event handling.
 
-use crate::github::PullRequestDetail;
+use std::path::PathBuf;
+
+use crate::config::NotifyEvent;
+use crate::github::{PullRequestDetail, ReviewEvent};
+use crate::notify::{self, Notification};
+use crate::pr_review::ReviewFindings;
 use crate::tui::IssueBrowser;
-use crate::tui_types::{PrFilterFocus, PrStatus, TuiView};
+use crate::tui_types::{
+    NewPullRequest, PrCreateField, PrEditField, PrFilterFocus, PrStatus, TuiView,
+};
 use crate::tui_utils::open_url;
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyModifiers};
 
 pub async fn handle_pr_list_key(browser: &mut IssueBrowser, key: KeyCode) {
     match key {
@@ -48,11 +55,17 @@ pub async fn handle_pr_list_key(browser: &mut IssueBrowser, key: KeyCode) {
                         Some("No local_path configured for this project.".to_string());
                     return;
                 }
-                let branch = pr.head_ref.clone();
-                browser.status_message =
-                    Some(format!("Creating worktree for branch: {}", branch));
-                // Reuse existing worktree creation logic
-                match browser.create_worktree_for_branch(&branch) {
+                // Search results may lack head info, so always fetch the full PR
+                let number = pr.number;
+                let detail = match browser.github.get_pull_request(number).await {
+                    Ok(detail) => detail,
+                    Err(e) => {
+                        browser.status_message =
+                            Some(format!("Failed to load PR #{}: {}", number, e));
+                        return;
+                    }
+                };
+                match browser.create_worktree_for_pr(&detail) {
                     Ok((path, _)) => {
                         browser.status_message =
                             Some(format!("Worktree created at: {}", path.display()));
@@ -64,6 +77,32 @@ pub async fn handle_pr_list_key(browser: &mut IssueBrowser, key: KeyCode) {
                 }
             }
         }
+        KeyCode::Char('F') => {
+            // Post review findings from the PR worktree
+            if let Some(pr) = browser.selected_pr() {
+                let number = pr.number;
+                match browser.github.get_pull_request(number).await {
+                    Ok(detail) => open_review_findings(browser, detail).await,
+                    Err(e) => {
+                        browser.status_message =
+                            Some(format!("Failed to load PR #{}: {}", number, e));
+                    }
+                }
+            }
+        }
+        KeyCode::Char('C') => {
+            // Dispatch an agent to fix failing checks
+            if let Some(pr) = browser.selected_pr() {
+                let number = pr.number;
+                match browser.github.get_pull_request(number).await {
+                    Ok(detail) => fix_failing_ci(browser, &detail).await,
+                    Err(e) => {
+                        browser.status_message =
+                            Some(format!("Failed to load PR #{}: {}", number, e));
+                    }
+                }
+            }
+        }
         KeyCode::Char('r') => {
             // Review PR with agent
             if let Some(pr) = browser.selected_pr() {
@@ -119,11 +158,13 @@ pub async fn handle_pr_list_key(browser: &mut IssueBrowser, key: KeyCode) {
     }
 }
 
-pub fn handle_pr_detail_key(
+pub async fn handle_pr_detail_key(
     browser: &mut IssueBrowser,
     key: KeyCode,
     pr: &PullRequestDetail,
 ) {
+    let is_open = pr.state.to_lowercase().contains("open");
+
     match key {
         KeyCode::Esc | KeyCode::Char('q') => {
             browser.view = TuiView::PullRequestList;
@@ -151,10 +192,355 @@ pub fn handle_pr_detail_key(
                 input: String::new(),
             };
         }
+        KeyCode::Char('D') => {
+            if !is_open {
+                browser.status_message = Some("PR is not open".to_string());
+                return;
+            }
+            let to_draft = !pr.draft;
+            match browser.github.set_pull_request_draft(pr.number, to_draft).await {
+                Ok(()) => {
+                    browser.status_message = Some(if to_draft {
+                        format!("PR #{} converted to draft", pr.number)
+                    } else {
+                        format!("PR #{} marked ready for review", pr.number)
+                    });
+                    if let Some(summary) = browser
+                        .all_pull_requests
+                        .iter_mut()
+                        .find(|p| p.number == pr.number)
+                    {
+                        summary.draft = to_draft;
+                    }
+                    browser.apply_pr_filters();
+                    reload_pr_detail(browser, pr.number).await;
+                }
+                Err(e) => {
+                    browser.status_message = Some(format!("Failed to update draft state: {}", e));
+                }
+            }
+        }
+        KeyCode::Char('v') => {
+            browser.load_reviewers().await;
+            let suggestions = browser.get_reviewer_suggestions("");
+            browser.view = TuiView::PrReviewers {
+                pr: pr.clone(),
+                input: String::new(),
+                suggestions,
+                selected: 0,
+            };
+        }
+        KeyCode::Char('e') => {
+            browser.view = TuiView::EditPullRequest {
+                pr: pr.clone(),
+                title: pr.title.clone(),
+                body: pr.body.clone().unwrap_or_default(),
+                base: pr.base_ref.clone(),
+                field: PrEditField::Title,
+            };
+        }
+        KeyCode::Char('x') => {
+            if is_open {
+                browser.view = TuiView::ConfirmClosePr { pr: pr.clone() };
+            } else {
+                browser.status_message = Some("PR is already closed".to_string());
+            }
+        }
+        KeyCode::Char('X') => {
+            if is_open {
+                browser.status_message = Some("PR is already open".to_string());
+            } else {
+                browser.view = TuiView::ConfirmReopenPr { pr: pr.clone() };
+            }
+        }
+        KeyCode::Char('F') => {
+            open_review_findings(browser, pr.clone()).await;
+        }
+        KeyCode::Char('a') => {
+            if !is_open {
+                browser.status_message = Some("PR is not open".to_string());
+                return;
+            }
+            address_review_feedback(browser, pr).await;
+        }
+        KeyCode::Char('A') => {
+            reply_feedback_addressed(browser, pr).await;
+        }
+        KeyCode::Char('C') => {
+            if !is_open {
+                browser.status_message = Some("PR is not open".to_string());
+                return;
+            }
+            fix_failing_ci(browser, pr).await;
+        }
+        KeyCode::Char('l') => {
+            let mut labels = browser.project_labels.clone();
+            for label in &pr.labels {
+                if !labels.contains(label) {
+                    labels.push(label.clone());
+                }
+            }
+            browser.view = TuiView::PrLabels {
+                pr: pr.clone(),
+                labels,
+                selected: 0,
+            };
+        }
         _ => {}
     }
 }
 
+/// Send unresolved review threads and PR comments to an agent working on the PR branch.
+///
+/// Reuses the running session for the branch when there is one, otherwise starts an agent
+/// in the PR worktree.
+async fn address_review_feedback(browser: &mut IssueBrowser, pr: &PullRequestDetail) {
+    let Some(project) = browser.project_name.clone() else {
+        browser.status_message = Some("No project configured".to_string());
+        return;
+    };
+
+    let threads = match browser.github.list_unresolved_review_threads(pr.number).await {
+        Ok(threads) => threads,
+        Err(e) => {
+            browser.status_message = Some(format!("Failed to load review threads: {}", e));
+            return;
+        }
+    };
+    let has_comments = pr.comments.iter().any(|c| c.author != pr.author);
+    if threads.is_empty() && !has_comments {
+        browser.status_message = Some(format!("No review feedback on PR #{}", pr.number));
+        return;
+    }
+
+    let prompt = crate::agents::build_review_feedback_prompt(pr, &threads, &pr.comments, None);
+
+    // Existing issue session working on this branch
+    let issue_session = browser
+        .session_cache
+        .values()
+        .find(|s| {
+            (s.branch_name == pr.head_ref || s.pr_url.as_deref() == Some(pr.html_url.as_str()))
+                && crate::agents::is_session_running(&s.session_name())
+        })
+        .map(|s| (s.session_name(), s.worktree_path.clone()));
+
+    let pr_session = format!("{}-pr-{}", project, pr.number);
+    let (session_name, worktree_path, reused) = match issue_session {
+        Some((name, path)) => (name, path, true),
+        None if crate::agents::is_session_running(&pr_session) => (
+            pr_session,
+            crate::agents::pr_worktree_path(&project, pr.number),
+            true,
+        ),
+        None => {
+            let worktree_path = match browser.create_worktree_for_pr(pr) {
+                Ok((path, _)) => path,
+                Err(e) => {
+                    browser.status_message = Some(format!("Failed to create worktree: {}", e));
+                    return;
+                }
+            };
+            (pr_session, worktree_path, false)
+        }
+    };
+
+    let Some(base_sha) = crate::agents::head_sha(&worktree_path) else {
+        browser.status_message = Some(format!(
+            "Cannot read HEAD of {}",
+            worktree_path.display()
+        ));
+        return;
+    };
+
+    let sent = if reused {
+        crate::agents::send_to_session(&session_name, &prompt)
+    } else {
+        crate::agents::launch_agent_interactive(
+            &worktree_path,
+            &session_name,
+            &crate::agents::resolve_agent_profile(&project, &pr.labels, &browser.coding_agent),
+            Some(&prompt),
+        )
+    };
+    if let Err(e) = sent {
+        browser.status_message = Some(format!("Failed to send feedback: {}", e));
+        return;
+    }
+
+    let pending = crate::pr_review::PendingFeedback {
+        base_sha,
+        comment_ids: threads.iter().map(|t| t.comment_id).collect(),
+    };
+    if let Err(e) = crate::pr_review::save_pending_feedback(&worktree_path, &pending) {
+        browser.status_message = Some(format!("Failed to record pending feedback: {}", e));
+        return;
+    }
+
+    browser.status_message = Some(format!(
+        "Sent {} thread(s) to {} — press A once the fix is committed",
+        threads.len(),
+        session_name
+    ));
+}
+
+/// Push the agent's fix and reply "Addressed in <sha>" on the threads it was given.
+async fn reply_feedback_addressed(browser: &mut IssueBrowser, pr: &PullRequestDetail) {
+    let Some(project) = browser.project_name.clone() else {
+        browser.status_message = Some("No project configured".to_string());
+        return;
+    };
+
+    // The feedback may have gone to an issue session's worktree or to the PR worktree
+    let candidates: Vec<PathBuf> = browser
+        .session_cache
+        .values()
+        .filter(|s| {
+            s.branch_name == pr.head_ref || s.pr_url.as_deref() == Some(pr.html_url.as_str())
+        })
+        .map(|s| s.worktree_path.clone())
+        .chain(std::iter::once(crate::agents::pr_worktree_path(&project, pr.number)))
+        .collect();
+    let Some((worktree_path, pending)) = candidates
+        .into_iter()
+        .find_map(|path| crate::pr_review::load_pending_feedback(&path).map(|p| (path, p)))
+    else {
+        browser.status_message = Some(format!("No pending feedback for PR #{}", pr.number));
+        return;
+    };
+
+    let Some(sha) = crate::agents::head_sha(&worktree_path) else {
+        browser.status_message = Some(format!(
+            "Cannot read HEAD of {}",
+            worktree_path.display()
+        ));
+        return;
+    };
+    if sha == pending.base_sha {
+        browser.status_message = Some("No new commits since the feedback was sent".to_string());
+        return;
+    }
+
+    let branch = crate::agents::current_branch(&worktree_path).unwrap_or(pr.head_ref.clone());
+    if let Err(e) = crate::agents::push_branch(&worktree_path, &branch) {
+        browser.status_message = Some(format!("Push failed: {}", e));
+        return;
+    }
+
+    let short_sha = &sha[..sha.len().min(7)];
+    let reply = format!("Addressed in {}", short_sha);
+    let mut failed = Vec::new();
+    for comment_id in &pending.comment_ids {
+        if browser
+            .github
+            .reply_to_review_comment(pr.number, *comment_id, &reply)
+            .await
+            .is_err()
+        {
+            failed.push(*comment_id);
+        }
+    }
+
+    if failed.is_empty() {
+        crate::pr_review::clear_pending_feedback(&worktree_path);
+        browser.status_message = Some(format!(
+            "Pushed {} and replied on {} thread(s)",
+            short_sha,
+            pending.comment_ids.len()
+        ));
+    } else {
+        browser.status_message = Some(format!(
+            "Pushed {} but {} of {} replies failed",
+            short_sha,
+            failed.len(),
+            pending.comment_ids.len()
+        ));
+        // Keep only the threads still waiting for a reply
+        let retry = crate::pr_review::PendingFeedback {
+            base_sha: pending.base_sha,
+            comment_ids: failed,
+        };
+        let _ = crate::pr_review::save_pending_feedback(&worktree_path, &retry);
+    }
+    reload_pr_detail(browser, pr.number).await;
+}
+
+/// Dispatch an agent into the PR worktree with the logs of its failing checks.
+async fn fix_failing_ci(browser: &mut IssueBrowser, pr: &PullRequestDetail) {
+    let Some(project) = browser.project_name.clone() else {
+        browser.status_message = Some("No project configured".to_string());
+        return;
+    };
+
+    let checks = match browser.github.list_failed_checks(&pr.head_sha).await {
+        Ok(checks) => checks,
+        Err(e) => {
+            browser.status_message = Some(format!("Failed to load checks: {}", e));
+            return;
+        }
+    };
+    if checks.is_empty() {
+        browser.status_message = Some(format!("No failing checks on PR #{}", pr.number));
+        return;
+    }
+
+    let mut failures = Vec::new();
+    for check in checks {
+        let log = if check.is_actions_job {
+            browser
+                .github
+                .download_job_log(check.id)
+                .await
+                .ok()
+                .map(|log| crate::ci::trim_log(&log))
+        } else {
+            None
+        };
+        failures.push(crate::ci::CheckFailure { check, log });
+    }
+
+    let (worktree_path, branch) = match browser.create_worktree_for_pr(pr) {
+        Ok(created) => created,
+        Err(e) => {
+            browser.status_message = Some(format!("Failed to create worktree: {}", e));
+            return;
+        }
+    };
+
+    let prompt = crate::ci::build_ci_fix_prompt(pr, &failures);
+    match crate::agents::dispatch_to_pr_worktree(
+        pr,
+        &worktree_path,
+        &branch,
+        &project,
+        &browser.coding_agent,
+        format!("Fix CI: {}", pr.title),
+        &prompt,
+        None,
+    ) {
+        Ok(_) => {
+            browser.status_message = Some(format!(
+                "Agent dispatched to fix {} failing check(s) on PR #{}",
+                failures.len(),
+                pr.number
+            ));
+            browser.refresh_sessions(&project);
+        }
+        Err(e) => {
+            browser.status_message = Some(format!("Failed to start agent: {}", e));
+        }
+    }
+}
+
+/// Refetch a pull request and show its detail view (falls back to the PR list).
+async fn reload_pr_detail(browser: &mut IssueBrowser, number: u64) {
+    if let Ok(detail) = browser.github.get_pull_request(number).await {
+        browser.view = TuiView::PullRequestDetail(detail);
+    } else {
+        browser.view = TuiView::PullRequestList;
+    }
+}
+
 pub async fn handle_confirm_merge_key(
     browser: &mut IssueBrowser,
     key: KeyCode,
@@ -202,28 +588,10 @@ pub async fn handle_dispatch_pr_review_key(
                 return;
             }
 
-            // Create worktree for PR branch
-            let branch = pr.head_ref.clone();
-            match browser.create_worktree_for_branch(&branch) {
+            // Create worktree for the PR head
+            match browser.create_worktree_for_pr(pr) {
                 Ok((worktree_path, _)) => {
-                    // Build review prompt
-                    let mut review_prompt =
-                        format!("Review this PR #{}: \"{}\"\n\n", pr.number, pr.title);
-                    if let Some(body) = &pr.body {
-                        review_prompt.push_str(&format!("Description:\n{}\n\n", body));
-                    }
-                    review_prompt.push_str(
-                        "Please review the code changes, check for:\n\
-                        - Code quality and best practices\n\
-                        - Potential bugs or edge cases\n\
-                        - Performance concerns\n\
-                        - Security issues\n\n\
-                        Provide a summary of your findings.",
-                    );
-                    if !input.is_empty() {
-                        review_prompt
-                            .push_str(&format!("\n\nAdditional instructions:\n{}", input));
-                    }
+                    let review_prompt = crate::pr_review::build_review_prompt(pr, input);
 
                     // Launch agent
                     let project_name = browser.current_project.clone();
@@ -234,7 +602,7 @@ pub async fn handle_dispatch_pr_review_key(
                             browser.refresh_sessions(&project_name);
 
                             // Open embedded terminal to show the agent
-                            let sessions = crate::agents::list_tmux_sessions();
+                            let sessions = crate::agents::list_issue_sessions();
                             let mut all_sessions = sessions;
                             if !all_sessions.contains(&session_name) {
                                 all_sessions.push(session_name.clone());
@@ -286,3 +654,677 @@ pub async fn handle_dispatch_pr_review_key(
         _ => {}
     }
 }
+
+pub async fn handle_pr_reviewers_key(
+    browser: &mut IssueBrowser,
+    key: KeyCode,
+    pr: &PullRequestDetail,
+    input: &str,
+    suggestions: &[String],
+    selected: usize,
+) {
+    let number = pr.number;
+
+    match key {
+        KeyCode::Esc => {
+            reload_pr_detail(browser, number).await;
+        }
+        KeyCode::Up => {
+            if let TuiView::PrReviewers { selected, .. } = &mut browser.view
+                && *selected > 0
+            {
+                *selected -= 1;
+            }
+        }
+        KeyCode::Down => {
+            let sugg_len = suggestions.len();
+            if let TuiView::PrReviewers { selected, .. } = &mut browser.view
+                && *selected < sugg_len.saturating_sub(1)
+            {
+                *selected += 1;
+            }
+        }
+        KeyCode::Enter => {
+            // Toggle: request a review, or remove the request if already pending
+            if let Some(user) = suggestions.get(selected) {
+                let user = user.clone();
+                let already_requested = pr.requested_reviewers.contains(&user);
+                let result = if already_requested {
+                    browser
+                        .github
+                        .remove_reviewers(number, std::slice::from_ref(&user))
+                        .await
+                } else {
+                    browser
+                        .github
+                        .request_reviewers(number, std::slice::from_ref(&user))
+                        .await
+                };
+
+                browser.status_message = Some(match (result, already_requested) {
+                    (Ok(()), true) => format!("Removed review request for {}", user),
+                    (Ok(()), false) => format!("Requested review from {}", user),
+                    (Err(e), _) => format!("Failed to update reviewers: {}", e),
+                });
+
+                match browser.github.get_pull_request(number).await {
+                    Ok(detail) => {
+                        let new_suggestions = browser.get_reviewer_suggestions(input);
+                        browser.view = TuiView::PrReviewers {
+                            pr: detail,
+                            input: input.to_string(),
+                            suggestions: new_suggestions,
+                            selected,
+                        };
+                    }
+                    Err(_) => browser.view = TuiView::PullRequestList,
+                }
+            }
+        }
+        KeyCode::Backspace => {
+            let mut new_input = input.to_string();
+            new_input.pop();
+            let new_suggestions = browser.get_reviewer_suggestions(&new_input);
+            if let TuiView::PrReviewers {
+                input: ref mut inp,
+                suggestions: ref mut sug,
+                selected: ref mut sel,
+                ..
+            } = browser.view
+            {
+                *inp = new_input;
+                *sug = new_suggestions;
+                *sel = 0;
+            }
+        }
+        KeyCode::Char(c) => {
+            let mut new_input = input.to_string();
+            new_input.push(c);
+            let new_suggestions = browser.get_reviewer_suggestions(&new_input);
+            if let TuiView::PrReviewers {
+                input: ref mut inp,
+                suggestions: ref mut sug,
+                selected: ref mut sel,
+                ..
+            } = browser.view
+            {
+                *inp = new_input;
+                *sug = new_suggestions;
+                *sel = 0;
+            }
+        }
+        _ => {}
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_edit_pr_key(
+    browser: &mut IssueBrowser,
+    key: KeyCode,
+    modifiers: KeyModifiers,
+    pr: &PullRequestDetail,
+    title: &mut String,
+    body: &mut String,
+    base: &mut String,
+    field: &mut PrEditField,
+) {
+    match key {
+        KeyCode::Esc => {
+            browser.view = TuiView::PullRequestDetail(pr.clone());
+        }
+        KeyCode::Tab => {
+            *field = field.next();
+        }
+        KeyCode::Enter if modifiers.contains(KeyModifiers::SHIFT) => {
+            submit_pr_edit(browser, pr, title, body, base).await;
+        }
+        KeyCode::Char('s') if modifiers.contains(KeyModifiers::CONTROL) => {
+            submit_pr_edit(browser, pr, title, body, base).await;
+        }
+        KeyCode::Enter => match field {
+            PrEditField::Body => body.push('\n'),
+            _ => *field = field.next(),
+        },
+        KeyCode::Backspace => {
+            match field {
+                PrEditField::Title => title.pop(),
+                PrEditField::Body => body.pop(),
+                PrEditField::Base => base.pop(),
+            };
+        }
+        KeyCode::Char(c) => match field {
+            PrEditField::Title => title.push(c),
+            PrEditField::Body => body.push(c),
+            PrEditField::Base => base.push(c),
+        },
+        _ => {}
+    }
+}
+
+/// Send only the fields that differ from the current pull request.
+async fn submit_pr_edit(
+    browser: &mut IssueBrowser,
+    pr: &PullRequestDetail,
+    title: &str,
+    body: &str,
+    base: &str,
+) {
+    if title.trim().is_empty() {
+        browser.status_message = Some("Title cannot be empty".to_string());
+        return;
+    }
+    if base.trim().is_empty() {
+        browser.status_message = Some("Base branch cannot be empty".to_string());
+        return;
+    }
+
+    let new_title = (title != pr.title).then_some(title);
+    let new_body = (body != pr.body.as_deref().unwrap_or_default()).then_some(body);
+    let new_base = (base.trim() != pr.base_ref).then_some(base.trim());
+
+    if new_title.is_none() && new_body.is_none() && new_base.is_none() {
+        browser.view = TuiView::PullRequestDetail(pr.clone());
+        return;
+    }
+
+    match browser
+        .github
+        .update_pull_request(pr.number, new_title, new_body, new_base)
+        .await
+    {
+        Ok(()) => {
+            browser.status_message = Some(format!("PR #{} updated", pr.number));
+            if let Some(summary) = browser
+                .all_pull_requests
+                .iter_mut()
+                .find(|p| p.number == pr.number)
+            {
+                summary.title = title.to_string();
+                summary.base_ref = base.trim().to_string();
+            }
+            browser.apply_pr_filters();
+            reload_pr_detail(browser, pr.number).await;
+        }
+        Err(e) => {
+            browser.status_message = Some(format!("Failed to update PR: {}", e));
+        }
+    }
+}
+
+pub async fn handle_confirm_close_pr_key(
+    browser: &mut IssueBrowser,
+    key: KeyCode,
+    pr: &PullRequestDetail,
+) {
+    match key {
+        KeyCode::Char('y') | KeyCode::Char('Y') => {
+            match browser.github.close_pull_request(pr.number).await {
+                Ok(()) => {
+                    browser.status_message = Some(format!("PR #{} closed", pr.number));
+                    browser.reload_pull_requests().await;
+                    browser.view = TuiView::PullRequestList;
+                }
+                Err(e) => {
+                    browser.status_message = Some(format!("Failed to close PR: {}", e));
+                    browser.view = TuiView::PullRequestDetail(pr.clone());
+                }
+            }
+        }
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+            browser.view = TuiView::PullRequestDetail(pr.clone());
+        }
+        _ => {}
+    }
+}
+
+pub async fn handle_confirm_reopen_pr_key(
+    browser: &mut IssueBrowser,
+    key: KeyCode,
+    pr: &PullRequestDetail,
+) {
+    match key {
+        KeyCode::Char('y') | KeyCode::Char('Y') => {
+            match browser.github.reopen_pull_request(pr.number).await {
+                Ok(()) => {
+                    browser.status_message = Some(format!("PR #{} reopened", pr.number));
+                    reload_pr_detail(browser, pr.number).await;
+                }
+                Err(e) => {
+                    browser.status_message = Some(format!("Failed to reopen PR: {}", e));
+                    browser.view = TuiView::PullRequestDetail(pr.clone());
+                }
+            }
+        }
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+            browser.view = TuiView::PullRequestDetail(pr.clone());
+        }
+        _ => {}
+    }
+}
+
+pub async fn handle_pr_labels_key(
+    browser: &mut IssueBrowser,
+    key: KeyCode,
+    pr: &PullRequestDetail,
+    labels: &[String],
+    selected: &mut usize,
+) {
+    match key {
+        KeyCode::Esc => {
+            reload_pr_detail(browser, pr.number).await;
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            *selected = selected.saturating_sub(1);
+        }
+        KeyCode::Down | KeyCode::Char('j') if *selected < labels.len().saturating_sub(1) => {
+            *selected += 1;
+        }
+        KeyCode::Enter | KeyCode::Char(' ') => {
+            let Some(label) = labels.get(*selected) else {
+                return;
+            };
+            let has_label = pr.labels.contains(label);
+            let result = if has_label {
+                browser.github.remove_label(pr.number, label).await
+            } else {
+                browser
+                    .github
+                    .add_labels(pr.number, std::slice::from_ref(label))
+                    .await
+            };
+
+            match result {
+                Ok(()) => {
+                    let mut updated = pr.clone();
+                    if has_label {
+                        updated.labels.retain(|l| l != label);
+                        browser.status_message = Some(format!("Removed label {}", label));
+                    } else {
+                        updated.labels.push(label.clone());
+                        browser.status_message = Some(format!("Added label {}", label));
+                    }
+                    if let Some(summary) = browser
+                        .all_pull_requests
+                        .iter_mut()
+                        .find(|p| p.number == pr.number)
+                    {
+                        summary.labels = updated.labels.clone();
+                    }
+                    browser.apply_pr_filters();
+                    if let TuiView::PrLabels { pr: ref mut p, .. } = browser.view {
+                        *p = updated;
+                    }
+                }
+                Err(e) => {
+                    browser.status_message = Some(format!("Failed to update labels: {}", e));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Prepare the pull request preview for a worktree: template, LLM-written
+/// description of the diff and linked issue, base branch and reviewer suggestions.
+///
+/// Uncommitted changes are committed first, so that the description matches what is pushed.
+pub async fn open_create_pull_request(
+    browser: &mut IssueBrowser,
+    worktree_path: PathBuf,
+    branch: String,
+    issue_number: Option<u64>,
+    session_id: Option<String>,
+    return_to_worktrees: bool,
+) {
+    // Work made only of new files is not in the diff against the merge-base
+    if crate::agents::get_diff(&worktree_path).trim().is_empty()
+        && crate::agents::get_uncommitted_diff(&worktree_path).trim().is_empty()
+    {
+        browser.status_message = Some(format!("No changes on {} to open a PR for", branch));
+        return;
+    }
+
+    let issue = match issue_number {
+        Some(number) => browser.github.get_issue(number).await.ok(),
+        None => None,
+    };
+
+    if crate::agents::has_uncommitted_changes(&worktree_path) {
+        let changes = crate::agents::get_uncommitted_diff(&worktree_path);
+        let message = crate::pull_requests::generate_commit_message(
+            issue.as_ref(),
+            &changes,
+            &browser.coding_agent,
+        )
+        .unwrap_or_else(|_| crate::pull_requests::fallback_commit_message(issue.as_ref(), &branch));
+        match crate::agents::commit_all(&worktree_path, &message) {
+            Ok(true) => {
+                let summary = message.lines().next().unwrap_or_default();
+                browser.status_message = Some(format!("Committed changes: {}", summary));
+            }
+            Ok(false) => {}
+            Err(e) => {
+                browser.status_message = Some(format!("Failed to commit changes: {}", e));
+                return;
+            }
+        }
+    }
+
+    let diff = crate::agents::get_diff(&worktree_path);
+    let template = crate::pull_requests::find_template(&worktree_path);
+
+    let content = match crate::pull_requests::generate_pr_content(
+        issue.as_ref(),
+        &diff,
+        template.as_deref(),
+        &browser.coding_agent,
+    ) {
+        Ok(content) => content,
+        Err(e) => {
+            browser.status_message = Some(format!("Description generation failed: {}", e));
+            crate::pull_requests::fallback_content(issue.as_ref(), &branch, template.as_deref())
+        }
+    };
+
+    let base = browser
+        .base_branch
+        .clone()
+        .or_else(|| crate::agents::default_branch(&worktree_path))
+        .unwrap_or_else(|| "main".to_string());
+
+    browser.load_reviewers().await;
+    let suggestions = browser.get_reviewer_suggestions("");
+
+    browser.view = TuiView::CreatePullRequest(NewPullRequest {
+        worktree_path,
+        branch,
+        issue_number,
+        session_id,
+        title: content.title,
+        body: content.body,
+        base,
+        draft: false,
+        reviewers: vec![],
+        reviewer_input: String::new(),
+        suggestions,
+        selected: 0,
+        field: PrCreateField::Title,
+        return_to_worktrees,
+    });
+}
+
+pub async fn handle_create_pull_request_key(
+    browser: &mut IssueBrowser,
+    key: KeyCode,
+    modifiers: KeyModifiers,
+    form: &mut NewPullRequest,
+) {
+    match key {
+        KeyCode::Esc => {
+            leave_create_pull_request(browser, form.return_to_worktrees);
+        }
+        KeyCode::Tab => {
+            form.field = form.field.next();
+        }
+        KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => {
+            form.draft = !form.draft;
+        }
+        KeyCode::Enter if modifiers.contains(KeyModifiers::SHIFT) => {
+            submit_pull_request(browser, form).await;
+        }
+        KeyCode::Char('s') if modifiers.contains(KeyModifiers::CONTROL) => {
+            submit_pull_request(browser, form).await;
+        }
+        KeyCode::Up if form.field == PrCreateField::Reviewers => {
+            form.selected = form.selected.saturating_sub(1);
+        }
+        KeyCode::Down if form.field == PrCreateField::Reviewers => {
+            form.selected = (form.selected + 1).min(form.suggestions.len().saturating_sub(1));
+        }
+        KeyCode::Enter => match form.field {
+            PrCreateField::Body => form.body.push('\n'),
+            PrCreateField::Reviewers => {
+                if let Some(user) = form.suggestions.get(form.selected).cloned() {
+                    if let Some(pos) = form.reviewers.iter().position(|r| *r == user) {
+                        form.reviewers.remove(pos);
+                    } else {
+                        form.reviewers.push(user);
+                    }
+                }
+            }
+            _ => form.field = form.field.next(),
+        },
+        KeyCode::Backspace => match form.field {
+            PrCreateField::Title => {
+                form.title.pop();
+            }
+            PrCreateField::Body => {
+                form.body.pop();
+            }
+            PrCreateField::Base => {
+                form.base.pop();
+            }
+            PrCreateField::Reviewers => {
+                form.reviewer_input.pop();
+                form.suggestions = browser.get_reviewer_suggestions(&form.reviewer_input);
+                form.selected = 0;
+            }
+        },
+        KeyCode::Char(c) => match form.field {
+            PrCreateField::Title => form.title.push(c),
+            PrCreateField::Body => form.body.push(c),
+            PrCreateField::Base => form.base.push(c),
+            PrCreateField::Reviewers => {
+                form.reviewer_input.push(c);
+                form.suggestions = browser.get_reviewer_suggestions(&form.reviewer_input);
+                form.selected = 0;
+            }
+        },
+        _ => {}
+    }
+}
+
+fn leave_create_pull_request(browser: &mut IssueBrowser, return_to_worktrees: bool) {
+    if return_to_worktrees {
+        browser.view = TuiView::WorktreeList {
+            worktrees: browser.build_worktree_list(),
+            selected: 0,
+        };
+    } else {
+        browser.view = TuiView::List;
+    }
+}
+
+/// Push the head branch, open the PR and request reviewers.
+async fn submit_pull_request(browser: &mut IssueBrowser, form: &NewPullRequest) {
+    if form.title.trim().is_empty() {
+        browser.status_message = Some("Title cannot be empty".to_string());
+        return;
+    }
+    if form.base.trim().is_empty() {
+        browser.status_message = Some("Base branch cannot be empty".to_string());
+        return;
+    }
+
+    if let Err(e) = crate::agents::push_branch(&form.worktree_path, &form.branch) {
+        browser.status_message = Some(format!("Failed to push branch: {}", e));
+        return;
+    }
+
+    let (url, summary) = match browser
+        .github
+        .create_pull_request(
+            &form.branch,
+            form.base.trim(),
+            form.title.trim(),
+            &form.body,
+            form.draft,
+        )
+        .await
+    {
+        Ok(created) => created,
+        Err(e) => {
+            browser.status_message = Some(format!("Failed to create PR: {}", e));
+            return;
+        }
+    };
+
+    let mut message = format!("PR created: {}", url);
+    if !form.reviewers.is_empty()
+        && let Err(e) = browser
+            .github
+            .request_reviewers(summary.number, &form.reviewers)
+            .await
+    {
+        message = format!("{} (requesting reviewers failed: {})", message, e);
+    }
+
+    if let Some(ref session_id) = form.session_id {
+        let _ = crate::agents::SessionManager::update(|m| m.set_pr_url(session_id, url.clone()));
+    }
+    let mut notification = Notification::new(
+        NotifyEvent::PrCreated,
+        "Pull request created",
+        &format!("#{} {}", summary.number, form.title),
+    )
+    .with_url(&url);
+    if let (Some(project), Some(issue)) = (&browser.project_name, form.issue_number) {
+        notification = notification.for_issue(project, issue);
+    }
+    notify::notify(notification);
+    if let Some(project) = browser.project_name.clone() {
+        browser.refresh_sessions(&project);
+    }
+
+    browser.all_pull_requests.insert(0, summary);
+    browser.apply_pr_filters();
+
+    browser.status_message = Some(message);
+    leave_create_pull_request(browser, form.return_to_worktrees);
+}
+
+/// Load the findings written by a review agent in the PR worktree and preview them.
+pub async fn open_review_findings(browser: &mut IssueBrowser, pr: PullRequestDetail) {
+    let Some(project) = browser.project_name.clone() else {
+        browser.status_message = Some("No project configured".to_string());
+        return;
+    };
+
+    let worktree_path = crate::agents::pr_worktree_path(&project, pr.number);
+    match crate::pr_review::load_findings(&worktree_path) {
+        Ok(findings) => {
+            browser.view = TuiView::ReviewFindings {
+                pr,
+                findings,
+                selected: 0,
+                edit: None,
+                event: ReviewEvent::Comment,
+            };
+        }
+        Err(e) => {
+            browser.status_message = Some(format!("Cannot load review findings: {}", e));
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_review_findings_key(
+    browser: &mut IssueBrowser,
+    key: KeyCode,
+    modifiers: KeyModifiers,
+    pr: &PullRequestDetail,
+    findings: &mut ReviewFindings,
+    selected: &mut usize,
+    edit: &mut Option<String>,
+    event: &mut ReviewEvent,
+) {
+    // Editing the selected item
+    if let Some(buffer) = edit {
+        match key {
+            KeyCode::Esc => *edit = None,
+            KeyCode::Enter if modifiers.contains(KeyModifiers::SHIFT) => {
+                save_finding_edit(findings, *selected, buffer);
+                *edit = None;
+            }
+            KeyCode::Char('s') if modifiers.contains(KeyModifiers::CONTROL) => {
+                save_finding_edit(findings, *selected, buffer);
+                *edit = None;
+            }
+            KeyCode::Enter => buffer.push('\n'),
+            KeyCode::Backspace => {
+                buffer.pop();
+            }
+            KeyCode::Char(c) => buffer.push(c),
+            _ => {}
+        }
+        return;
+    }
+
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            browser.view = TuiView::PullRequestDetail(pr.clone());
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            *selected = (*selected + 1).min(findings.comments.len());
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            *selected = selected.saturating_sub(1);
+        }
+        KeyCode::Enter | KeyCode::Char('e') => {
+            let current = if *selected == 0 {
+                findings.summary.clone()
+            } else {
+                findings.comments[*selected - 1].body.clone()
+            };
+            *edit = Some(current);
+        }
+        KeyCode::Char('d') | KeyCode::Delete => {
+            if *selected == 0 {
+                findings.summary.clear();
+            } else {
+                findings.comments.remove(*selected - 1);
+                *selected = (*selected).min(findings.comments.len());
+            }
+        }
+        KeyCode::Char('t') => {
+            *event = event.next();
+        }
+        KeyCode::Char('S') => {
+            if findings.summary.trim().is_empty() && findings.comments.is_empty() {
+                browser.status_message = Some("Nothing to submit".to_string());
+                return;
+            }
+            match browser
+                .github
+                .submit_review(pr.number, &findings.summary, *event, &findings.comments)
+                .await
+            {
+                Ok(()) => {
+                    if let Some(project) = browser.project_name.clone() {
+                        crate::pr_review::clear_findings(&crate::agents::pr_worktree_path(
+                            &project, pr.number,
+                        ));
+                    }
+                    browser.status_message = Some(format!(
+                        "Review submitted on PR #{} ({} inline comments)",
+                        pr.number,
+                        findings.comments.len()
+                    ));
+                    reload_pr_detail(browser, pr.number).await;
+                }
+                Err(e) => {
+                    browser.status_message = Some(format!("Failed to submit review: {}", e));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn save_finding_edit(findings: &mut ReviewFindings, selected: usize, buffer: &str) {
+    if selected == 0 {
+        findings.summary = buffer.to_string();
+    } else if let Some(comment) = findings.comments.get_mut(selected - 1) {
+        comment.body = buffer.to_string();
+    }
+}
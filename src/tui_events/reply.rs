@@ -0,0 +1,164 @@
+//! Quick reply popup event handling.
+
+use crate::agents::DialogAnswer;
+use crate::quick_reply::{awaiting_sessions, send_reply, QuickReply, QuickReplyPopup};
+use crate::tui::IssueBrowser;
+use crate::tui_types::TuiView;
+use crossterm::event::KeyCode;
+
+/// Open the quick reply popup on the agents waiting for input, starting with `first`.
+pub fn open_quick_reply(
+    browser: &mut IssueBrowser,
+    first: Option<&str>,
+    return_to_worktrees: bool,
+) {
+    let sessions = awaiting_sessions(browser.project_name.as_deref());
+    match QuickReplyPopup::new(sessions, first, return_to_worktrees) {
+        Some(popup) => browser.view = TuiView::QuickReply(popup),
+        None => browser.status_message = Some("No agent is waiting for input".to_string()),
+    }
+}
+
+/// Handle a key in the quick reply popup: answer the dialog, type a reply or switch agent.
+pub fn handle_quick_reply_key(browser: &mut IssueBrowser, key: KeyCode) {
+    let TuiView::QuickReply(popup) = &mut browser.view else {
+        return;
+    };
+
+    // Typing a free-text reply
+    if let Some(input) = &mut popup.input {
+        match key {
+            KeyCode::Esc => popup.input = None,
+            KeyCode::Enter => {
+                let text = input.trim().to_string();
+                if !text.is_empty() {
+                    reply(browser, QuickReply::Text(text));
+                }
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+        return;
+    }
+
+    let answer = match key {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            let return_to_worktrees = popup.return_to_worktrees;
+            leave(browser, return_to_worktrees);
+            return;
+        }
+        KeyCode::Tab | KeyCode::Char(']') => {
+            popup.cycle(true);
+            return;
+        }
+        KeyCode::BackTab | KeyCode::Char('[') => {
+            popup.cycle(false);
+            return;
+        }
+        KeyCode::Char('R') => {
+            popup.refresh();
+            return;
+        }
+        KeyCode::Char('i') => {
+            popup.input = Some(String::new());
+            return;
+        }
+        KeyCode::Char('t') => {
+            attach(browser);
+            return;
+        }
+        KeyCode::Char('y') => DialogAnswer::Approve,
+        KeyCode::Char('n') => DialogAnswer::Deny,
+        KeyCode::Char(c @ '1'..='9') => DialogAnswer::Option(c as u8 - b'0'),
+        _ => return,
+    };
+
+    // The agent may have moved on since the pane was captured: answer what it shows now
+    popup.refresh();
+    if popup.dialog.is_none() {
+        browser.status_message = Some("No dialog to answer: type a reply with i".to_string());
+        return;
+    }
+    if let DialogAnswer::Option(n) = answer
+        && !popup.options.is_empty()
+        && !popup.options.iter().any(|(option, _)| *option == n)
+    {
+        browser.status_message = Some(format!("No option {} in this dialog", n));
+        return;
+    }
+    reply(browser, QuickReply::Answer(answer));
+}
+
+/// Send a reply to the current agent, then move to the next one waiting.
+fn reply(browser: &mut IssueBrowser, reply: QuickReply) {
+    let TuiView::QuickReply(popup) = &mut browser.view else {
+        return;
+    };
+    let session = popup.session().clone();
+    if let Err(e) = send_reply(&session, &reply) {
+        browser.status_message =
+            Some(format!("Failed to reply to #{}: {}", session.issue_number, e));
+        return;
+    }
+    if popup.advance() {
+        browser.status_message = Some(format!(
+            "Replied to #{}, next: #{}",
+            session.issue_number,
+            popup.session().issue_number
+        ));
+    } else {
+        let return_to_worktrees = popup.return_to_worktrees;
+        leave(browser, return_to_worktrees);
+        browser.status_message = Some(format!(
+            "Replied to #{}, no other agent is waiting",
+            session.issue_number
+        ));
+    }
+}
+
+/// Attach to the current agent's session in the embedded terminal.
+fn attach(browser: &mut IssueBrowser) {
+    let TuiView::QuickReply(popup) = &browser.view else {
+        return;
+    };
+    let session_name = popup.session().session_name();
+    let return_to_worktrees = popup.return_to_worktrees;
+    let all_sessions = crate::agents::list_all_sessions();
+    let current_index = all_sessions
+        .iter()
+        .position(|s| s == &session_name)
+        .unwrap_or(0);
+    let area = crossterm::terminal::size().unwrap_or((80, 24));
+    match crate::embedded_term::EmbeddedTerminal::new(
+        &session_name,
+        area.1.saturating_sub(1),
+        area.0,
+    ) {
+        Ok(term) => {
+            browser.embedded_term = Some(term);
+            browser.view = TuiView::EmbeddedTmux {
+                available_sessions: all_sessions,
+                current_index,
+                return_to_worktrees,
+            };
+        }
+        Err(e) => browser.status_message = Some(format!("Failed to open terminal: {}", e)),
+    }
+}
+
+fn leave(browser: &mut IssueBrowser, return_to_worktrees: bool) {
+    if let Some(project) = browser.project_name.clone() {
+        browser.refresh_sessions(&project);
+    }
+    if return_to_worktrees {
+        browser.view = TuiView::WorktreeList {
+            worktrees: browser.build_worktree_list(),
+            selected: 0,
+        };
+    } else {
+        browser.view = TuiView::List;
+    }
+}
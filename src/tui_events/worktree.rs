@@ -6,7 +6,7 @@ use crate::tui_types::TuiView;
 use crossterm::event::KeyCode;
 use std::path::PathBuf;
 
-pub fn handle_worktree_list_key(
+pub async fn handle_worktree_list_key(
     browser: &mut IssueBrowser,
     key: KeyCode,
     worktrees: &[WorktreeInfo],
@@ -42,29 +42,81 @@ pub fn handle_worktree_list_key(
         }
         KeyCode::Char('p') => {
             // Create PR for selected worktree
-            if let Some(wt) = worktrees.get(*selected) {
-                if let Some(issue_num) = wt.issue_number {
-                    let manager = crate::agents::SessionManager::load();
-                    if let Some(session) = manager.get_by_issue(&wt.project, issue_num) {
-                        if session.is_running() {
-                            browser.status_message = Some("Agent is still running".to_string());
-                        } else if session.pr_url.is_some() {
-                            browser.status_message = Some("PR already created".to_string());
-                        } else {
-                            match crate::agents::create_pr(session, browser.base_branch.as_deref())
-                            {
-                                Ok(url) => {
-                                    browser.status_message = Some(format!("PR created: {}", url));
-                                }
-                                Err(e) => {
-                                    browser.status_message =
-                                        Some(format!("Failed to create PR: {}", e));
-                                }
-                            }
+            if let Some(wt) = worktrees.get(*selected).cloned() {
+                let manager = crate::agents::SessionManager::load();
+                let session = wt
+                    .issue_number
+                    .and_then(|n| manager.get_by_issue(&wt.project, n))
+                    .cloned();
+
+                match session {
+                    Some(session) if session.is_running() => {
+                        browser.status_message = Some("Agent is still running".to_string());
+                    }
+                    Some(session) if session.pr_url.is_some() => {
+                        browser.status_message = Some("PR already created".to_string());
+                    }
+                    session => match crate::agents::current_branch(&wt.path) {
+                        Some(branch) => {
+                            super::pr::open_create_pull_request(
+                                browser,
+                                wt.path.clone(),
+                                branch,
+                                wt.issue_number,
+                                session.map(|s| s.id),
+                                true,
+                            )
+                            .await;
                         }
-                    } else {
-                        browser.status_message = Some("No session for this worktree".to_string());
+                        None => {
+                            browser.status_message =
+                                Some("Worktree is not on a branch".to_string());
+                        }
+                    },
+                }
+            }
+        }
+        KeyCode::Char('v') => {
+            // Run the project's verification commands in the selected worktree
+            if let Some(wt) = worktrees.get(*selected) {
+                let configured = crate::config::load_config()
+                    .is_ok_and(|config| config.verify().contains_key(&wt.project));
+                match &wt.session_id {
+                    _ if !configured => {
+                        browser.status_message = Some(format!(
+                            "No verification commands configured for {}",
+                            wt.project
+                        ));
+                    }
+                    Some(id) => {
+                        crate::agents::verify_session(id);
+                        browser.status_message = Some(format!("Verifying {}...", wt.name));
                     }
+                    None => {
+                        browser.status_message =
+                            Some("No agent session for this worktree".to_string());
+                    }
+                }
+            }
+        }
+        KeyCode::Char('r') => {
+            // Answer the agents waiting for input, starting with the selected worktree's
+            let first = worktrees.get(*selected).and_then(|wt| wt.session_id.clone());
+            super::reply::open_quick_reply(browser, first.as_deref(), true);
+        }
+        KeyCode::Char('u') => {
+            // Relaunch the agent of the selected worktree's ended session
+            match worktrees.get(*selected).and_then(|wt| wt.session_id.clone()) {
+                Some(id) => {
+                    super::list::resume(browser, &id);
+                    browser.view = TuiView::WorktreeList {
+                        worktrees: browser.build_worktree_list(),
+                        selected: *selected,
+                    };
+                }
+                None => {
+                    browser.status_message =
+                        Some("No agent session for this worktree".to_string());
                 }
             }
         }
@@ -89,13 +141,13 @@ pub fn handle_worktree_list_key(
             if let Some(wt) = worktrees.get(selected_idx).cloned() {
                 // Determine tmux session name based on worktree type
                 let tmux_name = if let Some(issue_num) = wt.issue_number {
-                    crate::agents::tmux_session_name(&wt.project, issue_num)
+                    crate::agents::issue_session_name(&wt.project, issue_num)
                 } else {
                     // Standalone worktree: session name matches worktree name
                     wt.name.clone()
                 };
 
-                if crate::agents::is_tmux_session_running(&tmux_name) {
+                if crate::agents::is_session_running(&tmux_name) {
                     if let Some(issue_num) = wt.issue_number {
                         let manager = crate::agents::SessionManager::load();
                         if let Some(session) = manager.get_by_issue(&wt.project, issue_num) {
@@ -103,11 +155,11 @@ pub fn handle_worktree_list_key(
                             let _ = crate::agents::kill_agent(&session.id);
                         } else {
                             // Orphaned: no session but tmux running, kill directly
-                            let _ = crate::agents::kill_tmux_session(&tmux_name);
+                            let _ = crate::agents::kill_session(&tmux_name);
                         }
                     } else {
                         // Standalone worktree without issue: kill tmux directly
-                        let _ = crate::agents::kill_tmux_session(&tmux_name);
+                        let _ = crate::agents::kill_session(&tmux_name);
                     }
                     browser.status_message = Some(format!("Killed tmux session: {}", tmux_name));
                     // Refresh session cache to update issue list indicators
@@ -140,14 +192,14 @@ pub fn handle_worktree_list_key(
             if let Some(wt) = worktrees.get(*selected) {
                 // Determine tmux session name based on worktree type
                 let session_name = if let Some(issue_num) = wt.issue_number {
-                    crate::agents::tmux_session_name(&wt.project, issue_num)
+                    crate::agents::issue_session_name(&wt.project, issue_num)
                 } else {
                     // Standalone worktree: session name matches worktree name
                     wt.name.clone()
                 };
 
-                if crate::agents::is_tmux_session_running(&session_name) {
-                    let all_sessions = crate::agents::list_all_tmux_sessions();
+                if crate::agents::is_session_running(&session_name) {
+                    let all_sessions = crate::agents::list_all_sessions();
                     let current_index = all_sessions
                         .iter()
                         .position(|s| s == &session_name)
@@ -183,12 +235,12 @@ pub fn handle_worktree_list_key(
             if let Some(wt) = worktrees.get(*selected) {
                 // Check if tmux session is already running
                 let session_name = if let Some(issue_num) = wt.issue_number {
-                    crate::agents::tmux_session_name(&wt.project, issue_num)
+                    crate::agents::issue_session_name(&wt.project, issue_num)
                 } else {
                     wt.name.clone()
                 };
 
-                if crate::agents::is_tmux_session_running(&session_name) {
+                if crate::agents::is_session_running(&session_name) {
                     browser.status_message =
                         Some("Tmux session already running. Open it with 't'.".to_string());
                 } else {
@@ -323,16 +375,16 @@ pub fn handle_confirm_prune_key(
             let fail_count = results.len() - success_count;
 
             // Also remove sessions for pruned worktrees
-            let mut manager = crate::agents::SessionManager::load();
-            for wt in orphaned.iter() {
-                if let Some(issue_num) = wt.issue_number {
-                    if let Some(session) = manager.get_by_issue(&wt.project, issue_num) {
-                        let session_id = session.id.clone();
-                        manager.remove(&session_id);
+            let _ = crate::agents::SessionManager::update(|manager| {
+                for wt in orphaned.iter() {
+                    if let Some(issue_num) = wt.issue_number {
+                        if let Some(session) = manager.get_by_issue(&wt.project, issue_num) {
+                            let session_id = session.id.clone();
+                            manager.remove(&session_id);
+                        }
                     }
                 }
-            }
-            let _ = manager.save();
+            });
 
             if fail_count > 0 {
                 browser.status_message = Some(format!(
@@ -370,12 +422,12 @@ pub fn handle_confirm_delete_worktree_key(
                         browser.status_message = Some(format!("Deleted worktree: {}", name));
                         // Remove from session manager if exists
                         if let Some(issue_num) = wt.issue_number {
-                            let mut manager = crate::agents::SessionManager::load();
-                            if let Some(session) = manager.get_by_issue(&wt.project, issue_num) {
-                                let session_id = session.id.clone();
-                                manager.remove(&session_id);
-                                let _ = manager.save();
-                            }
+                            let _ = crate::agents::SessionManager::update(|manager| {
+                                if let Some(session) = manager.get_by_issue(&wt.project, issue_num) {
+                                    let session_id = session.id.clone();
+                                    manager.remove(&session_id);
+                                }
+                            });
                         }
                         // Refresh session cache to update issue list indicators
                         browser.refresh_sessions(&wt.project);
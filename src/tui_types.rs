@@ -4,10 +4,15 @@ use std::path::PathBuf;
 
 use std::collections::HashSet;
 
-use crate::agents::WorktreeInfo;
-use crate::github::{IssueDetail, PullRequestDetail, PullRequestSummary};
+use crate::agents::{AgentSession, AttemptSummary, WorktreeInfo};
+use crate::github::{IssueDetail, PullRequestDetail, PullRequestSummary, ReviewEvent};
 use crate::issues::IssueContent;
 use crate::llm;
+use crate::log_view::LogView;
+use crate::plan::PlanReview;
+use crate::pr_review::ReviewFindings;
+use crate::quick_reply::QuickReplyPopup;
+use crate::replay::Replay;
 
 /// View state for the TUI
 pub enum TuiView {
@@ -24,10 +29,29 @@ pub enum TuiView {
         selected: usize,
     },
     ConfirmDispatch { issue: IssueDetail },
-    AgentLogs {
-        session_id: String,
-        content: String,
-        scroll: u16,
+    /// Transcript of an agent session
+    AgentLogs(LogView),
+    /// Replay of a recorded agent session
+    Replay(Box<Replay>),
+    /// Attempts at one issue side by side, to keep the best one
+    CompareAttempts {
+        issue_number: u64,
+        attempts: Vec<AttemptSummary>,
+        selected: usize,
+        /// Asking to confirm keeping the selected attempt
+        confirm_keep: bool,
+    },
+    /// Implementation plan of an agent dispatched plan first, before it implements it
+    PlanReview(PlanReview),
+    /// Quick replies to agents waiting for input, over the issue or worktree list
+    QuickReply(QuickReplyPopup),
+    /// Dispatches waiting for a free agent slot, next to start first
+    DispatchQueue {
+        queued: Vec<AgentSession>,
+        /// Agents running, out of the configured maximum
+        running: usize,
+        max: usize,
+        selected: usize,
     },
     EmbeddedTmux {
         /// Available tmux sessions for switching
@@ -42,8 +66,9 @@ pub enum TuiView {
         projects: Vec<String>,
         selected: usize,
     },
-    /// Agent selection screen (claude/opencode)
+    /// Agent selection screen (built-in and custom agents)
     AgentSelect {
+        agents: Vec<crate::config::CodingAgentType>,
         selected: usize,
     },
     /// Command palette for custom commands
@@ -98,6 +123,18 @@ pub enum TuiView {
     DispatchInstructions {
         issue: IssueDetail,
         input: String,
+        /// Resolved agent profile first, then the alternatives
+        profiles: Vec<crate::config::ResolvedProfile>,
+        /// Index of the profile used for dispatch
+        profile: usize,
+        /// Profiles of the attempts to dispatch side by side, when more than one
+        attempts: Vec<usize>,
+        /// Ask the agent for an implementation plan to review first
+        plan_first: bool,
+        /// Linked issues and guidance files for the prompt
+        context: crate::agents::PromptContext,
+        /// Final prompt being previewed and edited, used as is for dispatch
+        prompt: Option<String>,
     },
     /// Start agent on worktree with optional instructions
     WorktreeAgentInstructions {
@@ -120,6 +157,47 @@ pub enum TuiView {
         pr: PullRequestDetail,
         input: String,
     },
+    /// Request or remove reviewers on a pull request
+    PrReviewers {
+        pr: PullRequestDetail,
+        input: String,
+        suggestions: Vec<String>,
+        selected: usize,
+    },
+    /// Edit pull request title, body and base branch
+    EditPullRequest {
+        pr: PullRequestDetail,
+        title: String,
+        body: String,
+        base: String,
+        field: PrEditField,
+    },
+    /// Confirm closing a pull request
+    ConfirmClosePr {
+        pr: PullRequestDetail,
+    },
+    /// Confirm reopening a pull request
+    ConfirmReopenPr {
+        pr: PullRequestDetail,
+    },
+    /// Add or remove labels on a pull request
+    PrLabels {
+        pr: PullRequestDetail,
+        labels: Vec<String>,
+        selected: usize,
+    },
+    /// Preview agent review findings before posting them as a GitHub review
+    ReviewFindings {
+        pr: PullRequestDetail,
+        findings: ReviewFindings,
+        /// 0 is the summary, `i + 1` is comment `i`
+        selected: usize,
+        /// Edit buffer for the selected item
+        edit: Option<String>,
+        event: ReviewEvent,
+    },
+    /// Preview and edit a pull request before opening it
+    CreatePullRequest(NewPullRequest),
     /// PR filters popup
     PrFilters {
         status_filter: HashSet<PrStatus>,
@@ -161,6 +239,69 @@ pub struct CommandSuggestion {
     pub labels: Option<Vec<String>>,
 }
 
+/// Field being edited in the pull request edit form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrEditField {
+    Title,
+    Body,
+    Base,
+}
+
+impl PrEditField {
+    /// Next field in Tab order
+    pub fn next(&self) -> Self {
+        match self {
+            PrEditField::Title => PrEditField::Body,
+            PrEditField::Body => PrEditField::Base,
+            PrEditField::Base => PrEditField::Title,
+        }
+    }
+}
+
+/// Field being edited in the pull request creation form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrCreateField {
+    Title,
+    Body,
+    Base,
+    Reviewers,
+}
+
+impl PrCreateField {
+    /// Next field in Tab order
+    pub fn next(&self) -> Self {
+        match self {
+            PrCreateField::Title => PrCreateField::Body,
+            PrCreateField::Body => PrCreateField::Base,
+            PrCreateField::Base => PrCreateField::Reviewers,
+            PrCreateField::Reviewers => PrCreateField::Title,
+        }
+    }
+}
+
+/// Pull request being prepared from a worktree
+#[derive(Debug, Clone)]
+pub struct NewPullRequest {
+    pub worktree_path: PathBuf,
+    /// Head branch, pushed to origin on submit
+    pub branch: String,
+    pub issue_number: Option<u64>,
+    /// Agent session to record the PR URL on
+    pub session_id: Option<String>,
+    pub title: String,
+    pub body: String,
+    pub base: String,
+    pub draft: bool,
+    /// Reviewers to request once the PR is open
+    pub reviewers: Vec<String>,
+    pub reviewer_input: String,
+    pub suggestions: Vec<String>,
+    pub selected: usize,
+    pub field: PrCreateField,
+    /// Return to worktree list instead of issue list
+    pub return_to_worktrees: bool,
+}
+
 /// Pull request status for filtering
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PrStatus {